@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    fs::{create_dir_all, write},
+    io::Read as _,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+/// A service to fetch avatars for email addresses from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarService {
+    Gravatar,
+    Libravatar,
+}
+
+impl AvatarService {
+    fn url(&self, hash: &str) -> String {
+        // d=404 so that addresses without an avatar are not given a generated one
+        match self {
+            AvatarService::Gravatar => format!("https://gravatar.com/avatar/{hash}?s=128&d=404"),
+            AvatarService::Libravatar => {
+                format!("https://seccdn.libravatar.org/avatar/{hash}?s=128&d=404")
+            }
+        }
+    }
+}
+
+/// Avatars for email addresses, fetched in the background and cached on disk.
+///
+/// Lookups never block: the first lookup of an address starts a fetch and returns nothing, later
+/// ones return the cached image once it has been downloaded.
+/// Failed fetches (e.g. when offline) are retried on a later lookup, addresses without an avatar
+/// are remembered so they are not fetched again.
+#[derive(Debug)]
+pub struct Avatars {
+    service: AvatarService,
+    cache_dir: PathBuf,
+    pending: Arc<Mutex<HashSet<String>>>,
+    /// Counts finished fetches, so rendered contacts can tell when they may be out of date.
+    generation: Arc<AtomicU64>,
+}
+
+impl Avatars {
+    pub fn new(service: AvatarService, cache_dir: PathBuf) -> Self {
+        Self {
+            service,
+            cache_dir,
+            pending: Arc::default(),
+            generation: Arc::default(),
+        }
+    }
+
+    /// A number that changes whenever a fetch finishes.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Get the path of the cached avatar for the email, fetching it if it is not cached yet.
+    pub fn get(&self, email: &str) -> Option<PathBuf> {
+        let hash = hash_email(email);
+        let image = self.cache_dir.join(format!("{hash}.png"));
+        let missing = self.cache_dir.join(format!("{hash}.missing"));
+        if image.is_file() {
+            return Some(image);
+        }
+        if missing.is_file() {
+            return None;
+        }
+
+        if !self.pending.lock().unwrap().insert(hash.clone()) {
+            // already being fetched
+            return None;
+        }
+        let url = self.service.url(&hash);
+        let cache_dir = self.cache_dir.clone();
+        let pending = Arc::clone(&self.pending);
+        let generation = Arc::clone(&self.generation);
+        std::thread::spawn(move || {
+            let _ = create_dir_all(&cache_dir);
+            match ureq::get(&url).timeout(Duration::from_secs(10)).call() {
+                Ok(response) => {
+                    let mut bytes = Vec::new();
+                    if response.into_reader().read_to_end(&mut bytes).is_ok() {
+                        let _ = write(&image, bytes);
+                    }
+                }
+                Err(ureq::Error::Status(404, _)) => {
+                    let _ = write(&missing, "");
+                }
+                Err(_) => {
+                    // probably offline, try again next time
+                }
+            }
+            pending.lock().unwrap().remove(&hash);
+            generation.fetch_add(1, Ordering::Relaxed);
+        });
+        None
+    }
+
+    /// Render the avatar for the email as a markdown image, if it is available.
+    pub fn render(&self, email: &str) -> Option<String> {
+        let path = self.get(email)?;
+        let url = lsp_types::Url::from_file_path(path).ok()?;
+        Some(format!("![avatar]({url})"))
+    }
+}
+
+/// Hash an email as avatar services expect: SHA256 of the trimmed, lowercased address.
+fn hash_email(email: &str) -> String {
+    hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash() {
+        // example from the gravatar documentation
+        assert_eq!(
+            hash_email(" MyEmailAddress@example.com "),
+            "84059b07d4be67b806386c0aad8070a23f18836bbaae342275dc0a83414c32ee"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{Datelike as _, NaiveDate};
+
+/// Days ahead that a birthday counts as near, for saying how soon it is.
+pub const NEAR_DAYS: u32 = 30;
+
+/// A contact's birthday, from their `BDAY`, with the year they were born if it is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Birthday {
+    pub year: Option<i32>,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Birthday {
+    /// Parse a date as vcards have it, e.g. `19900131`, `1990-01-31`, or `--0131` without a
+    /// year, ignoring any time after it.
+    pub fn parse(text: &str) -> Option<Self> {
+        let date = text.trim().split('T').next().unwrap_or_default();
+        let (year, rest) = match date.strip_prefix("--") {
+            Some(rest) => (None, rest),
+            None => {
+                let year = date.get(..4)?;
+                if !year.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                (Some(year.parse().ok()?), &date[4..])
+            }
+        };
+        let digits = rest.replace('-', "");
+        if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let birthday = Self {
+            year,
+            month: digits[..2].parse().ok()?,
+            day: digits[2..].parse().ok()?,
+        };
+        // 2000 was a leap year, for birthdays on the 29th of February without one
+        NaiveDate::from_ymd_opt(year.unwrap_or(2000), birthday.month, birthday.day)?;
+        Some(birthday)
+    }
+
+    /// The date of the next birthday, today or after, on the 1st of March in years without the
+    /// 29th of February.
+    pub fn next(&self, today: NaiveDate) -> NaiveDate {
+        [today.year(), today.year() + 1]
+            .into_iter()
+            .filter_map(|year| {
+                NaiveDate::from_ymd_opt(year, self.month, self.day)
+                    .or_else(|| NaiveDate::from_ymd_opt(year, 3, 1))
+            })
+            .find(|date| *date >= today)
+            .unwrap_or(today)
+    }
+
+    /// Days until the next birthday, 0 when it is today.
+    pub fn days_until(&self, today: NaiveDate) -> u32 {
+        (self.next(today) - today).num_days() as u32
+    }
+
+    /// How old they are today, if the year is known.
+    pub fn age(&self, today: NaiveDate) -> Option<u32> {
+        let year = self.year?;
+        let had_birthday = (today.month(), today.day()) >= (self.month, self.day);
+        let age = today.year() - year - i32::from(!had_birthday);
+        u32::try_from(age).ok()
+    }
+
+    /// The birthday with their age, and how soon it is when it is within [`NEAR_DAYS`], e.g.
+    /// `31 January 1990 (34), in 3 days`.
+    pub fn describe(&self, today: NaiveDate) -> String {
+        let mut description = match self.year {
+            Some(year) => {
+                let date = NaiveDate::from_ymd_opt(year, self.month, self.day)
+                    .map_or_else(String::new, |d| d.format("%-d %B %Y").to_string());
+                match self.age(today) {
+                    Some(age) => format!("{date} ({age})"),
+                    None => date,
+                }
+            }
+            None => self.next(today).format("%-d %B").to_string(),
+        };
+        match self.days_until(today) {
+            0 => description.push_str(", today"),
+            1 => description.push_str(", tomorrow"),
+            days if days <= NEAR_DAYS => description.push_str(&format!(", in {days} days")),
+            _ => {}
+        }
+        description
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn birthdays() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 28).unwrap();
+        let birthday = Birthday::parse("19900131").unwrap();
+        assert_eq!(Birthday::parse("1990-01-31T10:00:00Z"), Some(birthday));
+        assert_eq!(birthday.age(today), Some(33));
+        assert_eq!(birthday.days_until(today), 3);
+        assert_eq!(birthday.describe(today), "31 January 1990 (33), in 3 days");
+        assert_eq!(
+            birthday.describe(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()),
+            "31 January 1990 (34), today"
+        );
+        assert_eq!(
+            birthday.describe(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()),
+            "31 January 1990 (34)"
+        );
+
+        let leap = Birthday::parse("--0229").unwrap();
+        assert_eq!(leap.year, None);
+        assert_eq!(
+            leap.next(NaiveDate::from_ymd_opt(2025, 2, 1).unwrap()),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+        assert_eq!(
+            leap.describe(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()),
+            "29 February, in 28 days"
+        );
+        assert_eq!(Birthday::parse("--02-29"), Some(leap));
+
+        assert_eq!(Birthday::parse("31/01/1990"), None);
+        assert_eq!(Birthday::parse("19900231"), None);
+        assert_eq!(Birthday::parse("circa 1800"), None);
+    }
+}
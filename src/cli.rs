@@ -0,0 +1,1047 @@
+//! Commands run on the configured contacts from the command line, outside of the server.
+
+use std::collections::HashSet;
+use std::io::Read as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr as _;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use clap::CommandFactory as _;
+use itertools::Itertools as _;
+use lsp_types::CompletionItemKind;
+use maills::find_addresses;
+use maills::fold;
+use maills::render::RenderOptions;
+use maills::vcard_text::escape;
+use maills::vcard_text::fold_line;
+use maills::Conflict;
+use maills::ContactList;
+use maills::ContactListFormat;
+use maills::ContactSource;
+use maills::Location;
+use maills::Mailbox;
+use maills::Match;
+use maills::MatchOptions;
+use maills::Matcher;
+use maills::MergeInto;
+use maills::Sources;
+use maills::VCards;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::build_source;
+use crate::default_cache_dir;
+use crate::source_path;
+use crate::Args;
+use crate::CheckFormat;
+use crate::Command;
+use crate::ExportFormat;
+use crate::ImportFormat;
+use crate::InitializationOptions;
+use crate::SourceOptions;
+use crate::SourceType;
+
+pub(crate) fn run(args: &Args, command: &Command) -> Result<(), String> {
+    match command {
+        // it reports a missing or invalid configuration rather than failing on one
+        Command::Doctor => return doctor(args),
+        Command::Convert {
+            input,
+            from,
+            to,
+            output,
+        } => {
+            return convert(
+                input,
+                *from,
+                export_format(*to, args.json),
+                output.as_deref(),
+            )
+        }
+        Command::Completions { .. } | Command::Man { .. } if args.json => {
+            return Err("Completions and man pages have no JSON output".to_owned());
+        }
+        Command::Completions { shell } => {
+            let mut command = Args::command();
+            let name = command.get_name().to_owned();
+            clap_complete::generate(*shell, &mut command, name, &mut std::io::stdout());
+            return Ok(());
+        }
+        Command::Man { dir } => {
+            let written = match dir {
+                Some(dir) => clap_mangen::generate_to(Args::command(), dir),
+                None => clap_mangen::Man::new(Args::command()).render(&mut std::io::stdout()),
+            };
+            return written.map_err(|err| format!("Failed to write the man page: {err}"));
+        }
+        _ => {}
+    }
+    let options = args.config()?.ok_or_else(|| {
+        "No configuration file, give one with --config or create ~/.config/maills/config.json"
+            .to_owned()
+    })?;
+    let options = &options;
+    for conflict in options.conflicts() {
+        tracing::warn!("{conflict}");
+    }
+    let json = args.json;
+    match command {
+        Command::Query { query, no_header } => self::query(options, query, !no_header, json),
+        Command::Check { files, format } => {
+            let format = if json { CheckFormat::Json } else { *format };
+            check(options, files, format)
+        }
+        Command::Add { mailbox } => add(options, mailbox, json),
+        Command::Dedupe { fix } => dedupe(options, *fix, json),
+        Command::Export { format, output } => {
+            export(options, export_format(*format, json), output.as_deref())
+        }
+        Command::Stats => stats(options, json),
+        Command::Duplicates => duplicates(options, json),
+        Command::Doctor
+        | Command::Convert { .. }
+        | Command::Completions { .. }
+        | Command::Man { .. } => {
+            unreachable!("handled above")
+        }
+    }
+}
+
+/// The format to write contacts in, JSON if `--json` is given.
+fn export_format(format: ExportFormat, json: bool) -> ExportFormat {
+    if json {
+        ExportFormat::Json
+    } else {
+        format
+    }
+}
+
+/// Print the value as pretty JSON on stdout.
+fn print_json(value: &impl Serialize) -> Result<(), String> {
+    let mut out = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, value)
+        .map_err(std::io::Error::from)
+        .and_then(|()| writeln!(out))
+        .map_err(|err| format!("Failed to write JSON: {err}"))
+}
+
+/// Load all of the configured sources, leaving out any that can't be like the server does.
+///
+/// Relative paths are from the working directory.
+fn load_sources(options: &InitializationOptions) -> Sources {
+    let cache_dir = options.cache_dir().unwrap_or_else(|err| {
+        tracing::warn!("{err}");
+        default_cache_dir()
+    });
+    let mut sources = Sources::default();
+    match options.usage_log() {
+        Ok(Some(usage_log)) => sources.set_usage_log(Arc::new(usage_log)),
+        Ok(None) => {}
+        Err(err) => tracing::warn!("{err}"),
+    }
+    match options.write_source() {
+        Ok(position) => sources.set_write_source(position),
+        Err(err) => tracing::warn!("{err}"),
+    }
+    for (i, source_options) in options.source_options().iter().enumerate() {
+        match build_source(source_options, &RenderOptions::default(), None, &cache_dir) {
+            Ok(load) => sources.add(load(), i, source_options.priority.unwrap_or(0), None),
+            Err(err) => tracing::warn!("{err}"),
+        }
+    }
+    sources
+}
+
+/// Print the matching contacts for mutt's `query_command`: a line for mutt to show, then a line
+/// of `email<TAB>name<TAB>source` for each contact.
+///
+/// Groups give all of their members as the email.
+///
+/// As JSON, the matches are contacts as `export` writes them, the matched email first.
+fn query(
+    options: &InitializationOptions,
+    query: &str,
+    header: bool,
+    json: bool,
+) -> Result<(), String> {
+    let sources = load_sources(options);
+    let matcher = Matcher::new(query, options.match_options());
+    let matches = sources.find_matching(&matcher).collect::<Vec<_>>();
+    if json {
+        let contacts = matches
+            .into_iter()
+            .map(|mut m| {
+                if m.members.is_empty() {
+                    m.addresses.retain(|a| *a != m.mailbox.email);
+                    m.addresses.insert(0, m.mailbox.email.clone());
+                }
+                JsonContact::from(m)
+            })
+            .collect::<Vec<_>>();
+        return print_json(&contacts);
+    }
+    let mut lines = Vec::new();
+    if header {
+        lines.push(match matches.len() {
+            0 => format!("No contacts match {query:?}"),
+            1 => format!("1 contact matches {query:?}"),
+            n => format!("{n} contacts match {query:?}"),
+        });
+    }
+    for m in &matches {
+        let email = if m.members.is_empty() {
+            m.mailbox.email.clone()
+        } else {
+            m.members.iter().join(", ")
+        };
+        let name = m.mailbox.name.as_deref().unwrap_or_default();
+        lines.push([email.as_str(), name, &m.source].map(field).join("\t"));
+    }
+    let mut out = std::io::stdout().lock();
+    for line in lines {
+        writeln!(out, "{line}").map_err(|err| format!("Failed to write contacts: {err}"))?;
+    }
+    Ok(())
+}
+
+/// A field of an output line, without the tabs and newlines separating them.
+fn field(text: &str) -> String {
+    text.replace(['\t', '\r', '\n'], " ")
+}
+
+/// An address that isn't in the contacts.
+#[derive(Serialize)]
+struct Unknown {
+    path: PathBuf,
+    /// Line of the address, from 1.
+    line: usize,
+    /// Character the address starts at in the line, from 1.
+    column: usize,
+    address: String,
+}
+
+/// Print the addresses in the files that aren't in the contacts, failing if there are any.
+fn check(
+    options: &InitializationOptions,
+    files: &[PathBuf],
+    format: CheckFormat,
+) -> Result<(), String> {
+    let sources = load_sources(options);
+    let mut unknown = Vec::new();
+    for path in files {
+        let content = read_input(path)?;
+        for (line, text) in content.lines().enumerate() {
+            for (start, _, address) in find_addresses(text) {
+                if !sources.contains(address) {
+                    unknown.push(Unknown {
+                        path: path.clone(),
+                        line: line + 1,
+                        column: start + 1,
+                        address: address.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+    match format {
+        CheckFormat::Text => {
+            for u in &unknown {
+                println!(
+                    "{}:{}:{}: {} is not in contacts",
+                    u.path.display(),
+                    u.line,
+                    u.column,
+                    u.address
+                );
+            }
+        }
+        CheckFormat::Json => print_json(&unknown)?,
+    }
+    match unknown.len() {
+        0 => Ok(()),
+        1 => Err("1 address is not in contacts".to_owned()),
+        n => Err(format!("{n} addresses are not in contacts")),
+    }
+}
+
+/// The result of `add`.
+#[derive(Serialize)]
+struct Added {
+    mailbox: Mailbox,
+    /// Whether the contact was already there, rather than added.
+    existing: bool,
+    /// Where the contact was added.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+}
+
+/// Add the contact to the `default_write_source`, or else the first source that takes new ones,
+/// unless it is already in one.
+fn add(options: &InitializationOptions, mailbox: &str, json: bool) -> Result<(), String> {
+    let mut mailbox = Mailbox::from_str(mailbox.trim())?;
+    mailbox.email = mailbox
+        .email
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_owned();
+    if find_addresses(&mailbox.email).next().map(|(_, _, a)| a) != Some(&mailbox.email) {
+        return Err(format!("{:?} is not an email address", mailbox.email));
+    }
+    let mut sources = load_sources(options);
+    let matcher = Matcher::new(&mailbox.email, options.match_options());
+    if sources
+        .find_matching(&matcher)
+        .any(|m| m.mailbox.email.eq_ignore_ascii_case(&mailbox.email))
+    {
+        // not a failure, so scripts can add whoever they see
+        if json {
+            return print_json(&Added {
+                mailbox,
+                existing: true,
+                location: None,
+            });
+        }
+        println!("{mailbox} is already in the contacts");
+        return Ok(());
+    }
+    let location = sources
+        .create_contact(mailbox.clone(), None)
+        .ok_or("No source takes new contacts, they are all read only")?;
+    if json {
+        return print_json(&Added {
+            mailbox,
+            existing: false,
+            location: Some(location),
+        });
+    }
+    println!("Added {mailbox} to {}", location.path.display());
+    Ok(())
+}
+
+/// The result of `dedupe`, with the conflicts as the `maills/status` request gives them.
+#[derive(Serialize)]
+struct Deduped {
+    merged: Vec<Merged>,
+    /// Duplicates that are left, as they weren't or couldn't be merged.
+    left: Vec<Conflict>,
+}
+
+#[derive(Serialize)]
+struct Merged {
+    conflict: Conflict,
+    /// Where the contact the duplicates were merged into is.
+    location: Location,
+}
+
+/// Print the duplicate contacts, merging those that can be with `fix`, failing if any are left.
+fn dedupe(options: &InitializationOptions, fix: bool, json: bool) -> Result<(), String> {
+    let into = options.merge_into()?;
+    let mut sources = load_sources(options);
+    let mut merged = Vec::new();
+    let mut left = Vec::<Conflict>::new();
+    loop {
+        // merging moves the other contacts in the files, so their conflicts are found again
+        let conflicts = sources.conflicts();
+        if !fix {
+            left = conflicts;
+            break;
+        }
+        let Some(conflict) = conflicts
+            .into_iter()
+            .find(|c| !left.iter().any(|l| l.message == c.message))
+        else {
+            break;
+        };
+        let into = match &into {
+            Some(into) => into.clone(),
+            None => choose_merge_into(&conflict)?,
+        };
+        match sources.merge(&conflict, &into) {
+            Some(location) => merged.push(Merged { conflict, location }),
+            None => left.push(conflict),
+        }
+    }
+    if json {
+        print_json(&Deduped {
+            merged,
+            left: left.clone(),
+        })?;
+    } else {
+        for m in &merged {
+            println!(
+                "Merged the duplicates into {} ({})",
+                location(&m.location),
+                m.conflict.message
+            );
+        }
+        for conflict in &left {
+            println!("{}", conflict.message);
+            for l in &conflict.locations {
+                println!("  {}", location(l));
+            }
+        }
+    }
+    match (left.len(), fix) {
+        (0, _) => Ok(()),
+        (1, false) => Err("1 contact has duplicates, merge them with --fix".to_owned()),
+        (n, false) => Err(format!(
+            "{n} contacts have duplicates, merge them with --fix"
+        )),
+        (1, true) => Err("1 contact has duplicates that can't be merged".to_owned()),
+        (n, true) => Err(format!("{n} contacts have duplicates that can't be merged")),
+    }
+}
+
+/// Ask which of the conflict's contacts to merge the others into, the first by default.
+fn choose_merge_into(conflict: &Conflict) -> Result<MergeInto, String> {
+    eprintln!("{}", conflict.message);
+    for (i, l) in conflict.locations.iter().enumerate() {
+        eprintln!("  {}) {}", i + 1, location(l));
+    }
+    loop {
+        eprint!("Merge into [1]: ");
+        std::io::stderr().flush().map_err(|err| err.to_string())?;
+        let mut answer = String::new();
+        std::io::stdin()
+            .read_line(&mut answer)
+            .map_err(|err| format!("Failed to read the answer: {err}"))?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(MergeInto::Location(0));
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=conflict.locations.len()).contains(&n) => {
+                return Ok(MergeInto::Location(n - 1))
+            }
+            _ => eprintln!("Expected a number from 1 to {}", conflict.locations.len()),
+        }
+    }
+}
+
+/// A location as `path:line`, with the line from 1.
+fn location(location: &Location) -> String {
+    match location.line {
+        Some(line) => format!("{}:{}", location.path.display(), line + 1),
+        None => location.path.display().to_string(),
+    }
+}
+
+/// Print the addresses that are in more than one source, or that contacts give different names.
+fn duplicates(options: &InitializationOptions, json: bool) -> Result<(), String> {
+    let duplicates = load_sources(options).duplicates();
+    if json {
+        return print_json(&duplicates);
+    }
+    for duplicate in &duplicates {
+        println!("{}", duplicate.message());
+        for l in &duplicate.locations {
+            println!("  {}", location(l));
+        }
+    }
+    Ok(())
+}
+
+/// What `doctor` finds, under a heading for each of the things it checks.
+#[derive(Serialize)]
+struct Findings {
+    sections: Vec<Section>,
+    /// Number of warnings and errors.
+    problems: usize,
+}
+
+#[derive(Serialize)]
+struct Section {
+    heading: String,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize)]
+struct Finding {
+    level: Level,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Level {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Findings {
+    fn section(&mut self, heading: String) {
+        self.sections.push(Section {
+            heading,
+            findings: Vec::new(),
+        });
+    }
+
+    fn push(&mut self, level: Level, message: &str) {
+        if !matches!(level, Level::Ok) {
+            self.problems += 1;
+        }
+        if let Some(section) = self.sections.last_mut() {
+            section.findings.push(Finding {
+                level,
+                message: message.to_owned(),
+            });
+        }
+    }
+
+    fn ok(&mut self, message: &str) {
+        self.push(Level::Ok, message);
+    }
+
+    fn warning(&mut self, message: &str) {
+        self.push(Level::Warning, message);
+    }
+
+    fn error(&mut self, message: &str) {
+        self.push(Level::Error, message);
+    }
+
+    /// Print the findings, failing if there are any problems.
+    fn report(&self, json: bool) -> Result<(), String> {
+        if json {
+            print_json(self)?;
+        } else {
+            for (i, section) in self.sections.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{}", section.heading);
+                for finding in &section.findings {
+                    let level = match finding.level {
+                        Level::Ok => "ok",
+                        Level::Warning => "warning",
+                        Level::Error => "error",
+                    };
+                    println!("  {level}: {}", finding.message);
+                }
+            }
+            if self.problems == 0 {
+                println!("\nNo problems found");
+            }
+        }
+        match self.problems {
+            0 => Ok(()),
+            1 => Err("Found 1 problem".to_owned()),
+            n => Err(format!("Found {n} problems")),
+        }
+    }
+}
+
+/// Check the configuration file and load each source, printing what is wrong with them.
+fn doctor(args: &Args) -> Result<(), String> {
+    let mut findings = Findings {
+        sections: Vec::new(),
+        problems: 0,
+    };
+    findings.section(match args.config_path()? {
+        Some(path) => format!("Configuration file {}", path.display()),
+        None => "Configuration file".to_owned(),
+    });
+    let options = match args.config() {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            findings.warning(
+                "There is none, so the commands can't run and the server only uses the \
+                 editor's initialization options",
+            );
+            return findings.report(args.json);
+        }
+        Err(err) => {
+            findings.error(&err);
+            return findings.report(args.json);
+        }
+    };
+    let sources = options.source_options();
+    if sources.is_empty() {
+        findings.error("No sources are configured, add them to `sources`");
+    } else {
+        findings.ok(&match sources.len() {
+            1 => "1 source".to_owned(),
+            n => format!("{n} sources"),
+        });
+    }
+    for conflict in options.conflicts() {
+        findings.warning(&conflict);
+    }
+    let cache_dir = options.cache_dir().unwrap_or_else(|err| {
+        findings.error(&err);
+        default_cache_dir()
+    });
+
+    for (i, source_options) in sources.iter().enumerate() {
+        let mut heading = format!(
+            "Source {} ({}",
+            i + 1,
+            type_name(source_options.source_type)
+        );
+        if let Some(label) = &source_options.label {
+            heading.push_str(&format!(" {label:?}"));
+        }
+        if let Ok(path) = source_path(source_options) {
+            heading.push_str(&format!(" at {}", path.display()));
+        }
+        heading.push(')');
+        findings.section(heading);
+        if source_options.per_folder() {
+            findings.ok(
+                "Loaded for each workspace folder by the server, checked from the working \
+                 directory here",
+            );
+        }
+        let (source, elapsed) = match load_timed(source_options, &cache_dir) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                findings.error(&err);
+                continue;
+            }
+        };
+        let problems = source.problems();
+        match source.contact_count() {
+            // the problems say why
+            0 if !problems.is_empty() => {}
+            0 => findings.warning("No contacts were loaded"),
+            1 => findings.ok(&format!("1 contact loaded in {elapsed:.1?}")),
+            n => findings.ok(&format!("{n} contacts loaded in {elapsed:.1?}")),
+        }
+        for problem in problems {
+            findings.error(&problem);
+        }
+        match source.conflicts().len() {
+            0 => {}
+            1 => findings.warning("1 contact has duplicates, see `maills dedupe`"),
+            n => findings.warning(&format!(
+                "{n} contacts have duplicates, see `maills dedupe`"
+            )),
+        }
+    }
+
+    findings.report(args.json)
+}
+
+/// The name of the source type in the options.
+fn type_name(source_type: SourceType) -> &'static str {
+    match source_type {
+        SourceType::Vcard => "vcard",
+        SourceType::ContactList => "contact_list",
+    }
+}
+
+/// Load a single source, timing how long it takes.
+fn load_timed(
+    options: &SourceOptions,
+    cache_dir: &Path,
+) -> Result<(Box<dyn ContactSource + Send + Sync>, Duration), String> {
+    let load = build_source(options, &RenderOptions::default(), None, cache_dir)?;
+    let start = Instant::now();
+    let source = load();
+    Ok((source, start.elapsed()))
+}
+
+/// Number of the largest files of each source to list.
+const LARGEST_FILES: usize = 5;
+
+/// Number of the most used addresses to list.
+const MOST_USED: usize = 10;
+
+#[derive(Serialize)]
+struct Stats {
+    sources: Vec<SourceStats>,
+    /// Contacts over all of the sources.
+    contacts: usize,
+    /// Distinct emails over all of the sources.
+    emails: usize,
+    /// The addresses used most, as tracked by the server.
+    most_used: Vec<UsedAddress>,
+}
+
+#[derive(Serialize)]
+struct UsedAddress {
+    email: String,
+    /// Times completed or hovered.
+    uses: u64,
+    /// Times in the recipients of a saved message.
+    sent: u64,
+    /// Date of the last use, as `YYYY-MM-DD`.
+    last_used: Option<String>,
+    /// Date of the last send, as `YYYY-MM-DD`.
+    last_sent: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SourceStats {
+    label: String,
+    #[serde(rename = "type")]
+    source_type: &'static str,
+    path: Option<PathBuf>,
+    contacts: usize,
+    /// Distinct emails of the contacts.
+    emails: usize,
+    /// Contacts that have duplicates, as `maills dedupe` reports.
+    duplicates: usize,
+    files: usize,
+    /// The largest files, in bytes.
+    largest_files: Vec<FileSize>,
+    /// Time taken to load and index the source, in milliseconds.
+    load_time_ms: f64,
+    /// Why the source couldn't be loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FileSize {
+    path: PathBuf,
+    size: u64,
+}
+
+/// Print how many contacts and emails each source has, along with its duplicates, largest files
+/// and how long it took to load, then the addresses used most.
+fn stats(options: &InitializationOptions, json: bool) -> Result<(), String> {
+    let cache_dir = options.cache_dir().unwrap_or_else(|err| {
+        tracing::warn!("{err}");
+        default_cache_dir()
+    });
+    let matcher = Matcher::new("", MatchOptions::default());
+    let mut all_emails = HashSet::new();
+    let mut stats = Stats {
+        sources: Vec::new(),
+        contacts: 0,
+        emails: 0,
+        most_used: Vec::new(),
+    };
+    for (i, source_options) in options.source_options().iter().enumerate() {
+        let mut source_stats = SourceStats {
+            label: source_options
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("Source {}", i + 1)),
+            source_type: type_name(source_options.source_type),
+            path: source_path(source_options).ok(),
+            contacts: 0,
+            emails: 0,
+            duplicates: 0,
+            files: 0,
+            largest_files: Vec::new(),
+            load_time_ms: 0.0,
+            error: None,
+        };
+        match load_timed(source_options, &cache_dir) {
+            Ok((source, elapsed)) => {
+                let emails = source
+                    .find_matching(&matcher)
+                    .flat_map(|m| {
+                        if m.addresses.is_empty() {
+                            vec![m.mailbox.email]
+                        } else {
+                            m.addresses
+                        }
+                    })
+                    .map(|e| fold(&e, false))
+                    .collect::<HashSet<_>>();
+                let files = source
+                    .files()
+                    .into_iter()
+                    .map(|path| FileSize {
+                        size: std::fs::metadata(&path).map_or(0, |m| m.len()),
+                        path,
+                    })
+                    .sorted_by_key(|f| std::cmp::Reverse(f.size))
+                    .collect::<Vec<_>>();
+                source_stats.contacts = source.contact_count();
+                source_stats.emails = emails.len();
+                source_stats.duplicates = source.conflicts().len();
+                source_stats.files = files.len();
+                source_stats.largest_files = files.into_iter().take(LARGEST_FILES).collect();
+                source_stats.load_time_ms = elapsed.as_secs_f64() * 1000.0;
+                stats.contacts += source_stats.contacts;
+                all_emails.extend(emails);
+            }
+            Err(err) => source_stats.error = Some(err),
+        }
+        stats.sources.push(source_stats);
+    }
+    stats.emails = all_emails.len();
+    match options.usage_log() {
+        Ok(Some(usage_log)) => {
+            let date = |d: Option<chrono::NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+            stats.most_used = usage_log
+                .most_used(MOST_USED, chrono::Local::now().date_naive())
+                .into_iter()
+                .map(|(email, entry)| UsedAddress {
+                    email,
+                    uses: entry.uses,
+                    sent: entry.sent,
+                    last_used: date(entry.last_used),
+                    last_sent: date(entry.last_sent),
+                })
+                .collect();
+        }
+        Ok(None) => {}
+        Err(err) => tracing::warn!("{err}"),
+    }
+
+    if json {
+        return print_json(&stats);
+    }
+    for s in &stats.sources {
+        print!("{} ({}", s.label, s.source_type);
+        if let Some(path) = &s.path {
+            print!(" at {}", path.display());
+        }
+        println!(")");
+        if let Some(err) = &s.error {
+            println!("  not loaded: {err}");
+            continue;
+        }
+        println!("  contacts: {}", s.contacts);
+        println!("  emails: {}", s.emails);
+        println!("  duplicates: {}", s.duplicates);
+        println!("  files: {}", s.files);
+        println!("  load time: {:.1}ms", s.load_time_ms);
+        if s.largest_files.len() > 1 {
+            println!("  largest files:");
+            for f in &s.largest_files {
+                println!("    {} bytes {}", f.size, f.path.display());
+            }
+        }
+    }
+    println!("Total");
+    println!("  contacts: {}", stats.contacts);
+    println!("  emails: {}", stats.emails);
+    if !stats.most_used.is_empty() {
+        println!("Most used");
+        for u in &stats.most_used {
+            print!("  {}: {} uses, {} sent", u.email, u.uses, u.sent);
+            match u.last_sent.as_ref().max(u.last_used.as_ref()) {
+                Some(last) => println!(", last {last}"),
+                None => println!(),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A contact as exported to JSON, and read back by `convert`.
+#[derive(Serialize, Deserialize)]
+struct JsonContact {
+    name: Option<String>,
+    /// Most preferred first.
+    emails: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    source: String,
+    /// Members of a group, which has no emails of its own.
+    #[serde(default)]
+    members: Vec<Mailbox>,
+}
+
+impl From<Match> for JsonContact {
+    fn from(m: Match) -> Self {
+        JsonContact {
+            name: m.mailbox.name,
+            emails: if m.members.is_empty() {
+                m.addresses
+            } else {
+                Vec::new()
+            },
+            aliases: m.aliases,
+            source: m.source,
+            members: m.members,
+        }
+    }
+}
+
+/// Write every contact once, to the file or stdout.
+fn export(
+    options: &InitializationOptions,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    write_contacts(&load_sources(options).distinct_contacts(), format, output)
+}
+
+/// Convert the contacts in the file to another format, to the file or stdout.
+///
+/// The input is read with the sources' own parsers, with duplicate addresses left out as for
+/// `export`.
+fn convert(
+    input: &Path,
+    from: ImportFormat,
+    to: ExportFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    if !input.is_file() {
+        return Err(format!("{} isn't a file", input.display()));
+    }
+    let format = match from {
+        ImportFormat::Json => {
+            let content = read_input(input)?;
+            let contacts = serde_json::from_str::<Vec<JsonContact>>(&content)
+                .map_err(|err| format!("Invalid contacts in {}: {err}", input.display()))?;
+            let contacts = contacts
+                .into_iter()
+                .filter(|c| !c.emails.is_empty() || !c.members.is_empty())
+                .map(|c| Match {
+                    source: c.source,
+                    kind: CompletionItemKind::TEXT,
+                    mailbox: Mailbox {
+                        name: c.name,
+                        email: c.emails.first().cloned().unwrap_or_default(),
+                    },
+                    aliases: c.aliases,
+                    addresses: c.emails,
+                    members: c.members,
+                })
+                .collect::<Vec<_>>();
+            return write_contacts(&contacts, to, output);
+        }
+        ImportFormat::Vcf => None,
+        ImportFormat::List => Some(ContactListFormat::Auto),
+        ImportFormat::Plain => Some(ContactListFormat::Plain),
+        ImportFormat::MuttAlias => Some(ContactListFormat::MuttAlias),
+        ImportFormat::Notmuch => Some(ContactListFormat::Notmuch),
+        ImportFormat::Csv => Some(ContactListFormat::Csv),
+        ImportFormat::MuttAb => Some(ContactListFormat::MuttAb),
+    };
+    let label = input.display().to_string();
+    let source: Box<dyn ContactSource + Send + Sync> = match format {
+        Some(format) => Box::new(
+            ContactList::new(input.to_owned(), true)
+                .with_format(format)
+                .with_label(label),
+        ),
+        None => Box::new(VCards::new(input.to_owned()).with_label(label)),
+    };
+    let mut sources = Sources::default();
+    sources.add(source, 0, 0, None);
+    write_contacts(&sources.distinct_contacts(), to, output)
+}
+
+/// Write the contacts in the format, to the file or stdout.
+///
+/// Only the JSON and vcard formats have groups.
+fn write_contacts(
+    contacts: &[Match],
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<(), String> {
+    let people = || {
+        contacts
+            .iter()
+            .filter(|c| c.members.is_empty())
+            .flat_map(|c| c.addresses.iter().map(move |a| (c, a)))
+    };
+    let mut content = String::new();
+    match format {
+        ExportFormat::Csv => {
+            content.push_str("name,email,source\n");
+            for (c, address) in people() {
+                let name = c.mailbox.name.as_deref().unwrap_or_default();
+                content.push_str(&[name, address, &c.source].map(csv_field).join(","));
+                content.push('\n');
+            }
+        }
+        ExportFormat::Json => {
+            let exported = contacts
+                .iter()
+                .cloned()
+                .map(JsonContact::from)
+                .collect::<Vec<_>>();
+            content = serde_json::to_string_pretty(&exported)
+                .map_err(|err| format!("Failed to serialize contacts: {err}"))?;
+            content.push('\n');
+        }
+        ExportFormat::List => {
+            for (c, address) in people() {
+                let mailbox = Mailbox {
+                    name: c.mailbox.name.clone(),
+                    email: address.clone(),
+                };
+                content.push_str(&field(&mailbox.to_string()));
+                content.push('\n');
+            }
+        }
+        ExportFormat::Vcf => {
+            for c in contacts {
+                content.push_str(&vcard(c));
+            }
+        }
+    }
+    match output {
+        Some(path) => std::fs::write(path, content)
+            .map_err(|err| format!("Failed to write {}: {err}", path.display())),
+        None => std::io::stdout()
+            .lock()
+            .write_all(content.as_bytes())
+            .map_err(|err| format!("Failed to write contacts: {err}")),
+    }
+}
+
+/// A field of a CSV row, quoted if it needs to be.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// A vcard 4.0 for the contact, with a `MEMBER` for each member of a group.
+fn vcard(contact: &Match) -> String {
+    let mut lines = vec![
+        "BEGIN:VCARD".to_owned(),
+        "VERSION:4.0".to_owned(),
+        format!(
+            "FN:{}",
+            escape(contact.mailbox.name.as_deref().unwrap_or_default())
+        ),
+    ];
+    if contact.members.is_empty() {
+        lines.extend(
+            contact
+                .addresses
+                .iter()
+                .map(|a| format!("EMAIL:{}", escape(a))),
+        );
+    } else {
+        lines.push("KIND:group".to_owned());
+        lines.extend(
+            contact
+                .members
+                .iter()
+                .map(|m| format!("MEMBER:mailto:{}", m.email)),
+        );
+    }
+    if !contact.aliases.is_empty() {
+        let aliases = contact.aliases.iter().map(|a| escape(a)).join(",");
+        lines.push(format!("NICKNAME:{aliases}"));
+    }
+    lines.push("END:VCARD".to_owned());
+    lines
+        .iter()
+        .flat_map(|l| fold_line(l))
+        .map(|l| l + "\r\n")
+        .collect()
+}
+
+/// Read a file, or stdin for `-`.
+fn read_input(path: &Path) -> Result<String, String> {
+    if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|err| format!("Failed to read stdin: {err}"))?;
+        return Ok(content);
+    }
+    std::fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {err}", path.display()))
+}
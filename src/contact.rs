@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use lsp_types::CompletionItemKind;
+
+use crate::{fold, Birthday, Mailbox, Match, Matcher, Phone};
+
+/// A person, organisation or group in a source, with all of their names and addresses together,
+/// rather than a [`Match`] for each address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contact {
+    /// Identifies the contact as `path#n`, where `n` counts the contacts in the file from 1.
+    pub id: String,
+    /// Names of the contact, the one to address them by first, then others such as nicknames.
+    pub names: Vec<String>,
+    /// Mailboxes of the contact, most preferred first.
+    pub mailboxes: Vec<Mailbox>,
+    /// Mailboxes of the members, if the contact is a group.
+    pub members: Vec<Mailbox>,
+    pub meta: ContactMeta,
+}
+
+/// What else a source knows of a contact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContactMeta {
+    /// Label of the source with the contact.
+    pub source: String,
+    /// Kind to show the contact's completion items as.
+    pub kind: CompletionItemKind,
+    /// File the contact is in.
+    pub path: Option<PathBuf>,
+    pub birthday: Option<Birthday>,
+    pub phones: Vec<Phone>,
+}
+
+impl Contact {
+    /// The name to address the contact by, if they have one.
+    pub fn name(&self) -> Option<&str> {
+        self.names.first().map(String::as_str)
+    }
+
+    /// The addresses of the contact, most preferred first.
+    pub fn emails(&self) -> impl Iterator<Item = &str> {
+        self.mailboxes.iter().map(|m| m.email.as_str())
+    }
+
+    /// Whether the contact has the address, ignoring case and diacritics.
+    pub fn has_email(&self, email: &str) -> bool {
+        let email = fold(email, true);
+        self.emails().any(|e| fold(e, true) == email)
+    }
+
+    /// Whether the contact is a group of others.
+    pub fn is_group(&self) -> bool {
+        !self.members.is_empty()
+    }
+
+    /// The id for the `n`th contact in the file, counting from 0.
+    pub fn id_in(path: &Path, n: usize) -> String {
+        format!("{}#{}", path.display(), n + 1)
+    }
+
+    /// The match for the contact's preferred mailbox, named by the name to address them by, or
+    /// for the group.
+    ///
+    /// Returns `None` for contacts without an address.
+    pub fn to_match(&self) -> Option<Match> {
+        let mailbox = if self.is_group() {
+            Mailbox {
+                name: self.name().map(str::to_owned),
+                email: String::new(),
+            }
+        } else {
+            Mailbox {
+                name: self.name().map(str::to_owned),
+                email: self.mailboxes.first()?.email.clone(),
+            }
+        };
+        Some(Match {
+            source: self.meta.source.clone(),
+            kind: self.meta.kind,
+            mailbox,
+            aliases: self.names.iter().skip(1).cloned().collect(),
+            addresses: self.emails().map(str::to_owned).collect(),
+            members: self.members.clone(),
+        })
+    }
+
+    /// How well the contact matches the query, lower is better.
+    pub fn score(&self, matcher: &Matcher) -> u32 {
+        let names = self.names.iter().map(String::as_str).collect::<Vec<_>>();
+        matcher.score(&names, &self.emails().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(members: Vec<Mailbox>) -> Contact {
+        let mailbox = |email: &str| Mailbox {
+            name: Some("Jane Doe".to_owned()),
+            email: email.to_owned(),
+        };
+        Contact {
+            id: Contact::id_in(Path::new("/contacts/jane.vcf"), 0),
+            names: vec!["Jane Doe".to_owned(), "Janey".to_owned()],
+            mailboxes: if members.is_empty() {
+                vec![mailbox("jane@work.com"), mailbox("jane@home.com")]
+            } else {
+                Vec::new()
+            },
+            members,
+            meta: ContactMeta {
+                source: "work".to_owned(),
+                kind: CompletionItemKind::TEXT,
+                path: None,
+                birthday: None,
+                phones: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn to_match() {
+        let contact = contact(Vec::new());
+        assert_eq!(contact.id, "/contacts/jane.vcf#1");
+        assert!(contact.has_email("JANE@home.com"));
+        assert!(!contact.is_group());
+        assert_eq!(
+            contact.to_match(),
+            Some(Match {
+                source: "work".to_owned(),
+                kind: CompletionItemKind::TEXT,
+                mailbox: Mailbox {
+                    name: Some("Jane Doe".to_owned()),
+                    email: "jane@work.com".to_owned(),
+                },
+                aliases: vec!["Janey".to_owned()],
+                addresses: vec!["jane@work.com".to_owned(), "jane@home.com".to_owned()],
+                members: Vec::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn group_to_match() {
+        let members = vec![Mailbox {
+            name: None,
+            email: "john@example.com".to_owned(),
+        }];
+        let group = contact(members.clone());
+        assert!(group.is_group());
+        let m = group.to_match().unwrap();
+        assert_eq!(m.mailbox.email, "");
+        assert_eq!(m.members, members);
+        assert!(m.addresses.is_empty());
+
+        let mut empty = contact(Vec::new());
+        empty.mailboxes.clear();
+        assert_eq!(empty.to_match(), None);
+    }
+}
@@ -1,27 +1,385 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::read_to_string,
-    path::PathBuf,
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use crate::{ContactSource, Location, Mailbox};
+use itertools::Itertools as _;
+use lsp_types::CompletionItemKind;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    fold, Avatars, Conflict, Contact, ContactMeta, ContactSource, DiskIndex, ExcludePatterns,
+    FileStamp, Folded, IndexRecord, Location, Mailbox, Match, MatchOptions, Matcher, MergeInto,
+    SearchIndex, StructuredName, Usage,
+};
+
+/// The format of a contact list file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactListFormat {
+    /// Detect the format of each line.
+    #[default]
+    Auto,
+    /// `Name email` lines, as from `mu cfind --format=plain`.
+    Plain,
+    /// Mutt aliases: `alias nick Name <email>`.
+    MuttAlias,
+    /// `Name <email>` records from `notmuch address`, with or without `--output=count`, separated
+    /// by newlines or NULs (`--format=text0`).
+    Notmuch,
+    /// `mu cfind --format=csv`: `"Name","email"` with an optional header row.
+    Csv,
+    /// `mu cfind --format=mutt-ab`: `email<TAB>Name` after a header line.
+    MuttAb,
+}
+
+/// A contact parsed from a line of the file.
+#[derive(Debug, PartialEq, Eq)]
+struct ParsedLine {
+    mailbox: Mailbox,
+    aliases: Vec<String>,
+    /// Byte offset of the email in the line.
+    email_start: usize,
+    /// Number of messages with the address, from `notmuch address --output=count`.
+    messages: Option<u64>,
+}
+
+impl ContactListFormat {
+    fn parse_line(&self, line: &str) -> Option<ParsedLine> {
+        let line = strip_comment(line);
+        if line.trim().is_empty() {
+            return None;
+        }
+        match self {
+            ContactListFormat::Auto if is_mutt_alias(line) => parse_mutt_alias(line),
+            ContactListFormat::Auto if line.trim_end().ends_with('>') => parse_notmuch(line),
+            ContactListFormat::Auto | ContactListFormat::Plain => parse_plain(line),
+            ContactListFormat::MuttAlias => parse_mutt_alias(line),
+            ContactListFormat::Notmuch => parse_notmuch(line),
+            ContactListFormat::Csv => parse_csv(line),
+            ContactListFormat::MuttAb => parse_mutt_ab(line),
+        }
+    }
+
+    /// Format a new contact as a line, matching the existing content when detecting the format.
+    fn format_line(&self, mailbox: &Mailbox, content: &str) -> String {
+        let format = match self {
+            ContactListFormat::Auto if content.lines().any(is_mutt_alias) => {
+                ContactListFormat::MuttAlias
+            }
+            ContactListFormat::Auto => ContactListFormat::Plain,
+            format => *format,
+        };
+        match (&mailbox.name, format) {
+            (Some(name), ContactListFormat::MuttAlias) => {
+                format!("alias {} {name} <{}>", alias_key(mailbox), mailbox.email)
+            }
+            (None, ContactListFormat::MuttAlias) => {
+                format!("alias {} {}", alias_key(mailbox), mailbox.email)
+            }
+            (Some(name), ContactListFormat::Notmuch) => format!("{name} <{}>", mailbox.email),
+            (name, ContactListFormat::Csv) => format!(
+                "\"{}\",\"{}\"",
+                name.as_deref().unwrap_or_default().replace('"', "\"\""),
+                mailbox.email
+            ),
+            (name, ContactListFormat::MuttAb) => {
+                format!("{}\t{}", mailbox.email, name.as_deref().unwrap_or_default())
+            }
+            (Some(name), _) => format!("{name} {}", mailbox.email),
+            (None, _) => mailbox.email.clone(),
+        }
+    }
+}
+
+/// Remove a `#` comment, either a whole line or after whitespace at the end of one, ignoring
+/// `#`s in quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes && previous.is_whitespace() => return &line[..i],
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+fn is_mutt_alias(line: &str) -> bool {
+    line.trim_start().starts_with("alias ")
+}
+
+/// `Name email`, with the email being the last word.
+fn parse_plain(line: &str) -> Option<ParsedLine> {
+    let trimmed = line.trim_end();
+    let (name, email) = match trimmed.trim_start().rsplit_once(' ') {
+        Some((name, email)) => (Some(name.trim().to_owned()), email),
+        None => (None, trimmed.trim_start()),
+    };
+    Some(ParsedLine {
+        mailbox: Mailbox {
+            name: name.filter(|n| !n.is_empty()),
+            email: email.to_owned(),
+        },
+        aliases: Vec::new(),
+        email_start: trimmed.len() - email.len(),
+        messages: None,
+    })
+}
+
+/// `Name <email>` or just `email`, optionally after a count and a tab.
+fn parse_notmuch(line: &str) -> Option<ParsedLine> {
+    let address = line.rsplit('\t').next().unwrap_or_default().trim();
+    let (name, email) = match address.rsplit_once('<') {
+        Some((name, email)) => {
+            let name = name.trim().trim_matches('"').trim();
+            (Some(name.to_owned()), email.trim_end_matches('>').trim())
+        }
+        None => (None, address),
+    };
+    if email.is_empty() {
+        return None;
+    }
+    let email_start = line.rfind(email)?;
+    let messages = line
+        .split_once('\t')
+        .and_then(|(count, _)| count.trim().parse().ok());
+    Some(ParsedLine {
+        mailbox: Mailbox {
+            name: name.filter(|n| !n.is_empty()),
+            email: email.to_owned(),
+        },
+        aliases: Vec::new(),
+        email_start,
+        messages,
+    })
+}
+
+/// A row of comma separated fields, which may be quoted, with the email being the field with an
+/// `@`.
+/// Rows without an email, such as a header, are skipped.
+fn parse_csv(line: &str) -> Option<ParsedLine> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.trim_end().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    let email = fields.iter().find(|f| f.contains('@'))?.trim().to_owned();
+    let name = fields
+        .iter()
+        .map(|f| f.trim())
+        .find(|f| !f.is_empty() && !f.contains('@'))
+        .map(|f| f.to_owned());
+    Some(ParsedLine {
+        email_start: line.find(&email)?,
+        mailbox: Mailbox { name, email },
+        aliases: Vec::new(),
+        messages: None,
+    })
+}
+
+/// `email<TAB>Name`, as mutt's query command expects, skipping the header line.
+fn parse_mutt_ab(line: &str) -> Option<ParsedLine> {
+    let mut fields = line.split('\t');
+    let email = fields.next()?.trim();
+    if !email.contains('@') {
+        return None;
+    }
+    let name = fields.next().map(|n| n.trim()).filter(|n| !n.is_empty());
+    Some(ParsedLine {
+        email_start: line.find(email)?,
+        mailbox: Mailbox {
+            name: name.map(|n| n.to_owned()),
+            email: email.to_owned(),
+        },
+        aliases: Vec::new(),
+        messages: None,
+    })
+}
+
+/// `alias [-group name]... key address`, where the address is `Name <email>`, `email (Name)` or
+/// just `email`.
+///
+/// Only the first address of an alias for a list of addresses is used.
+fn parse_mutt_alias(line: &str) -> Option<ParsedLine> {
+    let mut rest = line.trim_start().strip_prefix("alias ")?;
+    let mut word = || {
+        let trimmed = rest.trim_start();
+        let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let (word, after) = trimmed.split_at(end);
+        rest = after;
+        word
+    };
+    let mut key = word();
+    while key == "-group" {
+        word();
+        key = word();
+    }
+    let key = key.to_owned();
+    let address = rest.split('#').next().unwrap_or_default();
+    // the first of any comma separated addresses, ignoring commas in quoted names
+    let mut in_quotes = false;
+    let end = address
+        .char_indices()
+        .find(|&(_, c)| {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            c == ',' && !in_quotes
+        })
+        .map_or(address.len(), |(i, _)| i);
+    let address = address[..end].trim();
+    let (name, email) = if let Some((name, email)) = address.rsplit_once('<') {
+        let name = name.trim().trim_matches('"').trim();
+        (Some(name.to_owned()), email.trim_end_matches('>').trim())
+    } else if let Some((email, name)) = address.split_once('(') {
+        (
+            Some(name.trim_end_matches(')').trim().to_owned()),
+            email.trim(),
+        )
+    } else {
+        (None, address)
+    };
+    if email.is_empty() {
+        return None;
+    }
+    let email_start = line.rfind(email)?;
+    Some(ParsedLine {
+        mailbox: Mailbox {
+            name: name.filter(|n| !n.is_empty()),
+            email: email.to_owned(),
+        },
+        aliases: vec![key],
+        email_start,
+        messages: None,
+    })
+}
+
+/// A key for a new mutt alias, from the name or the email's local part.
+fn alias_key(mailbox: &Mailbox) -> String {
+    let source = match &mailbox.name {
+        Some(name) => name.to_owned(),
+        None => mailbox
+            .email
+            .split('@')
+            .next()
+            .unwrap_or_default()
+            .to_owned(),
+    };
+    source
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// The records of the content with the line and character column each starts at.
+///
+/// Records are usually lines, but can be separated by NULs too.
+fn records(content: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    let mut line_number = 0;
+    let mut column = 0;
+    content.split_inclusive(['\n', '\0']).map(move |record| {
+        let position = (line_number, column);
+        if record.ends_with('\n') {
+            line_number += 1;
+            column = 0;
+        } else {
+            column += record.chars().count();
+        }
+        (
+            position.0,
+            position.1,
+            record.trim_end_matches(['\n', '\r', '\0']),
+        )
+    })
+}
 
 struct ContactListEntry {
     mailbox: Mailbox,
+    /// Other names for the contact, e.g. the mutt alias key.
+    aliases: Vec<String>,
     line: u32,
+    /// Character columns of the email in the line.
+    columns: (u32, u32),
+    folded_names: Vec<Folded>,
+    folded_email: Folded,
+    messages: Option<u64>,
+}
+
+impl ContactListEntry {
+    fn names(&self) -> Vec<&str> {
+        self.mailbox
+            .name
+            .iter()
+            .chain(&self.aliases)
+            .map(|n| n.as_str())
+            .collect()
+    }
+
+    /// The names and email to search for the entry.
+    fn search_fields(&self) -> Vec<&str> {
+        let mut fields = self.names();
+        fields.push(&self.mailbox.email);
+        fields
+    }
 }
 
 pub struct ContactList {
     path: PathBuf,
+    /// Name of the source, shown alongside its completion items.
+    label: String,
     diagnostics: bool,
+    completion_kind: CompletionItemKind,
+    avatars: Option<Arc<Avatars>>,
     contacts: Vec<ContactListEntry>,
     contact_lines: HashMap<Mailbox, usize>,
     emails_lower: HashSet<String>,
+    search_index: SearchIndex,
+    /// The state of the file when it was loaded.
+    stamp: Option<FileStamp>,
+    /// Whether new contacts are appended to the file.
+    writable: bool,
+    /// Whether the source only gets contacts harvested from messages sent.
+    harvest: bool,
+    format: ContactListFormat,
+    exclude: ExcludePatterns,
+    /// Directory to keep a [`DiskIndex`] of the file in, rather than holding it in memory.
+    index_dir: Option<PathBuf>,
+    disk_index: Option<DiskIndex>,
+    /// Contacts on several lines, found when the file is loaded into memory.
+    conflicts: Vec<Conflict>,
+    /// Lines that aren't contacts in the format, found when the file is loaded into memory.
+    unparsed: Vec<u32>,
 }
 
 impl ContactSource for ContactList {
     fn render(&self, mailbox: &Mailbox) -> String {
+        if !self.has_email(&mailbox.email) {
+            return String::new();
+        }
         let mut lines = Vec::new();
+        if let Some(avatar) = self.avatars.as_ref().and_then(|a| a.render(&mailbox.email)) {
+            lines.push(avatar);
+            lines.push(String::new());
+        }
         if let Some(name) = &mailbox.name {
             lines.push(format!("# {}", name));
             lines.push(String::new());
@@ -31,87 +389,832 @@ impl ContactSource for ContactList {
         lines.join("\n")
     }
 
-    fn find_matching(&self, word: String) -> Box<dyn Iterator<Item = (String, Mailbox)> + '_> {
-        Box::new(
-            self.contacts
-                .iter()
-                .map(|e| &e.mailbox)
-                .filter(move |m| {
-                    // TODO: make this contains check cheaper, rather than searching every entry
-                    // Likely a custom trie
-                    let matched_name = m
-                        .name
-                        .as_ref()
-                        .map_or(false, |n| n.to_lowercase().contains(&word));
-                    let matched_email = m.email.to_lowercase().contains(&word);
-                    matched_name || matched_email
-                })
-                .cloned()
-                .map(|m| ("ContactList".to_owned(), m)),
-        )
+    fn find_matching<'a>(&'a self, matcher: &'a Matcher) -> Box<dyn Iterator<Item = Match> + 'a> {
+        self.matching_entries(matcher, |_, e| self.to_match(e))
+    }
+
+    fn find_contacts(&self, matcher: &Matcher) -> Vec<Contact> {
+        self.matching_entries(matcher, |i, e| self.to_contact(i, e))
+            .collect()
     }
 
     fn contains(&self, email: &str) -> bool {
-        if self.diagnostics {
-            self.emails_lower.contains(&email.to_lowercase())
-        } else {
+        if !self.diagnostics {
             // contains nothing with respec to diagnostics
-            false
+            return false;
         }
+        self.has_email(email)
     }
 
     fn locations(&self, mailbox: &Mailbox) -> Vec<Location> {
-        let line = self
-            .contact_lines
-            .get(mailbox)
-            .map(|i| &self.contacts[*i].line)
-            .copied();
+        let position = self.locate(mailbox);
         vec![Location {
             path: self.path.clone(),
-            line,
+            line: position.map(|(line, _)| line),
+            columns: position.map(|(_, columns)| columns),
         }]
     }
 
-    fn create_contact(&mut self, _mailbox: Mailbox) -> Option<PathBuf> {
-        // not supported
-        None
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn writable(&self) -> bool {
+        self.writable
+    }
+
+    fn harvest(&self) -> bool {
+        self.harvest
+    }
+
+    fn contact_files(&self, mailbox: &Mailbox) -> Vec<PathBuf> {
+        if self.has_email(&mailbox.email) {
+            vec![self.path.clone()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn brief_render(&self) -> bool {
+        true
+    }
+
+    fn usage(&self, email: &str) -> Option<Usage> {
+        let email = fold(email, true);
+        let has_email = |e: &ContactListEntry| fold(&e.mailbox.email, true) == email;
+        let messages = match &self.disk_index {
+            Some(index) => index
+                .containing(&email)
+                .into_iter()
+                .filter_map(|i| self.indexed_entry(index, i))
+                .filter(|e| has_email(e))
+                .filter_map(|e| e.messages)
+                .max(),
+            None => self
+                .contacts
+                .iter()
+                .filter(|e| e.messages.is_some() && has_email(e))
+                .filter_map(|e| e.messages)
+                .max(),
+        }?;
+        Some(Usage {
+            messages: Some(messages),
+            ..Usage::default()
+        })
+    }
+
+    fn create_contact(
+        &mut self,
+        mailbox: Mailbox,
+        _name: Option<StructuredName>,
+    ) -> Option<Location> {
+        if !self.writable {
+            return None;
+        }
+        if self.position(&mailbox).is_none() {
+            let mut content = read_to_string(&self.path).unwrap_or_default();
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            let line_number = content.lines().count();
+            let line = self.format.format_line(&mailbox, &content);
+            content.push_str(&line);
+            content.push('\n');
+            write(&self.path, content).ok()?;
+            if self.index_dir.is_some() {
+                // the index is rebuilt for the new state of the file
+                self.load_contactlist();
+                return self.locations(&mailbox).pop();
+            }
+            self.stamp = FileStamp::of(&self.path);
+            let index = self.contacts.len();
+            self.add_line(line_number, 0, &line);
+            if let Some(entry) = self.contacts.get(index) {
+                self.search_index.insert(index, entry.search_fields());
+            }
+        }
+        self.locations(&mailbox).pop()
+    }
+
+    /// Only writable lists have conflicts, as the others are usually generated.
+    fn conflicts(&self) -> Vec<Conflict> {
+        if self.writable {
+            self.conflicts.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Keep the line of the contact picked by `into`, the first unless it is a location, removing
+    /// the others.
+    fn merge(&mut self, conflict: &Conflict, into: &MergeInto) -> Option<Location> {
+        if !self.writable || !self.conflicts.contains(conflict) {
+            return None;
+        }
+        let lines = conflict
+            .locations
+            .iter()
+            .map(|l| l.line.map(|line| line as usize))
+            .collect::<Option<Vec<_>>>()?;
+        // lines have no revisions and are all in the one file
+        let kept = match into {
+            MergeInto::Location(i) => *lines.get(*i)?,
+            MergeInto::First | MergeInto::Newest | MergeInto::Under(_) => *lines.first()?,
+        };
+        // records separated by NULs share a line
+        let removed = lines
+            .into_iter()
+            .unique()
+            .filter(|l| *l != kept)
+            .collect::<Vec<_>>();
+        if removed.is_empty() {
+            return None;
+        }
+        let content = read_to_string(&self.path).ok()?;
+        let content = content
+            .split_inclusive('\n')
+            .enumerate()
+            .filter(|(i, _)| !removed.contains(i))
+            .map(|(_, line)| line)
+            .collect::<String>();
+        write(&self.path, content).ok()?;
+        self.load_contactlist();
+        let location = conflict
+            .locations
+            .iter()
+            .find(|l| l.line == Some(kept as u32))?;
+        Some(Location {
+            line: Some((kept - removed.iter().filter(|l| **l < kept).count()) as u32),
+            ..location.clone()
+        })
+    }
+
+    fn contact_count(&self) -> usize {
+        self.disk_index
+            .as_ref()
+            .map_or(self.contacts.len(), |index| index.len())
+    }
+
+    fn problems(&self) -> Vec<String> {
+        match self.unparsed.as_slice() {
+            [] => Vec::new(),
+            [line] => vec![format!(
+                "Line {} of {} isn't a contact, check the `format`",
+                line + 1,
+                self.path.display()
+            )],
+            [line, ..] => vec![format!(
+                "{} lines of {} aren't contacts, e.g. line {}, check the `format`",
+                self.unparsed.len(),
+                self.path.display(),
+                line + 1
+            )],
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        // writable lists are only created with their first contact
+        if self.stamp.is_some() {
+            vec![self.path.clone()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    fn reload(&mut self, changed: &[PathBuf]) -> bool {
+        if !changed.contains(&self.path) || FileStamp::of(&self.path) == self.stamp {
+            return false;
+        }
+        self.load_contactlist();
+        true
     }
 }
 
 impl ContactList {
     pub fn new(path: PathBuf, diagnostics: bool) -> Self {
+        Self::load(path, diagnostics, ContactListFormat::default(), None)
+    }
+
+    /// Like [`ContactList::new`] but keeping the parsed file in a [`DiskIndex`] in the directory,
+    /// for lists too large to parse on every start or to hold in memory.
+    ///
+    /// The index is reused while the file and format stay the same.
+    pub fn indexed(
+        path: PathBuf,
+        diagnostics: bool,
+        format: ContactListFormat,
+        index_dir: PathBuf,
+    ) -> Self {
+        Self::load(path, diagnostics, format, Some(index_dir))
+    }
+
+    /// Load the list in the format, indexing it on disk in `index_dir` if given.
+    fn load(
+        path: PathBuf,
+        diagnostics: bool,
+        format: ContactListFormat,
+        index_dir: Option<PathBuf>,
+    ) -> Self {
         let mut s = Self {
             path,
+            label: "ContactList".to_owned(),
             diagnostics,
+            completion_kind: CompletionItemKind::TEXT,
+            avatars: None,
             contacts: Vec::new(),
             contact_lines: HashMap::new(),
             emails_lower: HashSet::new(),
+            search_index: SearchIndex::default(),
+            stamp: None,
+            writable: false,
+            harvest: false,
+            format,
+            exclude: ExcludePatterns::default(),
+            index_dir,
+            disk_index: None,
+            conflicts: Vec::new(),
+            unparsed: Vec::new(),
         };
         s.load_contactlist();
         s
     }
 
+    /// Set the kind of completion items for this source's contacts.
+    pub fn with_completion_kind(mut self, kind: CompletionItemKind) -> Self {
+        self.completion_kind = kind;
+        self
+    }
+
+    /// Set the name of the source, shown alongside its completion items.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Set whether the source only gets contacts harvested from messages sent, rather than those
+    /// added by hand.
+    pub fn with_harvest(mut self, harvest: bool) -> Self {
+        self.harvest = harvest;
+        self
+    }
+
+    /// Set the format of the file, reloading it.
+    pub fn with_format(mut self, format: ContactListFormat) -> Self {
+        self.format = format;
+        self.load_contactlist();
+        self
+    }
+
+    /// Leave out contacts whose email matches any of the patterns, reloading the file.
+    pub fn with_exclude(mut self, exclude: ExcludePatterns) -> Self {
+        self.exclude = exclude;
+        self.load_contactlist();
+        self
+    }
+
+    /// Append new contacts to the file.
+    pub fn with_writable(mut self, writable: bool) -> Self {
+        self.writable = writable;
+        self
+    }
+
+    /// Show avatars for contacts when rendering them.
+    pub fn with_avatars(mut self, avatars: Arc<Avatars>) -> Self {
+        self.avatars = Some(avatars);
+        self
+    }
+
     fn load_contactlist(&mut self) {
-        let content = read_to_string(&self.path).unwrap();
-        for (line_number, line) in content.lines().enumerate() {
-            let line = line.trim();
-            if line.is_empty() {
-                continue;
+        self.contacts.clear();
+        self.contact_lines.clear();
+        self.emails_lower.clear();
+        self.conflicts.clear();
+        self.unparsed.clear();
+        self.disk_index = None;
+        self.stamp = FileStamp::of(&self.path);
+        if let Some(index_dir) = self.index_dir.clone() {
+            match self.load_disk_index(&index_dir) {
+                Ok(index) => {
+                    self.disk_index = Some(index);
+                    return;
+                }
+                Err(err) => tracing::warn!(
+                    path = %self.path.display(),
+                    "Failed to index contact list, loading it into memory: {err}"
+                ),
+            }
+        }
+        let content = read_to_string(&self.path).unwrap_or_default();
+        // the header row of the formats that have one
+        let header = matches!(
+            self.format,
+            ContactListFormat::Csv | ContactListFormat::MuttAb
+        );
+        for (line_number, column, line) in records(&content) {
+            let count = self.contacts.len();
+            self.add_line(line_number, column, line);
+            if self.contacts.len() == count
+                && !(header && line_number == 0)
+                && !strip_comment(line).trim().is_empty()
+                && self.format.parse_line(line).is_none()
+            {
+                self.unparsed.push(line_number as u32);
+            }
+        }
+        self.conflicts = self.find_conflicts();
+        self.search_index = SearchIndex::new(
+            self.contacts
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (i, e.search_fields())),
+        );
+    }
+
+    /// Open the index of the file, building it if there isn't an up to date one.
+    fn load_disk_index(&self, index_dir: &Path) -> std::io::Result<DiskIndex> {
+        let name = hex::encode(Sha256::digest(self.path.to_string_lossy().as_bytes()));
+        let index_path = index_dir.join(format!("{name}.idx"));
+        // excluded contacts are left out when looking them up, so the patterns can change freely
+        let tag = format!("{:?} {:?}", self.format, self.stamp);
+        if let Some(index) = DiskIndex::open(&index_path, &tag) {
+            return Ok(index);
+        }
+        let content = read_to_string(&self.path)?;
+        let parsed = records(&content)
+            .filter_map(|(line, column, text)| {
+                Some((line, column, text, self.format.parse_line(text)?))
+            })
+            .collect::<Vec<_>>();
+        DiskIndex::build(
+            &index_path,
+            &tag,
+            parsed
+                .iter()
+                .map(|(line, column, text, parsed)| IndexRecord {
+                    line: *line as u32,
+                    column: *column as u32,
+                    text,
+                    fields: parsed
+                        .mailbox
+                        .name
+                        .iter()
+                        .chain(&parsed.aliases)
+                        .chain([&parsed.mailbox.email])
+                        .map(|f| f.as_str())
+                        .collect(),
+                }),
+        )
+    }
+
+    /// Find contacts with the same name and email on several lines.
+    fn find_conflicts(&self) -> Vec<Conflict> {
+        self.contacts
+            .iter()
+            .into_group_map_by(|e| {
+                (
+                    e.mailbox.name.as_deref().map(|n| fold(n, true)),
+                    fold(&e.mailbox.email, true),
+                )
+            })
+            .into_values()
+            .filter(|entries| entries.len() > 1)
+            .map(|entries| Conflict {
+                message: format!("{} is on {} lines", entries[0].mailbox, entries.len()),
+                locations: entries
+                    .iter()
+                    .map(|e| Location {
+                        path: self.path.clone(),
+                        line: Some(e.line),
+                        columns: Some(e.columns),
+                    })
+                    .collect(),
+            })
+            .sorted_by_key(|c| c.locations[0].line)
+            .collect()
+    }
+
+    /// Add the contact in the record, which starts at the given line and column of the file.
+    fn add_line(&mut self, line_number: usize, column: usize, line: &str) {
+        let Some(entry) = self.parse_entry(line_number, column, line) else {
+            return;
+        };
+        self.emails_lower.insert(fold(&entry.mailbox.email, true));
+        self.contact_lines
+            .insert(entry.mailbox.clone(), self.contacts.len());
+        self.contacts.push(entry);
+    }
+
+    /// Parse the contact in the record, unless it's excluded.
+    fn parse_entry(
+        &self,
+        line_number: usize,
+        column: usize,
+        line: &str,
+    ) -> Option<ContactListEntry> {
+        let parsed = self.format.parse_line(line)?;
+        if self.exclude.excludes(&parsed.mailbox.email) {
+            return None;
+        }
+        let start = (column + line[..parsed.email_start].chars().count()) as u32;
+        let columns = (start, start + parsed.mailbox.email.chars().count() as u32);
+        let folded_names = parsed
+            .mailbox
+            .name
+            .iter()
+            .chain(&parsed.aliases)
+            .map(|n| Folded::new(n))
+            .collect();
+        Some(ContactListEntry {
+            folded_email: Folded::new(&parsed.mailbox.email),
+            mailbox: parsed.mailbox,
+            aliases: parsed.aliases,
+            line: line_number as u32,
+            columns,
+            folded_names,
+            messages: parsed.messages,
+        })
+    }
+
+    fn indexed_entry(&self, index: &DiskIndex, entry: usize) -> Option<ContactListEntry> {
+        let (line, column, text) = index.record(entry);
+        self.parse_entry(line as usize, column as usize, text)
+    }
+
+    /// Whether a contact in the list has the email.
+    fn has_email(&self, email: &str) -> bool {
+        let email = fold(email, true);
+        match &self.disk_index {
+            Some(index) => index
+                .containing(&email)
+                .into_iter()
+                .filter_map(|i| self.indexed_entry(index, i))
+                .any(|e| fold(&e.mailbox.email, true) == email),
+            None => self.emails_lower.contains(&email),
+        }
+    }
+
+    /// The line and columns to go to for the mailbox: its own, else those of the first contact
+    /// with its email, else with its name, ignoring case and diacritics.
+    fn locate(&self, mailbox: &Mailbox) -> Option<(u32, (u32, u32))> {
+        if let Some(position) = self.position(mailbox) {
+            return Some(position);
+        }
+        let email = fold(&mailbox.email, true);
+        let name = mailbox.name.as_deref().map(|n| fold(n, true));
+        let has_email = |e: &ContactListEntry| e.folded_email.get(true) == email;
+        let has_name = |e: &ContactListEntry| {
+            name.as_ref()
+                .is_some_and(|name| e.folded_names.iter().any(|n| n.get(true) == name))
+        };
+        let position = |e: &ContactListEntry| (e.line, e.columns);
+        match &self.disk_index {
+            Some(index) => {
+                let entries = |indices: Vec<usize>| {
+                    indices
+                        .into_iter()
+                        .filter_map(|i| self.indexed_entry(index, i))
+                };
+                entries(index.containing(&email))
+                    .find(has_email)
+                    .or_else(|| {
+                        let options = MatchOptions {
+                            strip_diacritics: true,
+                            ..MatchOptions::default()
+                        };
+                        let matcher = Matcher::new(name.as_deref()?, options);
+                        let candidates = index
+                            .candidates(&matcher)
+                            .unwrap_or_else(|| (0..index.len()).collect());
+                        entries(candidates).find(has_name)
+                    })
+                    .map(|e| position(&e))
             }
-            let mut parts = line.split(' ').collect::<Vec<_>>();
-            let email = parts.remove(parts.len() - 1).to_owned();
-            let name = if !parts.is_empty() {
-                Some(parts.join(" "))
-            } else {
-                None
+            None => self
+                .contacts
+                .iter()
+                .find(|e| has_email(e))
+                .or_else(|| self.contacts.iter().find(|e| has_name(e)))
+                .map(position),
+        }
+    }
+
+    /// The line and columns of the contact's email in the file.
+    fn position(&self, mailbox: &Mailbox) -> Option<(u32, (u32, u32))> {
+        match &self.disk_index {
+            Some(index) => index
+                .containing(&fold(&mailbox.email, true))
+                .into_iter()
+                .filter_map(|i| self.indexed_entry(index, i))
+                .find(|e| &e.mailbox == mailbox)
+                .map(|e| (e.line, e.columns)),
+            None => self
+                .contact_lines
+                .get(mailbox)
+                .map(|i| (self.contacts[*i].line, self.contacts[*i].columns)),
+        }
+    }
+
+    /// The entries matching the query, with their position in the list, as `f` maps them.
+    fn matching_entries<'a, T: 'a>(
+        &'a self,
+        matcher: &'a Matcher,
+        f: impl Fn(usize, &ContactListEntry) -> T + 'a,
+    ) -> Box<dyn Iterator<Item = T> + 'a> {
+        let matches = move |e: &ContactListEntry| {
+            matcher.matches_folded(&e.folded_names, std::slice::from_ref(&e.folded_email))
+        };
+        if let Some(index) = &self.disk_index {
+            let candidates: Box<dyn Iterator<Item = usize>> = match index.candidates(matcher) {
+                Some(candidates) => Box::new(candidates.into_iter()),
+                None => Box::new(0..index.len()),
+            };
+            return Box::new(
+                candidates
+                    .filter_map(|i| Some((i, self.indexed_entry(index, i)?)))
+                    .filter(move |(_, e)| matches(e))
+                    .map(move |(i, e)| f(i, &e)),
+            );
+        }
+        let candidates: Box<dyn Iterator<Item = usize>> =
+            match self.search_index.candidates(matcher) {
+                Some(candidates) => Box::new(candidates.into_iter()),
+                None => Box::new(0..self.contacts.len()),
             };
-            self.emails_lower.insert(email.to_lowercase());
-            let mbox = Mailbox { name, email };
-            self.contact_lines.insert(mbox.clone(), self.contacts.len());
-            self.contacts.push(ContactListEntry {
-                mailbox: mbox,
-                line: line_number as u32,
-            });
+        Box::new(
+            candidates
+                .map(|i| (i, &self.contacts[i]))
+                .filter(move |(_, e)| matches(e))
+                .map(move |(i, e)| f(i, e)),
+        )
+    }
+
+    /// The contact of the `i`th entry.
+    fn to_contact(&self, i: usize, entry: &ContactListEntry) -> Contact {
+        Contact {
+            id: Contact::id_in(&self.path, i),
+            names: entry
+                .mailbox
+                .name
+                .iter()
+                .chain(&entry.aliases)
+                .cloned()
+                .collect(),
+            mailboxes: vec![entry.mailbox.clone()],
+            members: Vec::new(),
+            meta: ContactMeta {
+                source: self.label.clone(),
+                kind: self.completion_kind,
+                path: Some(self.path.clone()),
+                birthday: None,
+                phones: Vec::new(),
+            },
         }
     }
+
+    fn to_match(&self, entry: &ContactListEntry) -> Match {
+        Match {
+            source: self.label.clone(),
+            kind: self.completion_kind,
+            addresses: vec![entry.mailbox.email.clone()],
+            mailbox: entry.mailbox.clone(),
+            aliases: entry.aliases.clone(),
+            members: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    fn parse(format: ContactListFormat, line: &str) -> (Option<String>, String, Vec<String>) {
+        let parsed = format.parse_line(line).unwrap();
+        assert_eq!(
+            &line[parsed.email_start..][..parsed.mailbox.email.len()],
+            parsed.mailbox.email
+        );
+        (parsed.mailbox.name, parsed.mailbox.email, parsed.aliases)
+    }
+
+    #[test]
+    fn formats() {
+        let auto = ContactListFormat::Auto;
+        assert_eq!(
+            parse(auto, "Jane Doe jane@example.com"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse(auto, "jane@example.com"),
+            (None, "jane@example.com".to_owned(), vec![])
+        );
+        assert_eq!(
+            parse(auto, "alias jane Jane Doe <jane@example.com>"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec!["jane".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(
+                ContactListFormat::MuttAlias,
+                "alias -group work jd \"Doe, Jane\" <jane@example.com> # comment"
+            ),
+            (
+                Some("Doe, Jane".to_owned()),
+                "jane@example.com".to_owned(),
+                vec!["jd".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(auto, "alias jane jane@example.com (Jane Doe)"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec!["jane".to_owned()]
+            )
+        );
+        assert_eq!(
+            parse(auto, "Jane Doe <jane@example.com>"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse(
+                ContactListFormat::Notmuch,
+                "12\t\"Doe, Jane\" <jane@example.com>"
+            ),
+            (
+                Some("Doe, Jane".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse(ContactListFormat::Notmuch, "jane@example.com"),
+            (None, "jane@example.com".to_owned(), vec![])
+        );
+        let csv = ContactListFormat::Csv;
+        assert!(csv.parse_line("\"Name\",\"Email\"").is_none());
+        assert_eq!(
+            parse(csv, "\"Doe, \"\"JD\"\" Jane\",\"jane@example.com\""),
+            (
+                Some("Doe, \"JD\" Jane".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        let mutt_ab = ContactListFormat::MuttAb;
+        assert!(mutt_ab
+            .parse_line("Matching addresses in the mu database:")
+            .is_none());
+        assert_eq!(
+            parse(mutt_ab, "jane@example.com\tJane Doe\t"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        assert!(auto.parse_line("  ").is_none());
+        assert!(auto.parse_line("# friends").is_none());
+        assert!(auto.parse_line("  # friends").is_none());
+        assert_eq!(
+            parse(auto, "Jane Doe jane@example.com # met at work"),
+            (
+                Some("Jane Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+        assert_eq!(
+            parse(csv, "\"Jane # Doe\",\"jane@example.com\""),
+            (
+                Some("Jane # Doe".to_owned()),
+                "jane@example.com".to_owned(),
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn merge_duplicate_lines() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts");
+        std::fs::write(
+            &path,
+            "Jane Doe jane@example.com\nBob bob@example.com\njane doe JANE@example.com\n",
+        )
+        .unwrap();
+        let mut list = ContactList::new(path.clone(), true);
+        // only writable lists are the user's to fix
+        assert!(list.conflicts().is_empty());
+        list = list.with_writable(true);
+        let conflicts = list.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].locations.len(), 2);
+
+        let location = list.merge(&conflicts[0], &MergeInto::First).unwrap();
+        assert_eq!(location.line, Some(0));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Jane Doe jane@example.com\nBob bob@example.com\n"
+        );
+        assert!(list.conflicts().is_empty());
+
+        // into the later one, which moves up as the earlier is removed
+        std::fs::write(
+            &path,
+            "Jane Doe jane@example.com\nBob bob@example.com\njane doe JANE@example.com\n",
+        )
+        .unwrap();
+        list = ContactList::new(path.clone(), true).with_writable(true);
+        let conflicts = list.conflicts();
+        let location = list.merge(&conflicts[0], &MergeInto::Location(1)).unwrap();
+        assert_eq!(location.line, Some(1));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Bob bob@example.com\njane doe JANE@example.com\n"
+        );
+    }
+
+    #[test]
+    fn unparsed_lines() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts");
+        std::fs::write(
+            &path,
+            "# friends\nJane Doe jane@example.com\n\nalias bob Bob <bob@example.com>\n",
+        )
+        .unwrap();
+        let list = ContactList::new(path.clone(), true).with_format(ContactListFormat::MuttAlias);
+        assert_eq!(list.contact_count(), 1);
+        assert_eq!(list.problems().len(), 1);
+        assert!(list.problems()[0].starts_with("Line 2 of"));
+    }
+
+    #[test]
+    fn find_contacts() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts");
+        std::fs::write(&path, "Jane Doe jane@example.com\nBob bob@example.com\n").unwrap();
+        let list = ContactList::new(path.clone(), true);
+        let contacts = list.find_contacts(&Matcher::new("bob", MatchOptions::default()));
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].id, format!("{}#2", path.display()));
+        assert_eq!(
+            contacts[0].emails().collect::<Vec<_>>(),
+            ["bob@example.com"]
+        );
+        assert_eq!(list.contacts().len(), 2);
+    }
+
+    #[test]
+    fn notmuch_counts() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts");
+        std::fs::write(
+            &path,
+            "37\tJane Doe <jane@example.com>\n2\tJ Doe <JANE@example.com>\nBob <bob@example.com>\n",
+        )
+        .unwrap();
+        let list = ContactList::new(path.clone(), true).with_format(ContactListFormat::Notmuch);
+        assert_eq!(
+            list.usage("jane@example.com"),
+            Some(Usage {
+                last: None,
+                messages: Some(37)
+            })
+        );
+        assert_eq!(list.usage("bob@example.com"), None);
+        assert_eq!(
+            list.usage("jane@example.com").unwrap().to_string(),
+            "37 messages"
+        );
+    }
+
+    #[test]
+    fn locate_by_email_or_name() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts");
+        std::fs::write(&path, "Bob bob@example.com\nJane Doe jane@example.com\n").unwrap();
+        let list = ContactList::new(path.clone(), true);
+        let line = |mailbox: &str| list.locations(&mailbox.parse().unwrap())[0].line;
+        assert_eq!(line("Jane Doe <jane@example.com>"), Some(1));
+        assert_eq!(line("JD <JANE@example.com>"), Some(1));
+        assert_eq!(line("Jáne DOE <jane@work.example>"), Some(1));
+        assert_eq!(line("Someone <someone@example.com>"), None);
+    }
 }
@@ -1,16 +1,35 @@
-use std::path::PathBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::metadata,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
+use chrono::{Local, NaiveDate};
 use itertools::Itertools as _;
-use lsp_types::Url;
+use lsp_types::{CompletionItemKind, Url};
+use serde::{Deserialize, Serialize};
 
-use crate::Mailbox;
+use crate::{
+    find_duplicates, fold, AddressWeights, Birthday, Contact, Duplicate, Mailbox, MatchOptions,
+    Matcher, Phone, StructuredName, UsageLog,
+};
 
 pub trait ContactSource {
     /// Render a version of the contact for this mailbox using markdown.
     fn render(&self, mailbox: &Mailbox) -> String;
 
-    /// Find any matching mailboxes.
-    fn find_matching(&self, word: String) -> Box<(dyn Iterator<Item = (String, Mailbox)> + '_)>;
+    /// Find any mailboxes matching the query.
+    fn find_matching<'a>(&'a self, matcher: &'a Matcher) -> Box<dyn Iterator<Item = Match> + 'a>;
+
+    /// Find any contacts matching the query, each with all of its addresses, groups included.
+    fn find_contacts(&self, matcher: &Matcher) -> Vec<Contact>;
+
+    /// All of the contacts in the source.
+    fn contacts(&self) -> Vec<Contact> {
+        self.find_contacts(&Matcher::new("", MatchOptions::default()))
+    }
 
     /// Whether the given mailbox is in the source.
     fn contains(&self, email: &str) -> bool;
@@ -18,72 +37,1250 @@ pub trait ContactSource {
     /// Get the locations for the given mailbox.
     fn locations(&self, mailbox: &Mailbox) -> Vec<Location>;
 
-    /// Create the contact for the given mailbox, returning the path to it.
-    fn create_contact(&mut self, mailbox: Mailbox) -> Option<PathBuf>;
+    /// Get the locations of the contacts with the `UID`.
+    fn uid_locations(&self, _uid: &str) -> Vec<Location> {
+        Vec::new()
+    }
+
+    /// Get the locations of the groups the mailbox is a member of.
+    fn group_locations(&self, _mailbox: &Mailbox) -> Vec<Location> {
+        Vec::new()
+    }
+
+    /// The source's label, heading its part of renders that combine sources.
+    fn label(&self) -> &str {
+        ""
+    }
+
+    /// Files the mailbox's contact is in, like [`locations`](Self::locations) without finding
+    /// the lines.
+    fn contact_files(&self, _mailbox: &Mailbox) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Whether renders only repeat the mailbox, so are left out when another source has more.
+    fn brief_render(&self) -> bool {
+        false
+    }
+
+    /// Whether new contacts can be created in the source.
+    fn writable(&self) -> bool {
+        false
+    }
+
+    /// Whether the source collects the recipients of messages sent, so only gets contacts
+    /// harvested from them, rather than those added by hand.
+    fn harvest(&self) -> bool {
+        false
+    }
+
+    /// The phone numbers of the mailbox's contact.
+    fn phones(&self, _mailbox: &Mailbox) -> Vec<Phone> {
+        Vec::new()
+    }
+
+    /// Render the contacts with the phone number, as [`normalize_phone`](crate::normalize_phone)
+    /// gives it, using markdown.
+    fn render_phone(&self, _phone: &str) -> String {
+        String::new()
+    }
+
+    /// The postal addresses of the mailbox's contact.
+    fn postal_addresses(&self, _mailbox: &Mailbox) -> Vec<PostalAddress> {
+        Vec::new()
+    }
+
+    /// The birthday of the mailbox's contact, if it has one.
+    fn birthday(&self, _mailbox: &Mailbox) -> Option<Birthday> {
+        None
+    }
+
+    /// What the source knows of the mail sent to or from the address.
+    fn usage(&self, _email: &str) -> Option<Usage> {
+        None
+    }
+
+    /// Create the contact for the given mailbox, returning where it is.
+    ///
+    /// The structured name overrides the one derived from the mailbox's name, for sources that
+    /// store one.
+    fn create_contact(
+        &mut self,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+    ) -> Option<Location>;
+
+    /// Contacts in the source that conflict with each other.
+    fn conflicts(&self) -> Vec<Conflict> {
+        Vec::new()
+    }
+
+    /// Merge the contacts of one of the source's current conflicts into the one picked by
+    /// `into`, returning where it is.
+    ///
+    /// Returns `None` if the conflict isn't the source's, e.g. as the files changed since it was
+    /// found, or the source can't merge it.
+    fn merge(&mut self, _conflict: &Conflict, _into: &MergeInto) -> Option<Location> {
+        None
+    }
+
+    /// Append the text to the notes of the mailbox's contact, dated today, returning where the
+    /// notes are.
+    ///
+    /// Returns `None` if the source doesn't have the contact or can't edit it.
+    fn add_note(&mut self, _mailbox: &Mailbox, _note: &str) -> Option<Location> {
+        None
+    }
+
+    /// Number of contacts in the source, for logging.
+    fn contact_count(&self) -> usize;
+
+    /// Problems with the source that the user can fix, e.g. files that couldn't be loaded.
+    fn problems(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Files the contacts were loaded from.
+    fn files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Files and directories to watch for changes to the source.
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Reload after the files at the paths changed, returning whether the source changed.
+    fn reload(&mut self, _changed: &[PathBuf]) -> bool {
+        false
+    }
 }
 
+/// All of the sources, in the order they were configured.
+///
+/// Sources can be limited to the documents in a workspace folder, see
+/// [`for_document`](Self::for_document); as a [`ContactSource`] it uses all of them.
 #[derive(Default)]
 pub struct Sources {
-    pub sources: Vec<Box<dyn ContactSource>>,
+    sources: Vec<SourceEntry>,
+    /// Where the addresses have been used, to show and rank them by along with the sources.
+    usage_log: Option<Arc<UsageLog>>,
+    /// Weights of addresses from mail outside of the sources, by where they're from.
+    weights: HashMap<&'static str, AddressWeights>,
+    /// Position in the configuration of the source to create new contacts in before the others.
+    write_source: Option<usize>,
+}
+
+struct SourceEntry {
+    source: Box<dyn ContactSource + Send + Sync>,
+    /// Position of the source in the configuration.
+    position: usize,
+    /// Priority of the source in the configuration, which its position is sorted by.
+    priority: i32,
+    /// The workspace folder the source is limited to.
+    folder: Option<PathBuf>,
+}
+
+impl Sources {
+    /// Add a source in its position in the configuration, after any others in the same one.
+    pub fn add(
+        &mut self,
+        source: Box<dyn ContactSource + Send + Sync>,
+        position: usize,
+        priority: i32,
+        folder: Option<PathBuf>,
+    ) {
+        let index = self.sources.partition_point(|e| e.position <= position);
+        self.sources.insert(
+            index,
+            SourceEntry {
+                source,
+                position,
+                priority,
+                folder,
+            },
+        );
+    }
+
+    /// Remove the sources limited to the folder, returning whether there were any.
+    pub fn remove_folder(&mut self, folder: &Path) -> bool {
+        let len = self.sources.len();
+        self.sources.retain(|e| e.folder.as_deref() != Some(folder));
+        self.sources.len() != len
+    }
+
+    /// Create new contacts in the source at the position in the configuration when it can,
+    /// rather than the first that can.
+    pub fn set_write_source(&mut self, position: Option<usize>) {
+        self.write_source = position;
+    }
+
+    /// Indices of the sources in the order to try creating new contacts in, the write source
+    /// first.
+    fn write_order(&self) -> Vec<usize> {
+        let mut order = (0..self.sources.len()).collect::<Vec<_>>();
+        order.sort_by_key(|i| Some(self.sources[*i].position) != self.write_source);
+        order
+    }
+
+    /// The sources for the document that new contacts can be created in, in the order they are
+    /// tried, by their position in the configuration with their label as
+    /// [`create_contact_for`](Self::create_contact_for) gives it.
+    pub fn write_targets(&self, document: Option<&Path>) -> Vec<(usize, String)> {
+        self.write_order()
+            .into_iter()
+            .map(|i| &self.sources[i])
+            .filter(|e| e.applies_to(document) && !e.source.harvest() && e.source.writable())
+            .map(|e| (e.position, e.label()))
+            .collect()
+    }
+
+    /// Create the contact in the source at the position in the configuration, like
+    /// [`create_contact_for`](Self::create_contact_for).
+    pub fn create_contact_at(
+        &mut self,
+        position: usize,
+        document: Option<&Path>,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+    ) -> Option<(String, Location)> {
+        let e = self
+            .sources
+            .iter_mut()
+            .find(|e| e.position == position && e.applies_to(document))?;
+        let location = e.source.create_contact(mailbox, name)?;
+        Some((e.label(), location))
+    }
+
+    pub fn set_usage_log(&mut self, usage_log: Arc<UsageLog>) {
+        self.usage_log = Some(usage_log);
+    }
+
+    /// Rank completions by the weights, replacing any from the same place.
+    pub fn set_weights(&mut self, from: &'static str, weights: AddressWeights) {
+        self.weights.insert(from, weights);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// The sources to use for the document at the path: those not limited to a workspace folder
+    /// and those of the folders it is in.
+    pub fn for_document(&self, document: Option<&Path>) -> DocumentSources<'_> {
+        DocumentSources::new(
+            self.sources.iter().filter(|e| e.applies_to(document)),
+            self.usage_log.as_deref(),
+            self.weights.values().collect(),
+        )
+    }
+
+    /// Create the contact in the write source set by [`set_write_source`](Self::set_write_source)
+    /// if it can, or else the first source for the document that can, other than those that
+    /// [`harvest`](ContactSource::harvest), returning the label of the source, or its position in
+    /// the configuration if it has none, with where the contact is.
+    pub fn create_contact_for(
+        &mut self,
+        document: Option<&Path>,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+    ) -> Option<(String, Location)> {
+        self.create_contact_in(document, mailbox, name, false)
+    }
+
+    /// Create the contact of a recipient in the first source for the document that
+    /// [`harvest`](ContactSource::harvest)s them, like
+    /// [`create_contact_for`](Self::create_contact_for).
+    pub fn harvest_for(
+        &mut self,
+        document: Option<&Path>,
+        mailbox: Mailbox,
+    ) -> Option<(String, Location)> {
+        self.create_contact_in(document, mailbox, None, true)
+    }
+
+    fn create_contact_in(
+        &mut self,
+        document: Option<&Path>,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+        harvest: bool,
+    ) -> Option<(String, Location)> {
+        for i in self.write_order() {
+            let e = &mut self.sources[i];
+            if !e.applies_to(document) || e.source.harvest() != harvest {
+                continue;
+            }
+            if let Some(location) = e.source.create_contact(mailbox.clone(), name.clone()) {
+                return Some((e.label(), location));
+            }
+        }
+        None
+    }
+
+    /// Add the note to the mailbox's contact in the first source for the document that can.
+    pub fn add_note_for(
+        &mut self,
+        document: Option<&Path>,
+        mailbox: &Mailbox,
+        note: &str,
+    ) -> Option<Location> {
+        self.sources
+            .iter_mut()
+            .filter(|e| e.applies_to(document))
+            .find_map(|e| e.source.add_note(mailbox, note))
+    }
+
+    /// Addresses that are in more than one source, or that contacts give different names.
+    pub fn duplicates(&self) -> Vec<Duplicate> {
+        find_duplicates(
+            &self
+                .sources
+                .iter()
+                .map(|e| &*e.source as &dyn ContactSource)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Every contact once, sorted: addresses that an earlier source, or an earlier contact in the
+    /// same one, already has are left out, along with contacts that have none left.
+    ///
+    /// The mailbox of each contact has its most preferred remaining address.
+    pub fn distinct_contacts(&self) -> Vec<Match> {
+        let matcher = Matcher::new("", MatchOptions::default());
+        let mut seen = HashSet::new();
+        let mut groups = HashSet::new();
+        let mut contacts = Vec::new();
+        for e in &self.sources {
+            let mut matches = e
+                .source
+                .contacts()
+                .iter()
+                .filter_map(Contact::to_match)
+                .collect::<Vec<_>>();
+            sort_matches(&mut matches, &matcher);
+            for mut m in matches {
+                if !m.members.is_empty() {
+                    if groups.insert((m.mailbox.name.clone(), m.members.clone())) {
+                        contacts.push(m);
+                    }
+                    continue;
+                }
+                m.addresses.retain(|a| seen.insert(fold(a, false)));
+                let Some(email) = m.addresses.first() else {
+                    continue;
+                };
+                m.mailbox.email = email.clone();
+                contacts.push(m);
+            }
+        }
+        sort_matches(&mut contacts, &matcher);
+        contacts
+    }
+
+    fn all(&self) -> DocumentSources<'_> {
+        DocumentSources::new(
+            self.sources.iter(),
+            self.usage_log.as_deref(),
+            self.weights.values().collect(),
+        )
+    }
+}
+
+impl SourceEntry {
+    fn applies_to(&self, document: Option<&Path>) -> bool {
+        match &self.folder {
+            Some(folder) => document.is_some_and(|d| d.starts_with(folder)),
+            None => true,
+        }
+    }
+
+    /// The source's label, or its position in the configuration if it has none.
+    fn label(&self) -> String {
+        match self.source.label() {
+            "" => format!("source {}", self.position + 1),
+            label => label.to_owned(),
+        }
+    }
 }
 
 impl ContactSource for Sources {
     fn render(&self, mailbox: &Mailbox) -> String {
+        self.all().render(mailbox)
+    }
+
+    fn find_matching<'a>(&'a self, matcher: &'a Matcher) -> Box<dyn Iterator<Item = Match> + 'a> {
+        Box::new(self.all().find_matching(matcher).into_iter())
+    }
+
+    fn find_contacts(&self, matcher: &Matcher) -> Vec<Contact> {
+        self.all().find_contacts(matcher)
+    }
+
+    fn contains(&self, email: &str) -> bool {
+        self.all().contains(email)
+    }
+
+    fn locations(&self, mailbox: &Mailbox) -> Vec<Location> {
+        self.all().locations(mailbox)
+    }
+
+    fn group_locations(&self, mailbox: &Mailbox) -> Vec<Location> {
+        self.all().group_locations(mailbox)
+    }
+
+    fn uid_locations(&self, uid: &str) -> Vec<Location> {
+        self.all().uid_locations(uid)
+    }
+
+    fn usage(&self, email: &str) -> Option<Usage> {
+        self.all().usage(email)
+    }
+
+    fn phones(&self, mailbox: &Mailbox) -> Vec<Phone> {
+        self.all().phones(mailbox)
+    }
+
+    fn render_phone(&self, phone: &str) -> String {
+        self.all().render_phone(phone)
+    }
+
+    fn postal_addresses(&self, mailbox: &Mailbox) -> Vec<PostalAddress> {
+        self.all().postal_addresses(mailbox)
+    }
+
+    fn birthday(&self, mailbox: &Mailbox) -> Option<Birthday> {
+        self.all().birthday(mailbox)
+    }
+
+    fn create_contact(
+        &mut self,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+    ) -> Option<Location> {
+        for i in self.write_order() {
+            let e = &mut self.sources[i];
+            if e.source.harvest() {
+                continue;
+            }
+            if let Some(location) = e.source.create_contact(mailbox.clone(), name.clone()) {
+                return Some(location);
+            }
+        }
+        None
+    }
+
+    fn conflicts(&self) -> Vec<Conflict> {
+        self.sources
+            .iter()
+            .flat_map(|e| e.source.conflicts())
+            .collect()
+    }
+
+    fn merge(&mut self, conflict: &Conflict, into: &MergeInto) -> Option<Location> {
+        self.sources
+            .iter_mut()
+            .find_map(|e| e.source.merge(conflict, into))
+    }
+
+    fn add_note(&mut self, mailbox: &Mailbox, note: &str) -> Option<Location> {
+        self.sources
+            .iter_mut()
+            .find_map(|e| e.source.add_note(mailbox, note))
+    }
+
+    fn contact_count(&self) -> usize {
+        self.sources.iter().map(|e| e.source.contact_count()).sum()
+    }
+
+    fn problems(&self) -> Vec<String> {
+        self.sources
+            .iter()
+            .flat_map(|e| e.source.problems())
+            .collect()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.sources.iter().flat_map(|e| e.source.files()).collect()
+    }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
         self.sources
             .iter()
-            .map(|s| s.render(mailbox))
-            .filter(|s| !s.is_empty())
+            .flat_map(|e| e.source.watched_paths())
+            .collect()
+    }
+
+    fn reload(&mut self, changed: &[PathBuf]) -> bool {
+        let mut reloaded = false;
+        for e in &mut self.sources {
+            reloaded |= e.source.reload(changed);
+        }
+        reloaded
+    }
+}
+
+/// The sources that apply to a document, from [`Sources::for_document`].
+pub struct DocumentSources<'a> {
+    sources: Vec<&'a dyn ContactSource>,
+    /// Priority of each of the sources, in the same order.
+    priorities: Vec<i32>,
+    usage_log: Option<&'a UsageLog>,
+    weights: Vec<&'a AddressWeights>,
+}
+
+/// Which sources goto definition goes to the contact in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionSources {
+    /// Only the first source with the contact.
+    First,
+    /// All of the sources with the contact.
+    #[default]
+    All,
+    /// The sources with the contact that have the highest priority of them.
+    Priority,
+}
+
+impl<'a> DocumentSources<'a> {
+    fn new(
+        entries: impl Iterator<Item = &'a SourceEntry>,
+        usage_log: Option<&'a UsageLog>,
+        weights: Vec<&'a AddressWeights>,
+    ) -> Self {
+        let (sources, priorities) = entries
+            .map(|e| (&*e.source as &dyn ContactSource, e.priority))
+            .unzip();
+        Self {
+            sources,
+            priorities,
+            usage_log,
+            weights,
+        }
+    }
+}
+
+impl DocumentSources<'_> {
+    /// Render the contact from each of the sources that has it, headed by the source's label and
+    /// the files it's in, after any usage of the address.
+    ///
+    /// Brief renders are left out if any source has more to show.
+    pub fn render(&self, mailbox: &Mailbox) -> String {
+        let usage = self.usage(&mailbox.email);
+        let renders = self
+            .sources
+            .iter()
+            .map(|s| (s, s.render(mailbox)))
+            .filter(|(_, r)| !r.is_empty())
+            .collect::<Vec<_>>();
+        let full = renders.iter().any(|(s, _)| !s.brief_render());
+        let sections = renders
+            .into_iter()
+            .filter(|(s, _)| !(full && s.brief_render()))
+            .map(|(s, render)| {
+                let heading = (!s.label().is_empty())
+                    .then(|| format!("*{}*", s.label()))
+                    .into_iter()
+                    .chain(
+                        s.contact_files(mailbox)
+                            .iter()
+                            .map(|p| format!("`{}`", p.display())),
+                    )
+                    .collect::<Vec<_>>();
+                if heading.is_empty() {
+                    render
+                } else {
+                    format!("{}\n\n{}", heading.join(" "), render)
+                }
+            });
+        usage
+            .map(|u| u.to_string())
+            .into_iter()
+            .chain(sections)
             .collect::<Vec<_>>()
-            .join("\n\n")
+            .join("\n\n---\n\n")
     }
 
-    fn find_matching(&self, word: String) -> Box<(dyn Iterator<Item = (String, Mailbox)> + '_)> {
-        Box::new(
-            self.sources
-                .iter()
-                .flat_map(move |s| s.find_matching(word.clone()))
-                .unique(),
-        )
+    /// The phone numbers of the mailbox's contact in each of the sources, once each.
+    pub fn phones(&self, mailbox: &Mailbox) -> Vec<Phone> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.phones(mailbox))
+            .unique_by(|p| p.number.clone())
+            .collect()
     }
 
-    fn contains(&self, email: &str) -> bool {
+    /// Render the contacts with the phone number from each of the sources that has them, headed
+    /// by the source's label.
+    pub fn render_phone(&self, phone: &str) -> String {
+        self.sources
+            .iter()
+            .map(|s| (s, s.render_phone(phone)))
+            .filter(|(_, r)| !r.is_empty())
+            .map(|(s, render)| {
+                if s.label().is_empty() {
+                    render
+                } else {
+                    format!("*{}*\n\n{render}", s.label())
+                }
+            })
+            .join("\n\n---\n\n")
+    }
+
+    /// The postal addresses of the mailbox's contact in each of the sources, once each.
+    pub fn postal_addresses(&self, mailbox: &Mailbox) -> Vec<PostalAddress> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.postal_addresses(mailbox))
+            .unique_by(|a| a.lines.clone())
+            .collect()
+    }
+
+    /// The birthday of the mailbox's contact, from the first source that has one.
+    pub fn birthday(&self, mailbox: &Mailbox) -> Option<Birthday> {
+        self.sources.iter().find_map(|s| s.birthday(mailbox))
+    }
+
+    /// Usage of the address combined from the sources that know of it and the usage log.
+    pub fn usage(&self, email: &str) -> Option<Usage> {
+        self.sources
+            .iter()
+            .filter_map(|s| s.usage(email))
+            .chain(self.usage_log.and_then(|log| log.usage(email)))
+            .reduce(Usage::combine)
+    }
+
+    /// Matches from all of the sources, sorted.
+    pub fn find_matching(&self, matcher: &Matcher) -> Vec<Match> {
+        self.find_matching_timed(matcher).0
+    }
+
+    /// The contacts matching the query in each of the sources, best matches first.
+    pub fn find_contacts(&self, matcher: &Matcher) -> Vec<Contact> {
+        let mut contacts = self
+            .sources
+            .iter()
+            .flat_map(|s| s.find_contacts(matcher))
+            .collect::<Vec<_>>();
+        contacts.sort_by_cached_key(|c| (c.score(matcher), c.name().map(str::to_owned)));
+        contacts
+    }
+
+    /// Matches like [`find_matching`](Self::find_matching), with how long each source took to
+    /// find its own, by the source's label or its position if it has none.
+    pub fn find_matching_timed(&self, matcher: &Matcher) -> (Vec<Match>, Vec<(String, Duration)>) {
+        let mut matches = Vec::new();
+        let mut timings = Vec::new();
+        for (i, s) in self.sources.iter().enumerate() {
+            let start = Instant::now();
+            matches.extend(s.find_matching(matcher));
+            let label = match s.label() {
+                "" => format!("source {}", i + 1),
+                label => label.to_owned(),
+            };
+            timings.push((label, start.elapsed()));
+        }
+        let mut matches = matches
+            .into_iter()
+            .unique_by(|m| (m.source.clone(), m.mailbox.clone()))
+            .collect::<Vec<_>>();
+        self.sort_matches(&mut matches, matcher);
+        (matches, timings)
+    }
+
+    /// Sort matches like [`sort_matches`], putting the addresses used most in the usage log and
+    /// the weights first of those that match equally well.
+    pub fn sort_matches(&self, matches: &mut [Match], matcher: &Matcher) {
+        if self.usage_log.is_none() && self.weights.is_empty() {
+            return sort_matches(matches, matcher);
+        }
+        let today = Local::now().date_naive();
+        sort_matches_by_frecency(matches, matcher, |m| {
+            let email = &m.mailbox.email;
+            self.usage_log.map_or(0, |log| log.frecency(email, today))
+                + self
+                    .weights
+                    .iter()
+                    .map(|w| w.frecency(email, today))
+                    .sum::<u64>()
+        });
+    }
+
+    /// Contacts with the name, or one of their aliases, exactly, from all of the sources.
+    ///
+    /// Each contact is found once, by its preferred address.
+    pub fn find_by_name(&self, name: &str, options: MatchOptions) -> Vec<Match> {
+        let folded = fold(name, options.strip_diacritics);
+        self.find_matching(&Matcher::new(name, options))
+            .into_iter()
+            .filter(|m| m.members.is_empty())
+            .filter(|m| {
+                m.names()
+                    .iter()
+                    .any(|n| fold(n, options.strip_diacritics) == folded)
+            })
+            .unique_by(|m| (m.source.clone(), m.addresses.clone()))
+            .collect()
+    }
+
+    pub fn contains(&self, email: &str) -> bool {
         self.sources.iter().any(|s| s.contains(email))
     }
 
-    fn locations(&self, mailbox: &Mailbox) -> Vec<Location> {
+    /// Whether any of the sources has a contact with the address, including those that aren't
+    /// used for diagnostics, unlike [`contains`](Self::contains).
+    pub fn has_contact(&self, email: &str) -> bool {
+        let mailbox = Mailbox {
+            name: None,
+            email: email.to_owned(),
+        };
+        self.sources
+            .iter()
+            .any(|s| !s.contact_files(&mailbox).is_empty())
+    }
+
+    pub fn locations(&self, mailbox: &Mailbox) -> Vec<Location> {
         self.sources
             .iter()
             .flat_map(|s| s.locations(mailbox))
             .collect()
     }
 
-    fn create_contact(&mut self, mailbox: Mailbox) -> Option<PathBuf> {
-        for s in &mut self.sources {
-            if let Some(path) = s.create_contact(mailbox.clone()) {
-                return Some(path);
+    /// Locations of the mailbox in the sources picked by `which`.
+    ///
+    /// Sources only count as having the contact when they know its line, as contact lists give
+    /// their file regardless; if none do, every location is used.
+    pub fn definition_locations(
+        &self,
+        mailbox: &Mailbox,
+        which: DefinitionSources,
+    ) -> Vec<Location> {
+        let found = self
+            .sources
+            .iter()
+            .zip(&self.priorities)
+            .map(|(s, priority)| (s.locations(mailbox), *priority))
+            .collect::<Vec<_>>();
+        let has_contact = |locations: &[Location]| locations.iter().any(|l| l.line.is_some());
+        let top = found
+            .iter()
+            .filter(|(l, _)| has_contact(l))
+            .map(|(_, priority)| *priority)
+            .max();
+        let picked = match (which, top) {
+            (DefinitionSources::All, _) | (_, None) => found,
+            (DefinitionSources::First, Some(_)) => found
+                .into_iter()
+                .filter(|(l, _)| has_contact(l))
+                .take(1)
+                .collect(),
+            (DefinitionSources::Priority, Some(top)) => found
+                .into_iter()
+                .filter(|(l, priority)| has_contact(l) && *priority == top)
+                .collect(),
+        };
+        picked.into_iter().flat_map(|(l, _)| l).collect()
+    }
+
+    pub fn group_locations(&self, mailbox: &Mailbox) -> Vec<Location> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.group_locations(mailbox))
+            .collect()
+    }
+
+    pub fn uid_locations(&self, uid: &str) -> Vec<Location> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.uid_locations(uid))
+            .collect()
+    }
+}
+
+/// The modification time and size of a file when it was loaded, to tell whether it has changed
+/// since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let metadata = metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Contacts that conflict with each other, e.g. cards sharing a `UID`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub message: String,
+    /// Where each of the conflicting contacts is.
+    pub locations: Vec<Location>,
+}
+
+/// How to merge the contacts of a conflict, deciding which of them the others are merged into.
+///
+/// The contact merged into keeps its values of properties a contact can only have one of, such as
+/// its structured name and `UID`, and gets the other properties of the rest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Into the first of them.
+    #[default]
+    Union,
+    /// Into the one revised last, by its `REV`.
+    Newest,
+    /// Into the first under the preferred path, e.g. the directory of the account that is kept
+    /// most up to date.
+    PreferPath,
+    /// Into the one the user picks for each conflict.
+    Prompt,
+}
+
+impl MergeStrategy {
+    /// Which contact to merge into, `None` if the user has to be asked.
+    pub fn into(self, preferred_path: Option<&Path>) -> Option<MergeInto> {
+        match self {
+            Self::Union => Some(MergeInto::First),
+            Self::Newest => Some(MergeInto::Newest),
+            Self::PreferPath => {
+                Some(preferred_path.map_or(MergeInto::First, |p| MergeInto::Under(p.to_owned())))
             }
+            Self::Prompt => None,
+        }
+    }
+}
+
+/// Which of the contacts of a conflict the others are merged into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeInto {
+    First,
+    /// The one with the newest `REV`, or the first if none have one.
+    Newest,
+    /// The first in a file under the path, or the first if none are.
+    Under(PathBuf),
+    /// The one at the index of the conflict's locations.
+    Location(usize),
+}
+
+/// Mail sent to or from an address, as far as a source knows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    /// Date of the latest message.
+    pub last: Option<NaiveDate>,
+    /// Number of messages.
+    pub messages: Option<u64>,
+}
+
+impl Usage {
+    /// How frequently and recently the address was mailed, higher is more.
+    pub fn frecency(&self, today: NaiveDate) -> u64 {
+        crate::usage::frecency(self.messages.unwrap_or(0), self.last, today)
+    }
+
+    /// Combine what two sources know, taking the latest date and the most messages as sources
+    /// may count the same ones.
+    pub fn combine(self, other: Self) -> Self {
+        Self {
+            last: self.last.max(other.last),
+            messages: self.messages.max(other.messages),
         }
-        None
     }
 }
 
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let last = self
+            .last
+            .map(|d| format!("Last emailed: {}", d.format("%Y-%m-%d")));
+        let messages = self.messages.map(|n| match n {
+            1 => "1 message".to_owned(),
+            n => format!("{n} messages"),
+        });
+        write!(f, "{}", last.into_iter().chain(messages).join(" · "))
+    }
+}
+
+/// A postal address of a contact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostalAddress {
+    /// The first `TYPE` of the address, e.g. `home` or `work`.
+    pub kind: Option<String>,
+    /// The lines of the address as it goes on an envelope, starting with the contact's name.
+    pub lines: Vec<String>,
+}
+
+/// A mailbox found by a source for a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Name of the source that found the mailbox.
+    pub source: String,
+    /// Kind to show the completion item as, distinguishing sources in editors.
+    pub kind: CompletionItemKind,
+    pub mailbox: Mailbox,
+    /// Other names the contact is known by, such as nicknames.
+    pub aliases: Vec<String>,
+    /// All email addresses of the contact, most preferred first.
+    pub addresses: Vec<String>,
+    /// The members of a group, to be expanded when completed.
+    pub members: Vec<Mailbox>,
+}
+
+impl Match {
+    fn names(&self) -> Vec<&str> {
+        self.mailbox
+            .name
+            .iter()
+            .chain(&self.aliases)
+            .map(|n| n.as_str())
+            .collect()
+    }
+
+    /// Whether this match still matches the given query.
+    pub fn matches(&self, matcher: &Matcher) -> bool {
+        matcher.matches(&self.names(), &[&self.mailbox.email])
+    }
+
+    /// How well this matches the given query, lower is better.
+    pub fn score(&self, matcher: &Matcher) -> u32 {
+        matcher.score(&self.names(), &[&self.mailbox.email])
+    }
+}
+
+/// Sort matches into a stable order: by score, then name, then email.
+pub fn sort_matches(matches: &mut [Match], matcher: &Matcher) {
+    sort_matches_by_frecency(matches, matcher, |_| 0);
+}
+
+/// Sort matches by score, then highest frecency, then name, then email.
+pub fn sort_matches_by_frecency(
+    matches: &mut [Match],
+    matcher: &Matcher,
+    frecency: impl Fn(&Match) -> u64,
+) {
+    matches.sort_by_cached_key(|m| {
+        (
+            m.score(matcher),
+            std::cmp::Reverse(frecency(m)),
+            m.mailbox.name.as_deref().map(|n| fold(n, false)),
+            fold(&m.mailbox.email, false),
+        )
+    });
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
     pub path: PathBuf,
     pub line: Option<u32>,
+    /// Start and end character of the contact in the line.
+    pub columns: Option<(u32, u32)>,
 }
 
-impl From<Location> for lsp_types::Location {
-    fn from(value: Location) -> Self {
-        lsp_types::Location {
-            uri: Url::from_file_path(value.path).unwrap(),
+impl TryFrom<Location> for lsp_types::Location {
+    type Error = String;
+
+    /// Fails for relative paths, which have no URL.
+    fn try_from(value: Location) -> Result<Self, Self::Error> {
+        Ok(lsp_types::Location {
+            uri: Url::from_file_path(&value.path)
+                .map_err(|()| format!("{} is not an absolute path", value.path.display()))?,
             range: if let Some(line) = value.line {
+                let (start, end) = value.columns.unwrap_or_default();
                 lsp_types::Range {
-                    start: lsp_types::Position { line, character: 0 },
-                    end: lsp_types::Position { line, character: 0 },
+                    start: lsp_types::Position {
+                        line,
+                        character: start,
+                    },
+                    end: lsp_types::Position {
+                        line,
+                        character: end,
+                    },
                 }
             } else {
                 lsp_types::Range::default()
             },
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContactList;
+    use crate::TestDir;
+    use std::str::FromStr;
+
+    #[test]
+    fn scoped_sources() {
+        let dir = TestDir::new();
+        let mut sources = Sources::default();
+        sources.add(
+            Box::new(dir.contact_list("work", "Bob Smith bob@work.example\n")),
+            1,
+            0,
+            Some("/work".into()),
+        );
+        sources.add(
+            Box::new(dir.contact_list("home", "Jane Doe jane@example.com\n")),
+            0,
+            0,
+            None,
+        );
+        assert_eq!(sources.len(), 2);
+
+        let work = sources.for_document(Some(Path::new("/work/mail/draft.eml")));
+        assert!(work.contains("bob@work.example"));
+        assert!(work.contains("jane@example.com"));
+        let elsewhere = sources.for_document(Some(Path::new("/tmp/draft.eml")));
+        assert!(!elsewhere.contains("bob@work.example"));
+        assert!(elsewhere.contains("jane@example.com"));
+        assert!(sources.contains("bob@work.example"));
+
+        assert!(sources.remove_folder(Path::new("/work")));
+        assert_eq!(sources.len(), 1);
+    }
+
+    #[test]
+    fn ranked_by_usage() {
+        let dir = TestDir::new();
+        let path = dir.join("list");
+        std::fs::write(
+            &path,
+            "Jo Bloggs jo@example.com\nJo Smith jo@work.example\n",
+        )
+        .unwrap();
+        let mut sources = Sources::default();
+        sources.add(Box::new(ContactList::new(path, true)), 0, 0, None);
+        let log = Arc::new(UsageLog::load(dir.join("usage.db")).unwrap());
+        sources.set_usage_log(log.clone());
+        let emails = |sources: &Sources| {
+            sources
+                .all()
+                .find_matching(&Matcher::new("jo", MatchOptions::default()))
+                .into_iter()
+                .map(|m| m.mailbox.email)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(emails(&sources), vec!["jo@example.com", "jo@work.example"]);
+
+        let today = Local::now().date_naive();
+        log.record("jo@work.example", crate::UsageKind::Sent, today);
+        assert_eq!(emails(&sources), vec!["jo@work.example", "jo@example.com"]);
+        assert_eq!(
+            sources.usage("jo@work.example"),
+            Some(Usage {
+                last: Some(today),
+                messages: Some(1),
+            })
+        );
+
+        // weights from elsewhere add to those of the usage log
+        let mut weights = AddressWeights::default();
+        weights.insert(
+            "jo@example.com",
+            Usage {
+                last: Some(today),
+                messages: Some(5),
+            },
+        );
+        sources.set_weights("notmuch", weights);
+        assert_eq!(emails(&sources), vec!["jo@example.com", "jo@work.example"]);
+    }
+
+    #[test]
+    fn merged_contacts() {
+        let dir = TestDir::new();
+        let mut sources = Sources::default();
+        sources.add(
+            Box::new(dir.contact_list(
+                "second",
+                "J Doe JANE@example.com\nBob Smith bob@example.com\n",
+            )),
+            1,
+            0,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("first", "Jane Doe jane@example.com\n")),
+            0,
+            0,
+            None,
+        );
+
+        let contacts = sources
+            .distinct_contacts()
+            .into_iter()
+            .map(|m| (m.source, m.mailbox.email))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contacts,
+            [
+                ("second".to_owned(), "bob@example.com".to_owned()),
+                ("first".to_owned(), "jane@example.com".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn labelled_renders() {
+        let dir = TestDir::new();
+        let mut sources = Sources::default();
+        sources.add(
+            Box::new(dir.contact_list("work", "Jane Doe jane@example.com\n")),
+            0,
+            0,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("home", "Bob Smith bob@example.com\n")),
+            1,
+            0,
+            None,
+        );
+
+        let mailbox = Mailbox::from_str("Jane Doe <jane@example.com>").unwrap();
+        let render = sources.for_document(None).render(&mailbox);
+        let heading = format!("*work* `{}`", dir.join("work").display());
+        assert!(
+            render.starts_with(&format!("{}\n\n", heading)),
+            "{}",
+            render
+        );
+        assert!(!render.contains("*home*"), "{}", render);
+
+        let mailbox = Mailbox::from_str("nobody@example.com").unwrap();
+        assert_eq!(sources.for_document(None).render(&mailbox), "");
+
+        let (matches, timings) = sources
+            .for_document(None)
+            .find_matching_timed(&Matcher::new("", MatchOptions::default()));
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            timings.iter().map(|(l, _)| l.as_str()).collect::<Vec<_>>(),
+            vec!["work", "home"]
+        );
+
+        let options = MatchOptions::default();
+        let found = sources.for_document(None).find_by_name("jane doe", options);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].mailbox.email, "jane@example.com");
+        assert!(sources
+            .for_document(None)
+            .find_by_name("Jane", options)
+            .is_empty());
+    }
+
+    #[test]
+    fn definition_sources() {
+        let dir = TestDir::new();
+        let mut sources = Sources::default();
+        sources.add(
+            Box::new(dir.contact_list("curated", "Jane Doe jane@example.com\n")),
+            0,
+            1,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("other", "Bob bob@example.com\n")),
+            1,
+            1,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("history", "Jane Doe jane@example.com\n")),
+            2,
+            0,
+            None,
+        );
+        let sources = sources.for_document(None);
+        let files = |mailbox: &str, which| {
+            sources
+                .definition_locations(&Mailbox::from_str(mailbox).unwrap(), which)
+                .into_iter()
+                .map(|l| l.path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        let jane = "Jane Doe <jane@example.com>";
+        assert_eq!(
+            files(jane, DefinitionSources::All),
+            ["curated", "other", "history"]
+        );
+        assert_eq!(files(jane, DefinitionSources::First), ["curated"]);
+        assert_eq!(files(jane, DefinitionSources::Priority), ["curated"]);
+        // nothing knows the contact, so every source's file is given as before
+        assert_eq!(
+            files("nobody@example.com", DefinitionSources::First).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn write_sources() {
+        let dir = TestDir::new();
+        let mut sources = Sources::default();
+        sources.add(
+            Box::new(
+                dir.contact_list("sent", "")
+                    .with_writable(true)
+                    .with_harvest(true),
+            ),
+            0,
+            0,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("curated", "").with_writable(true)),
+            1,
+            0,
+            None,
+        );
+        sources.add(
+            Box::new(dir.contact_list("work", "").with_writable(true)),
+            2,
+            0,
+            None,
+        );
+
+        let jane = Mailbox::from_str("Jane Doe <jane@example.com>").unwrap();
+        assert_eq!(sources.write_targets(None)[0].1, "curated");
+        let (source, _) = sources.create_contact_for(None, jane, None).unwrap();
+        assert_eq!(source, "curated");
+        sources.set_write_source(Some(2));
+        assert_eq!(
+            sources.write_targets(None),
+            vec![(2, "work".to_owned()), (1, "curated".to_owned())]
+        );
+        let ann = Mailbox::from_str("ann@example.com").unwrap();
+        let (source, _) = sources.create_contact_at(1, None, ann, None).unwrap();
+        assert_eq!(source, "curated");
+        let al = Mailbox::from_str("al@example.com").unwrap();
+        let (source, _) = sources.create_contact_for(None, al, None).unwrap();
+        assert_eq!(source, "work");
+        let bob = Mailbox::from_str("bob@example.com").unwrap();
+        let (source, location) = sources.harvest_for(None, bob).unwrap();
+        assert_eq!(source, "sent");
+        assert_eq!(location.path, dir.join("sent"));
+        assert!(sources.contains("bob@example.com"));
+        // a source that only harvests is never written to otherwise
+        sources.set_write_source(Some(0));
+        assert_eq!(
+            sources.write_targets(None),
+            vec![(1, "curated".to_owned()), (2, "work".to_owned())]
+        );
+
+        let quiet = dir.join("quiet");
+        std::fs::write(&quiet, "Eve eve@example.com\n").unwrap();
+        sources.add(Box::new(ContactList::new(quiet, false)), 3, 0, None);
+        assert!(!sources.contains("eve@example.com"));
+        assert!(sources.for_document(None).has_contact("EVE@example.com"));
     }
 }
@@ -0,0 +1,258 @@
+use std::{
+    fs::{create_dir_all, rename, File},
+    io::{self, BufWriter, Write as _},
+    path::Path,
+};
+
+use memmap::Mmap;
+
+use crate::{fold, MatchMode, Matcher};
+
+const MAGIC: &[u8; 8] = b"MAILLSI1";
+/// Line, column, offset and length of each record.
+const ENTRY_SIZE: usize = 16;
+/// Entry, start and end of each suffix.
+const SUFFIX_SIZE: usize = 12;
+
+/// A record to store in a [`DiskIndex`].
+pub struct IndexRecord<'a> {
+    pub line: u32,
+    pub column: u32,
+    /// The text of the record, parsed again when it's looked up.
+    pub text: &'a str,
+    /// The names and email to find the record by.
+    pub fields: Vec<&'a str>,
+}
+
+/// A search index kept in a file and memory mapped, for sources too large to parse on every start
+/// or to hold in memory.
+///
+/// Like [`SearchIndex`](crate::SearchIndex) it holds the sorted suffixes of the folded fields of
+/// each record, along with the text of the records themselves.
+/// The file is tagged with the state of the source it was built from, so a stale one is rebuilt.
+pub struct DiskIndex {
+    mmap: Mmap,
+    entries: usize,
+    suffixes: usize,
+    entries_start: usize,
+    suffixes_start: usize,
+    records_start: usize,
+    keys_start: usize,
+}
+
+impl DiskIndex {
+    /// Open the index at the path, if it exists and was built with the same tag.
+    pub fn open(path: &Path, tag: &str) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        // safety: the file is only ever replaced, never written in place
+        let mmap = unsafe { Mmap::map(&file) }.ok()?;
+        let header_len = MAGIC.len() + 4 + tag.len() + 16;
+        if mmap.len() < header_len
+            || &mmap[..MAGIC.len()] != MAGIC
+            || read_u32(&mmap, MAGIC.len()) as usize != tag.len()
+            || &mmap[MAGIC.len() + 4..][..tag.len()] != tag.as_bytes()
+        {
+            return None;
+        }
+        let counts = header_len - 16;
+        let entries = read_u32(&mmap, counts) as usize;
+        let suffixes = read_u32(&mmap, counts + 4) as usize;
+        let records_len = read_u32(&mmap, counts + 8) as usize;
+        let keys_len = read_u32(&mmap, counts + 12) as usize;
+        let entries_start = header_len;
+        let suffixes_start = entries_start + entries * ENTRY_SIZE;
+        let records_start = suffixes_start + suffixes * SUFFIX_SIZE;
+        let keys_start = records_start + records_len;
+        if mmap.len() != keys_start + keys_len {
+            return None;
+        }
+        Some(Self {
+            mmap,
+            entries,
+            suffixes,
+            entries_start,
+            suffixes_start,
+            records_start,
+            keys_start,
+        })
+    }
+
+    /// Build the index for the records and write it to the path, replacing any existing one.
+    pub fn build<'a>(
+        path: &Path,
+        tag: &str,
+        records: impl IntoIterator<Item = IndexRecord<'a>>,
+    ) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        let mut texts = String::new();
+        let mut keys = String::new();
+        let mut suffixes = Vec::new();
+        for (entry, record) in records.into_iter().enumerate() {
+            entries.push((
+                record.line,
+                record.column,
+                texts.len() as u32,
+                record.text.len() as u32,
+            ));
+            texts.push_str(record.text);
+            for field in record.fields {
+                let start = keys.len();
+                keys.push_str(&fold(field, true));
+                let end = keys.len() as u32;
+                suffixes.extend(
+                    keys[start..]
+                        .char_indices()
+                        .map(|(offset, _)| (entry as u32, (start + offset) as u32, end)),
+                );
+            }
+        }
+        suffixes.sort_unstable_by(|a, b| {
+            keys[a.1 as usize..a.2 as usize].cmp(&keys[b.1 as usize..b.2 as usize])
+        });
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        // write alongside and move it into place so an open index is never modified
+        let partial = path.with_extension("partial");
+        let mut out = BufWriter::new(File::create(&partial)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&(tag.len() as u32).to_le_bytes())?;
+        out.write_all(tag.as_bytes())?;
+        for count in [entries.len(), suffixes.len(), texts.len(), keys.len()] {
+            out.write_all(&(count as u32).to_le_bytes())?;
+        }
+        for (line, column, offset, len) in entries {
+            for value in [line, column, offset, len] {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+        for (entry, start, end) in suffixes {
+            for value in [entry, start, end] {
+                out.write_all(&value.to_le_bytes())?;
+            }
+        }
+        out.write_all(texts.as_bytes())?;
+        out.write_all(keys.as_bytes())?;
+        out.into_inner()?.sync_all()?;
+        rename(&partial, path)?;
+        Self::open(path, tag)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "index not readable"))
+    }
+
+    /// The number of records in the index.
+    pub fn len(&self) -> usize {
+        self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    /// The line, column and text of a record.
+    pub fn record(&self, entry: usize) -> (u32, u32, &str) {
+        let at = self.entries_start + entry * ENTRY_SIZE;
+        let offset = self.records_start + read_u32(&self.mmap, at + 8) as usize;
+        let len = read_u32(&self.mmap, at + 12) as usize;
+        (
+            read_u32(&self.mmap, at),
+            read_u32(&self.mmap, at + 4),
+            std::str::from_utf8(&self.mmap[offset..offset + len]).unwrap_or_default(),
+        )
+    }
+
+    /// The records that may match the query, in ascending order, or `None` when the index can't
+    /// narrow them down and all records need checking.
+    pub fn candidates(&self, matcher: &Matcher) -> Option<Vec<usize>> {
+        if matcher.options().mode == MatchMode::Fuzzy {
+            return None;
+        }
+        let token = matcher.tokens().iter().max_by_key(|t| t.len())?;
+        Some(self.containing(&fold(token, true)))
+    }
+
+    /// The records with a field containing the folded text, in ascending order.
+    pub fn containing(&self, text: &str) -> Vec<usize> {
+        // binary search for the first suffix not before the text
+        let (mut low, mut high) = (0, self.suffixes);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.suffix(mid) < text.as_bytes() {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let mut entries = (low..self.suffixes)
+            .take_while(|s| self.suffix(*s).starts_with(text.as_bytes()))
+            .map(|s| read_u32(&self.mmap, self.suffixes_start + s * SUFFIX_SIZE) as usize)
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+        entries.dedup();
+        entries
+    }
+
+    fn suffix(&self, suffix: usize) -> &[u8] {
+        let at = self.suffixes_start + suffix * SUFFIX_SIZE;
+        let start = read_u32(&self.mmap, at + 4) as usize;
+        let end = read_u32(&self.mmap, at + 8) as usize;
+        &self.mmap[self.keys_start + start..self.keys_start + end]
+    }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchOptions;
+    use crate::TestDir;
+
+    #[test]
+    fn build_and_open() {
+        let dir = TestDir::new();
+        let path = dir.join("contacts.idx");
+        let records = [
+            ("Jane Doe jane@example.com", "Jane Doe", "jane@example.com"),
+            (
+                "José García jose@example.org",
+                "José García",
+                "jose@example.org",
+            ),
+            ("Mary Jane mj@example.net", "Mary Jane", "mj@example.net"),
+        ];
+        let index = DiskIndex::build(
+            &path,
+            "v1",
+            records
+                .iter()
+                .enumerate()
+                .map(|(i, (text, name, email))| IndexRecord {
+                    line: i as u32,
+                    column: 0,
+                    text,
+                    fields: vec![name, email],
+                }),
+        )
+        .unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.record(1), (1, 0, "José García jose@example.org"));
+        assert_eq!(index.containing("jane"), vec![0, 2]);
+        assert_eq!(index.containing("garc"), vec![1]);
+        assert_eq!(index.containing("nobody"), Vec::<usize>::new());
+        let matcher = Matcher::new(
+            "example.n",
+            MatchOptions {
+                mode: MatchMode::Substring,
+                strip_diacritics: true,
+            },
+        );
+        assert_eq!(index.candidates(&matcher), Some(vec![2]));
+
+        let reopened = DiskIndex::open(&path, "v1").unwrap();
+        assert_eq!(reopened.record(2).2, "Mary Jane mj@example.net");
+        assert!(DiskIndex::open(&path, "v2").is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::expand_path;
+
+/// Which documents maills is active in, by the paths of their files, e.g. only those under
+/// `~/mail/drafts/**` and never those under `~/src/**`.
+///
+/// Patterns are globs where `**` matches any number of directories, `*` anything within a file or
+/// directory name and `?` any single character of one.
+/// A leading `~` and environment variables are expanded, then absolute patterns match the whole
+/// path and relative ones its end, e.g. `*.rs` or `drafts/*`.
+#[derive(Debug, Clone, Default)]
+pub struct DocumentFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl DocumentFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            include: patterns(include)?,
+            exclude: patterns(exclude)?,
+        })
+    }
+
+    /// Whether maills is active for the document at the path, or for one that isn't a file for
+    /// `None`.
+    ///
+    /// Documents are included when they match any of the include patterns, or when there are
+    /// none, and aren't excluded by any of the exclude patterns.
+    pub fn includes(&self, path: Option<&Path>) -> bool {
+        let Some(path) = path else {
+            return self.include.is_empty();
+        };
+        let path = path.to_string_lossy().replace('\\', "/");
+        let matches = |pattern: &Regex| pattern.is_match(&path);
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+fn patterns(globs: &[String]) -> Result<Vec<Regex>, String> {
+    globs
+        .iter()
+        .map(|glob| {
+            let expanded = expand_path(Path::new(glob))?;
+            let regex = glob_to_regex(
+                &expanded.to_string_lossy().replace('\\', "/"),
+                expanded.is_absolute(),
+            );
+            Regex::new(&regex).map_err(|err| format!("Invalid pattern {glob:?}: {err}"))
+        })
+        .collect()
+}
+
+fn glob_to_regex(glob: &str, absolute: bool) -> String {
+    let mut regex = String::from(if absolute { "^" } else { "(^|/)" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // `a/**/b` also matches `a/b`
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes() {
+        let filter = DocumentFilter::new(
+            &["/home/jane/mail/**".to_owned(), "*.eml".to_owned()],
+            &["**/*.json".to_owned()],
+        )
+        .unwrap();
+        let includes = |path: &str| filter.includes(Some(Path::new(path)));
+        assert!(includes("/home/jane/mail/drafts/reply"));
+        assert!(includes("/tmp/message.eml"));
+        assert!(!includes("/home/jane/mail/drafts/config.json"));
+        assert!(!includes("/home/jane/mail/config.json"));
+        assert!(!includes("/home/jane/src/main.rs"));
+        assert!(!includes("/home/jane/mail.eml.bak"));
+        assert!(!filter.includes(None));
+
+        let filter = DocumentFilter::new(&[], &["/home/*/src/**".to_owned()]).unwrap();
+        assert!(!filter.includes(Some(Path::new("/home/jane/src/a/main.rs"))));
+        assert!(filter.includes(Some(Path::new("/home/jane/notes/src/todo.md"))));
+        assert!(filter.includes(None));
+    }
+}
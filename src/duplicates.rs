@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools as _;
+use serde::{Deserialize, Serialize};
+
+use crate::{fold, ContactSource, Location, Mailbox};
+
+/// An address that is in more than one source, or that contacts give different names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Duplicate {
+    pub email: String,
+    /// The names of the contacts with the address, each once.
+    pub names: Vec<String>,
+    /// The labels of the sources with the address, in their order.
+    pub sources: Vec<String>,
+    /// Where the address is in each of the sources.
+    pub locations: Vec<Location>,
+}
+
+impl Duplicate {
+    /// A summary, e.g. `jane@example.com is in work and home, named Jane Doe and Jane`.
+    pub fn message(&self) -> String {
+        let mut message = self.email.clone();
+        if self.sources.len() > 1 {
+            message.push_str(&format!(" is in {}", list(&self.sources)));
+        }
+        if self.names.len() > 1 {
+            if self.sources.len() > 1 {
+                message.push(',');
+            } else {
+                message.push_str(" is");
+            }
+            message.push_str(&format!(" named {}", list(&self.names)));
+        }
+        message
+    }
+}
+
+/// The items joined by commas, with `and` before the last.
+fn list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// What the sources have for an address.
+#[derive(Default)]
+struct Found {
+    email: String,
+    names: Vec<String>,
+    /// Indices of the sources with the address.
+    sources: Vec<usize>,
+}
+
+/// Addresses that are in more than one of the sources, or that contacts give different names, by
+/// address.
+///
+/// Sources are labelled by [`ContactSource::label`], or by their position if they have none.
+pub fn find_duplicates(sources: &[&dyn ContactSource]) -> Vec<Duplicate> {
+    let mut found = BTreeMap::<String, Found>::new();
+    for (i, source) in sources.iter().enumerate() {
+        for contact in source.contacts() {
+            for address in contact.emails().unique_by(|a| fold(a, false)) {
+                let entry = found.entry(fold(address, false)).or_default();
+                if entry.email.is_empty() {
+                    entry.email = address.to_owned();
+                }
+                if entry.sources.last() != Some(&i) {
+                    entry.sources.push(i);
+                }
+                if let Some(name) = contact.name().filter(|n| !n.trim().is_empty()) {
+                    if !entry
+                        .names
+                        .iter()
+                        .any(|n| fold(n, true) == fold(name, true))
+                    {
+                        entry.names.push(name.to_owned());
+                    }
+                }
+            }
+        }
+    }
+    found
+        .into_values()
+        .filter(|f| f.sources.len() > 1 || f.names.len() > 1)
+        .map(|f| {
+            let mailbox = Mailbox {
+                name: None,
+                email: f.email.clone(),
+            };
+            Duplicate {
+                sources: f
+                    .sources
+                    .iter()
+                    .map(|i| match sources[*i].label() {
+                        "" => format!("source {}", i + 1),
+                        label => label.to_owned(),
+                    })
+                    .collect(),
+                locations: f
+                    .sources
+                    .iter()
+                    .flat_map(|i| sources[*i].locations(&mailbox))
+                    .collect(),
+                email: f.email,
+                names: f.names,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    #[test]
+    fn across_sources() {
+        let dir = TestDir::new();
+        let work = dir.contact_list(
+            "work",
+            "Jane Doe jane@example.com\nBob Smith bob@example.com\nAl al@example.com\n",
+        );
+        let home = dir.contact_list(
+            "home",
+            "jane doe jane@example.com\nRobert Smith bob@example.com\n",
+        );
+        let duplicates = find_duplicates(&[&work, &home]);
+        assert_eq!(
+            duplicates.iter().map(|d| d.message()).collect::<Vec<_>>(),
+            vec![
+                "bob@example.com is in work and home, named Bob Smith and Robert Smith",
+                "jane@example.com is in work and home",
+            ]
+        );
+        assert_eq!(duplicates[1].locations.len(), 2);
+
+        let names = dir.contact_list("names", "Al al@example.com\nAlan al@example.com\n");
+        assert_eq!(
+            find_duplicates(&[&names])
+                .iter()
+                .map(|d| d.message())
+                .collect::<Vec<_>>(),
+            vec!["al@example.com is named Al and Alan"]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs::File,
+    io::Write as _,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Commands to decrypt and encrypt contact files, e.g. with gpg or age.
+///
+/// Plaintext is only ever kept in memory: files are piped through the commands on stdin and
+/// stdout.
+#[derive(Debug, Clone)]
+pub struct Encryption {
+    /// Extensions of encrypted files, e.g. `gpg` for `contact.vcf.gpg`.
+    pub extensions: Vec<String>,
+    /// Command reading an encrypted file on stdin and writing the plaintext to stdout, e.g.
+    /// `["gpg", "--decrypt", "--quiet"]`.
+    pub decrypt_command: Vec<String>,
+    /// Command reading plaintext on stdin and writing it encrypted to stdout, e.g.
+    /// `["gpg", "--encrypt", "--recipient", "me@example.com"]`.
+    pub encrypt_command: Option<Vec<String>>,
+    /// Whether new contacts are written encrypted.
+    pub encrypt_new: bool,
+}
+
+impl Encryption {
+    /// Whether the file is encrypted, going by its extension.
+    pub fn is_encrypted(&self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        self.extensions
+            .iter()
+            .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+
+    /// The extension to give new encrypted files.
+    pub fn extension(&self) -> &str {
+        self.extensions
+            .first()
+            .map_or("gpg", |e| e.trim_start_matches('.'))
+    }
+
+    /// Decrypt the file.
+    pub fn decrypt(&self, path: &Path) -> Result<String, String> {
+        let input = File::open(path).map_err(|err| err.to_string())?;
+        let output = command(&self.decrypt_command)?
+            .stdin(input)
+            .output()
+            .map_err(|err| err.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+        }
+        String::from_utf8(output.stdout).map_err(|err| err.to_string())
+    }
+
+    /// Encrypt the plaintext.
+    pub fn encrypt(&self, plaintext: &str) -> Result<Vec<u8>, String> {
+        let Some(encrypt_command) = &self.encrypt_command else {
+            return Err("No command to encrypt with is configured".to_owned());
+        };
+        let mut child = command(encrypt_command)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| err.to_string())?;
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(plaintext.as_bytes())
+            .map_err(|err| err.to_string())?;
+        let output = child.wait_with_output().map_err(|err| err.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+        }
+        Ok(output.stdout)
+    }
+}
+
+fn command(args: &[String]) -> Result<Command, String> {
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| "Empty encryption command".to_owned())?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    #[test]
+    fn round_trip() {
+        // rot13 stands in for a real cipher
+        let rot13 = vec![
+            "tr".to_owned(),
+            "A-Za-z".to_owned(),
+            "N-ZA-Mn-za-m".to_owned(),
+        ];
+        let encryption = Encryption {
+            extensions: vec!["rot13".to_owned()],
+            decrypt_command: rot13.clone(),
+            encrypt_command: Some(rot13),
+            encrypt_new: true,
+        };
+        let dir = TestDir::new();
+        let path = dir.join("jane.vcf.rot13");
+        assert!(encryption.is_encrypted(&path));
+
+        let encrypted = encryption.encrypt("FN:Jane Doe").unwrap();
+        assert_eq!(encrypted, b"SA:Wnar Qbr");
+        std::fs::write(&path, encrypted).unwrap();
+        assert_eq!(encryption.decrypt(&path).unwrap(), "FN:Jane Doe");
+    }
+}
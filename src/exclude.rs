@@ -0,0 +1,65 @@
+use regex::{Regex, RegexBuilder};
+
+/// Patterns of email addresses to leave out of a source, e.g. `noreply@*`.
+///
+/// Patterns are globs matching the whole address, where `*` matches anything and `?` any single
+/// character, or regexes when prefixed with `re:`, e.g. `re:^(bounce|mailer-daemon)`.
+/// Matching ignores case.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludePatterns {
+    patterns: Vec<Regex>,
+}
+
+impl ExcludePatterns {
+    pub fn new(patterns: &[String]) -> Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                let regex = match pattern.strip_prefix("re:") {
+                    Some(regex) => regex.to_owned(),
+                    None => glob_to_regex(pattern),
+                };
+                RegexBuilder::new(&regex).case_insensitive(true).build()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Whether the email should be left out.
+    pub fn excludes(&self, email: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(email))
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn excludes() {
+        let exclude = ExcludePatterns::new(&[
+            "noreply@*".to_owned(),
+            "*@tickets.example.com".to_owned(),
+            "re:^bounce[+-]".to_owned(),
+        ])
+        .unwrap();
+        assert!(exclude.excludes("NoReply@github.com"));
+        assert!(exclude.excludes("1234@tickets.example.com"));
+        assert!(exclude.excludes("bounce-abc@example.com"));
+        assert!(!exclude.excludes("jane@example.com"));
+        assert!(!exclude.excludes("jane.noreply@example.com"));
+    }
+}
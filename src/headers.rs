@@ -0,0 +1,410 @@
+use std::{ops::Range, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::Mailbox;
+
+static ADDRESS_HEADER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(to|cc|bcc|from|sender|reply-to|resent-to|resent-cc|resent-bcc)\s*:").unwrap()
+});
+
+/// Whether the line is an email header that holds a list of addresses, e.g. `To:` or `Cc:`.
+pub fn is_address_header(line: &str) -> bool {
+    ADDRESS_HEADER_REGEX.is_match(line)
+}
+
+static FROM_HEADER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(resent-)?from\s*:").unwrap());
+
+/// Whether the line is the `From:` header, with the address mail is sent from.
+pub fn is_from_header(line: &str) -> bool {
+    FROM_HEADER_REGEX.is_match(line)
+}
+
+static RECIPIENT_HEADER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(resent-)?(to|cc|bcc)\s*:").unwrap());
+
+/// The lines of a message's `To:`, `Cc:` and `Bcc:` headers, including their folded continuation
+/// lines.
+///
+/// Headers end at the first empty line.
+fn recipient_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut in_recipients = false;
+    text.lines()
+        .take_while(|l| !l.trim().is_empty())
+        .filter(move |line| {
+            if !line.starts_with([' ', '\t']) {
+                in_recipients = RECIPIENT_HEADER_REGEX.is_match(line);
+            }
+            in_recipients
+        })
+}
+
+/// The addresses a message is sent to, from its `To:`, `Cc:` and `Bcc:` headers, including
+/// their folded continuation lines.
+///
+/// Headers end at the first empty line.
+pub fn recipient_addresses(text: &str) -> Vec<&str> {
+    recipient_lines(text)
+        .flat_map(|line| crate::find_addresses(line).map(|(_, _, address)| address))
+        .collect()
+}
+
+/// The mailboxes a message is sent to, like [`recipient_addresses`] with the names they have.
+pub fn recipient_mailboxes(text: &str) -> Vec<Mailbox> {
+    recipient_lines(text)
+        .flat_map(|line| {
+            crate::find_addresses(line).map(|(start, _, address)| {
+                let name = Mailbox::span_at(line, start)
+                    .and_then(|(_, _, mailbox)| mailbox.name)
+                    .filter(|name| !name.trim().is_empty());
+                Mailbox {
+                    name,
+                    email: address.to_owned(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// The number of lines of headers the message starts with, up to the first empty line.
+pub fn header_end(text: &str) -> usize {
+    text.lines()
+        .position(|l| l.trim().is_empty())
+        .unwrap_or_else(|| text.lines().count())
+}
+
+/// The trailers of commit messages that name people, completed and checked by default.
+pub const DEFAULT_TRAILERS: [&str; 5] = [
+    "Signed-off-by",
+    "Reviewed-by",
+    "Acked-by",
+    "Co-authored-by",
+    "Cc",
+];
+
+/// The character the value of the line starts at, if it is one of the named trailers of a commit
+/// message, e.g. `Signed-off-by: Jane <jane@a.com>`, ignoring case.
+pub fn trailer_value_start(line: &str, names: &[String]) -> Option<usize> {
+    let (name, _) = line.split_once(':')?;
+    names
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(name))
+        .then(|| name.chars().count() + 1)
+}
+
+/// Get the query ending at `character` in the value of one of the named trailers, along with the
+/// character it starts at.
+///
+/// Trailers hold a single mailbox, so the query is all of the value up to the cursor, without
+/// any quoting or angle brackets.
+pub fn trailer_query_at(line: &str, character: usize, names: &[String]) -> Option<(usize, String)> {
+    let value_start = trailer_value_start(line, names)?;
+    let value = line
+        .chars()
+        .take(character + 1)
+        .skip(value_start)
+        .collect::<String>();
+    let entry = value.trim_start();
+    let query = entry
+        .trim_start_matches(['"', '<'])
+        .replace(['"', '<', '>'], "");
+    if query.trim().is_empty() {
+        None
+    } else {
+        let start = value_start + value.chars().count() - entry.chars().count();
+        Some((start, query))
+    }
+}
+
+/// The lines of each message in the text: all of them, or for an mbox file, those from each
+/// `From ` line at its start or after an empty line up to the next.
+pub fn messages(text: &str, mbox: bool) -> Vec<Range<usize>> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut starts = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, l)| {
+            mbox && l.starts_with("From ") && (*i == 0 || lines[i - 1].trim().is_empty())
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    // anything before the first `From ` line is a message of its own
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&lines.len()]))
+        .map(|(start, end)| *start..*end)
+        .collect()
+}
+
+/// The lines of the last paragraph of a commit message, where its trailers are, leaving out
+/// git's `#` comments.
+///
+/// The first paragraph is the subject and never has trailers.
+pub fn trailer_lines(text: &str) -> Range<usize> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let is_blank = |l: &&str| l.trim().is_empty() || l.starts_with('#');
+    let Some(last) = lines.iter().rposition(|l| !is_blank(l)) else {
+        return 0..0;
+    };
+    let start = lines[..last]
+        .iter()
+        .rposition(|l| l.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    if start == 0 {
+        return 0..0;
+    }
+    start..last + 1
+}
+
+/// The first line of the diff of a patch from `git format-patch`, after its headers and commit
+/// message, or the number of lines if there isn't one.
+pub fn patch_diff_start(text: &str) -> usize {
+    let start = header_end(text);
+    text.lines()
+        .enumerate()
+        .skip(start)
+        .find(|(_, l)| *l == "---" || l.starts_with("diff --git "))
+        .map_or_else(|| text.lines().count(), |(i, _)| i)
+}
+
+/// Get the (possibly multi-word) query ending at `character` in an address header line, along
+/// with the character it starts at.
+///
+/// The query runs from the previous address separator (`:` or `,`) to the cursor, without any
+/// quoting or angle brackets, so `To: "Jane Do` gives `Jane Do`.
+/// The start includes any quoting, so replacing from it removes the partially typed mailbox.
+pub fn address_query_at(line: &str, character: usize) -> Option<(usize, String)> {
+    if !is_address_header(line) {
+        return None;
+    }
+    list_query_at(line, character)
+}
+
+/// Like [`address_query_at`], for a line known to be part of an address header, such as one of
+/// its continuation lines.
+pub fn list_query_at(line: &str, character: usize) -> Option<(usize, String)> {
+    let before = line.chars().take(character + 1).collect::<String>();
+    let start = before.rfind([',', ':']).map_or(0, |i| i + 1);
+    let entry = before[start..].trim_start();
+    let query = entry
+        .trim_start_matches(['"', '<'])
+        .replace(['"', '<', '>'], "");
+    if query.trim().is_empty() {
+        None
+    } else {
+        let start = before.chars().count() - entry.chars().count();
+        Some((start, query))
+    }
+}
+
+/// Whether the line at the index continues a folded address header, like the second line of
+/// `To: a@example.com,\n  b@example.com`.
+pub fn continues_address_header(text: &str, index: usize) -> bool {
+    let lines = text.lines().take(index + 1).collect::<Vec<_>>();
+    if lines.len() <= index || lines.iter().any(|l| l.trim().is_empty()) {
+        return false;
+    }
+    lines[index].starts_with([' ', '\t'])
+        && lines
+            .iter()
+            .rev()
+            .find(|l| !l.starts_with([' ', '\t']))
+            .is_some_and(|l| is_address_header(l))
+}
+
+/// The length header lines should be kept within, and the length they must be, in characters
+/// ([RFC 5322 2.1.1](https://www.rfc-editor.org/rfc/rfc5322#section-2.1.1)).
+pub const LINE_LENGTH: usize = 78;
+pub const MAX_LINE_LENGTH: usize = 998;
+
+/// Fold mailboxes inserted into an address header line after `before` onto continuation lines,
+/// so the lines are no longer than [`LINE_LENGTH`] where possible and never longer than
+/// [`MAX_LINE_LENGTH`].
+///
+/// Lines are broken between the mailboxes, or within one only when it would otherwise go over
+/// the maximum.
+/// Gives whether to fold `before` too, replacing any whitespace it ends with by a line break and
+/// an indent, when an earlier mailbox there leaves no room, along with the folded text.
+pub fn fold_insertion(before: &str, text: &str) -> (bool, String) {
+    let mut width = before.chars().count();
+    // a line can't be broken before its first mailbox
+    let mut can_break = before.trim_end().ends_with(',');
+    let mut break_before = false;
+    let mut folded = String::new();
+    for (i, mailbox) in split_mailboxes(text).into_iter().enumerate() {
+        for (j, word) in mailbox.split(' ').enumerate() {
+            let space = usize::from(i + j > 0);
+            let length = word.chars().count();
+            // mailboxes are kept whole within the line length where they can be
+            let too_long = if j == 0 {
+                width + space + mailbox.chars().count() > LINE_LENGTH
+            } else {
+                width + space + length > MAX_LINE_LENGTH
+            };
+            if can_break && too_long {
+                if folded.is_empty() {
+                    break_before = true;
+                } else {
+                    folded.push_str("\n ");
+                }
+                width = 1;
+            } else if space > 0 {
+                folded.push(' ');
+                width += 1;
+            }
+            folded.push_str(word);
+            width += length;
+            can_break = true;
+        }
+    }
+    (break_before, folded)
+}
+
+/// Split a list of mailboxes after each of the commas between them, leaving those in quoted names
+/// and angle brackets.
+fn split_mailboxes(text: &str) -> Vec<&str> {
+    let mut mailboxes = Vec::new();
+    let (mut quoted, mut escaped, mut angled) = (false, false, false);
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '<' if !quoted => angled = true,
+            '>' if !quoted => angled = false,
+            ',' if !quoted && !angled => {
+                mailboxes.push(text[start..=i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    mailboxes.push(text[start..].trim());
+    mailboxes.retain(|m| !m.is_empty());
+    mailboxes
+}
+
+/// Whether an address separator (`,`) already follows `character` in the line.
+pub fn separator_follows(line: &str, character: usize) -> bool {
+    line.chars()
+        .skip(character)
+        .find(|c| !c.is_whitespace())
+        .is_some_and(|c| c == ',')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_query() {
+        let line = "To: a@b.com, \"Jane Do";
+        assert_eq!(
+            address_query_at(line, line.chars().count() - 1),
+            Some((13, "Jane Do".to_owned()))
+        );
+        assert_eq!(address_query_at("To: ", 3), None);
+        assert_eq!(address_query_at("Subject: Jane Do", 15), None);
+    }
+
+    #[test]
+    fn trailer_query() {
+        let names = DEFAULT_TRAILERS.map(str::to_owned);
+        let line = "Signed-off-by: \"Jane Do";
+        assert_eq!(
+            trailer_query_at(line, line.chars().count() - 1, &names),
+            Some((15, "Jane Do".to_owned()))
+        );
+        assert_eq!(
+            trailer_query_at("reviewed-by: j", 13, &names),
+            Some((13, "j".to_owned()))
+        );
+        assert_eq!(trailer_query_at("Signed-off-by: ", 14, &names), None);
+        assert_eq!(trailer_query_at("Fixes: abc", 9, &names), None);
+    }
+
+    #[test]
+    fn recipients() {
+        let text = "From: me@example.com\nTo: Jane <jane@example.com>,\n  bob@example.com\nSubject: a@b.com\nCc: c@example.com\n\nTo: body@example.com\n";
+        assert_eq!(
+            recipient_addresses(text),
+            vec!["jane@example.com", "bob@example.com", "c@example.com"]
+        );
+        let mailboxes = recipient_mailboxes(text);
+        assert_eq!(mailboxes.len(), 3);
+        assert_eq!(mailboxes[0].name.as_deref(), Some("Jane"));
+        assert_eq!(mailboxes[1].name, None);
+        assert_eq!(mailboxes[2].email, "c@example.com");
+    }
+
+    #[test]
+    fn mbox() {
+        let text = "From a@example.com Mon Jan 1 00:00:00 2024\nTo: b@example.com\n\nhi\n\nFrom c@example.com Tue Jan 2 00:00:00 2024\nTo: d@example.com\n\n>From here\n";
+        assert_eq!(messages(text, true), vec![0..5, 5..9]);
+        assert_eq!(
+            messages(text, false).iter().map(|r| r.len()).sum::<usize>(),
+            9
+        );
+        assert_eq!(
+            messages(&format!("To: a@example.com\n\n{text}"), true),
+            vec![0..2, 2..7, 7..11]
+        );
+    }
+
+    #[test]
+    fn continuation() {
+        let text = "To: a@example.com,\n  b@example.com,\nSubject: hi\n c\n\n d\n";
+        assert!(!continues_address_header(text, 0));
+        assert!(continues_address_header(text, 1));
+        assert!(!continues_address_header(text, 3));
+        assert!(!continues_address_header(text, 5));
+    }
+
+    #[test]
+    fn fold() {
+        let group = "\"Doe, Jane\" <jane.doe@example.com>, bob@example.com, \"Carol Example\" <carol@example.com>";
+        assert_eq!(
+            fold_insertion("To: ", group),
+            (
+                false,
+                "\"Doe, Jane\" <jane.doe@example.com>, bob@example.com,\n \"Carol Example\" <carol@example.com>".to_owned()
+            )
+        );
+        let before = format!("Cc: {}, ", "a".repeat(60));
+        assert_eq!(
+            fold_insertion(&before, "jane@example.com"),
+            (true, "jane@example.com".to_owned())
+        );
+        assert_eq!(
+            fold_insertion("Cc: ", "jane@example.com"),
+            (false, "jane@example.com".to_owned())
+        );
+        // a mailbox too long to fit on a line of its own is only folded within at the maximum
+        let long = format!("\"{}\" <jane@example.com>", ["Jane"; 300].join(" "));
+        let (_, folded) = fold_insertion("To: a@example.com, ", &long);
+        assert_eq!(folded.lines().count(), 2);
+        assert!(folded.lines().all(|l| l.chars().count() <= MAX_LINE_LENGTH));
+    }
+
+    #[test]
+    fn from() {
+        assert!(is_from_header("From: Jane <jane@example.com>"));
+        assert!(is_from_header("resent-from: jane@example.com"));
+        assert!(!is_from_header(
+            "From jane@example.com Mon Jan 1 00:00:00 2024"
+        ));
+        assert!(!is_from_header("To: jane@example.com"));
+    }
+
+    #[test]
+    fn separator() {
+        let line = "Cc: jane@example.com , bob@example.com";
+        assert!(separator_follows(line, 20));
+        assert!(!separator_follows(line, 38));
+    }
+}
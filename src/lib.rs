@@ -1,16 +1,120 @@
+mod avatars;
+pub use avatars::AvatarService;
+pub use avatars::Avatars;
+
+mod birthday;
+pub use birthday::Birthday;
+
+mod disk_index;
+pub use disk_index::DiskIndex;
+pub use disk_index::IndexRecord;
+
+mod duplicates;
+pub use duplicates::find_duplicates;
+pub use duplicates::Duplicate;
+
+mod encryption;
+pub use encryption::Encryption;
+
+mod exclude;
+pub use exclude::ExcludePatterns;
+
 mod mailbox;
+pub use mailbox::find_addresses;
 pub use mailbox::Mailbox;
 
+mod notmuch;
+pub use notmuch::notmuch_weights;
+
+mod names;
+pub use names::names_at;
+pub use names::StructuredName;
+
 mod contact_list;
 pub use contact_list::ContactList;
+pub use contact_list::ContactListFormat;
+
+pub mod vcard_schema;
+pub mod vcard_text;
 
 mod vcards;
+pub use vcards::render_vcard;
+pub use vcards::CategoryFilter;
+pub use vcards::ScanOptions;
 pub use vcards::VCards;
 
+mod contact;
+pub use contact::Contact;
+pub use contact::ContactMeta;
+
 mod contact_source;
+pub use contact_source::sort_matches;
+pub use contact_source::Conflict;
 pub use contact_source::ContactSource;
+pub use contact_source::DefinitionSources;
+pub use contact_source::DocumentSources;
+pub(crate) use contact_source::FileStamp;
 pub use contact_source::Location;
+pub use contact_source::Match;
+pub use contact_source::MergeInto;
+pub use contact_source::MergeStrategy;
+pub use contact_source::PostalAddress;
 pub use contact_source::Sources;
+pub use contact_source::Usage;
+
+pub mod headers;
+
+mod matching;
+pub use matching::fold;
+pub use matching::Folded;
+pub use matching::MatchMode;
+pub use matching::MatchOptions;
+pub use matching::Matcher;
+
+mod document_filter;
+pub use document_filter::DocumentFilter;
+
+mod phone;
+pub use phone::normalize_phone;
+pub use phone::phone_at;
+pub use phone::tel_query_at;
+pub use phone::tel_uri;
+pub use phone::Phone;
+
+mod profiles;
+pub use profiles::Profile;
+pub use profiles::ProfileOptions;
+pub use profiles::Profiles;
+pub use profiles::Scope;
+
+pub mod render;
+
+mod sent;
+pub use sent::sent_weights;
+
+mod search_index;
+pub use search_index::SearchIndex;
+
+mod paths;
+pub use paths::expand_path;
 
 mod open_files;
+pub use open_files::Applied;
+pub use open_files::LineChange;
 pub use open_files::OpenFiles;
+
+mod usage;
+pub use usage::UsageEntry;
+pub use usage::UsageKind;
+pub use usage::UsageLog;
+
+mod weights;
+pub use weights::AddressWeights;
+
+mod watcher;
+pub use watcher::Watcher;
+
+#[cfg(test)]
+mod test_dir;
+#[cfg(test)]
+pub(crate) use test_dir::TestDir;
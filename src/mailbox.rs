@@ -10,6 +10,18 @@ static MAILBOX_REGEX: LazyLock<Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
+// from https://www.regular-expressions.info/email.html
+static ADDRESS_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b([A-Z0-9._%+-~/]+@[A-Z0-9.-]+\.[A-Z]{2,})\b").unwrap());
+
+/// The email addresses in the line, with the characters each spans as a half-open range.
+pub fn find_addresses(line: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    ADDRESS_REGEX.find_iter(line).map(|m| {
+        let start = line[..m.start()].chars().count();
+        (start, start + m.as_str().chars().count(), m.as_str())
+    })
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Mailbox {
     pub name: Option<String>,
@@ -18,15 +30,21 @@ pub struct Mailbox {
 
 impl Mailbox {
     pub fn from_line_at(line: &str, character: usize) -> Option<Self> {
-        let mut mailbox = None;
+        Self::span_at(line, character).map(|(_, _, mailbox)| mailbox)
+    }
+
+    /// Find the mailbox at the character in the line, along with the characters it spans
+    /// (including any quotes and angle brackets) as a half-open range.
+    pub fn span_at(line: &str, character: usize) -> Option<(usize, usize, Self)> {
+        let char_index = |byte: usize| line[..byte].chars().count();
         for captures in MAILBOX_REGEX.captures_iter(line) {
             let mut start = None;
             let mut end = None;
             let mut mbox = Mailbox::default();
 
             if let Some(name) = captures.name("name") {
-                start = Some(name.start());
-                end = Some(name.end());
+                start = Some(char_index(name.start()));
+                end = Some(char_index(name.end()));
                 mbox.name = Some(
                     name.as_str()
                         .trim()
@@ -37,32 +55,101 @@ impl Mailbox {
             }
             if let Some(email) = captures.name("email") {
                 if start.is_none() {
-                    start = Some(email.start());
+                    start = Some(char_index(email.start()));
                 }
-                end = Some(email.end());
+                end = Some(char_index(email.end()));
                 mbox.email = email.as_str().trim().to_owned();
             }
 
-            if start.map_or(false, |s| s <= character) && end.map_or(false, |e| character <= e) {
-                mailbox = Some(mbox);
-                break;
+            if start.is_some_and(|s| s <= character) && end.is_some_and(|e| character <= e) {
+                let whole = captures.get(0).unwrap();
+                let text = whole.as_str();
+                let span_start = char_index(whole.start())
+                    + (text.chars().count() - text.trim_start().chars().count());
+                let span_end = char_index(whole.end())
+                    - (text.chars().count() - text.trim_end().chars().count());
+                return Some((span_start, span_end, mbox));
             }
         }
-        mailbox
+        None
+    }
+
+    /// The mailbox as git writes it in trailers, with the name unquoted.
+    pub fn to_trailer(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{name} <{}>", self.email),
+            None => self.email.clone(),
+        }
+    }
+
+    /// The mailbox like its [`Display`], with a name that isn't ASCII as encoded-words
+    /// ([RFC 2047](https://www.rfc-editor.org/rfc/rfc2047)), for mail software that doesn't
+    /// take UTF-8 in headers.
+    pub fn to_encoded(&self) -> String {
+        match self.name.as_deref().filter(|n| !n.is_empty()) {
+            Some(name) if !name.is_ascii() => format!("{} <{}>", encoded_words(name), self.email),
+            _ => self.to_string(),
+        }
     }
 }
 
+/// Whether the character can be in an atom, with any character that isn't ASCII as
+/// [RFC 6532](https://www.rfc-editor.org/rfc/rfc6532) allows.
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || "!#$%&'*+-/=?^_`{|}~".contains(c)
+        || !(c.is_ascii() || c.is_control())
+}
+
+/// The name as an [RFC 5322](https://www.rfc-editor.org/rfc/rfc5322#section-3.2.5) phrase: as
+/// it is when it is words of atoms, otherwise quoted with any quotes and backslashes escaped.
+fn phrase(name: &str) -> String {
+    if name
+        .split(' ')
+        .all(|word| !word.is_empty() && word.chars().all(is_atext))
+    {
+        return name.to_owned();
+    }
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Longest encoded-word that RFC 2047 allows.
+const ENCODED_WORD_LENGTH: usize = 75;
+
+/// The text as `Q` encoded-words of UTF-8, split between characters to keep each short enough.
+fn encoded_words(text: &str) -> String {
+    let (prefix, suffix) = ("=?UTF-8?Q?", "?=");
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        let encoded = match c {
+            ' ' => "_".to_owned(),
+            c if c.is_ascii_alphanumeric() || "!*+-/".contains(c) => c.to_string(),
+            c => c.to_string().bytes().map(|b| format!("={b:02X}")).collect(),
+        };
+        if prefix.len() + word.len() + encoded.len() + suffix.len() > ENCODED_WORD_LENGTH {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push_str(&encoded);
+    }
+    words.push(word);
+    words
+        .iter()
+        .map(|w| format!("{prefix}{w}{suffix}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 impl FromStr for Mailbox {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let Some((name, email)) = s.split_once(" <") {
-            let name = name
-                .trim()
-                .trim_start_matches('"')
-                .trim_end_matches('"')
-                .trim()
-                .to_owned();
+            let name = name.trim();
+            let name = match name.strip_prefix('"').and_then(|n| n.strip_suffix('"')) {
+                Some(quoted) => unescape(quoted).trim().to_owned(),
+                None => name.trim_matches('"').trim().to_owned(),
+            };
             let email = email.trim().trim_end_matches('>').to_owned();
             Ok(Self {
                 name: Some(name),
@@ -77,16 +164,26 @@ impl FromStr for Mailbox {
     }
 }
 
+/// The content of a quoted string, without the backslashes escaping its characters.
+fn unescape(quoted: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// The mailbox as it is written in headers, quoting the name only when it needs to be.
 impl Display for Mailbox {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(name) = &self.name {
-            write!(f, "{:?} <", name)?;
+        match self.name.as_deref().filter(|n| !n.is_empty()) {
+            Some(name) => write!(f, "{} <{}>", phrase(name), self.email),
+            None => write!(f, "{}", self.email),
         }
-        write!(f, "{}", self.email)?;
-        if self.name.is_some() {
-            write!(f, ">")?;
-        }
-        Ok(())
     }
 }
 
@@ -106,6 +203,41 @@ mod tests {
             }
         );
         assert_eq!(Mailbox::from_str(&mbox.to_string()).unwrap(), mbox);
+        assert_eq!(Mailbox::from_str(&mbox.to_trailer()).unwrap(), mbox);
+    }
+
+    #[test]
+    fn display() {
+        let mailbox = |name: &str| Mailbox {
+            name: Some(name.to_owned()),
+            email: "jane@test.com".to_owned(),
+        };
+        assert_eq!(mailbox("Jane Doe").to_string(), "Jane Doe <jane@test.com>");
+        assert_eq!(mailbox("Zoë").to_string(), "Zoë <jane@test.com>");
+        assert_eq!(
+            mailbox("Doe, Jane").to_string(),
+            "\"Doe, Jane\" <jane@test.com>"
+        );
+        assert_eq!(mailbox("J. Doe").to_string(), "\"J. Doe\" <jane@test.com>");
+        let quoted = mailbox(r#"Jane "JD" Doe\"#);
+        assert_eq!(quoted.to_string(), r#""Jane \"JD\" Doe\\" <jane@test.com>"#);
+        assert_eq!(Mailbox::from_str(&quoted.to_string()).unwrap(), quoted);
+        assert_eq!(mailbox("").to_string(), "jane@test.com");
+
+        assert_eq!(
+            mailbox("Zoë Doe").to_encoded(),
+            "=?UTF-8?Q?Zo=C3=AB_Doe?= <jane@test.com>"
+        );
+        assert_eq!(
+            mailbox("Doe, Jane").to_encoded(),
+            "\"Doe, Jane\" <jane@test.com>"
+        );
+        let long = mailbox(&"é".repeat(30)).to_encoded();
+        assert!(long
+            .split(' ')
+            .filter(|w| w.starts_with("=?"))
+            .all(|w| w.len() <= ENCODED_WORD_LENGTH));
+        assert_eq!(long.matches("=?UTF-8?Q?").count(), 3);
     }
 
     #[test]
@@ -144,6 +276,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn span_at() {
+        let line = "To: \"Old Name\" <old@test.com>, other@test.com";
+        let (start, end, mbox) = Mailbox::span_at(line, 8).unwrap();
+        assert_eq!(&line[start..end], "\"Old Name\" <old@test.com>");
+        assert_eq!(mbox.email, "old@test.com");
+        let (start, end, _) = Mailbox::span_at(line, 33).unwrap();
+        assert_eq!(&line[start..end], "other@test.com");
+    }
+
+    #[test]
+    fn find_addresses() {
+        let line = "Cc: Zoë <zoe@test.com>, other@test.co.uk, not an@address";
+        assert_eq!(
+            super::find_addresses(line).collect::<Vec<_>>(),
+            vec![(9, 21, "zoe@test.com"), (24, 40, "other@test.co.uk")]
+        );
+    }
+
     #[test]
     fn from_line_at_context() {
         let line = "Other words before \"First Last\" <first.last@test.com> and other words after";
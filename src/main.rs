@@ -1,6 +1,8 @@
 use clap::Parser;
-use line_index::LineIndex;
-use line_index::TextSize;
+use clap::Subcommand;
+use crossbeam_channel::Receiver;
+use crossbeam_channel::Sender;
+use itertools::Itertools as _;
 use lsp_server::ErrorCode;
 use lsp_server::Message;
 use lsp_server::Notification;
@@ -13,6 +15,7 @@ use lsp_types::notification::Notification as _;
 use lsp_types::notification::PublishDiagnostics;
 use lsp_types::notification::ShowMessage;
 use lsp_types::request::Request as _;
+use lsp_types::ClientCapabilities;
 use lsp_types::CodeActionKind;
 use lsp_types::CompletionItem;
 use lsp_types::CompletionItemKind;
@@ -22,6 +25,7 @@ use lsp_types::DiagnosticSeverity;
 use lsp_types::ExecuteCommandOptions;
 use lsp_types::InitializeParams;
 use lsp_types::InitializeResult;
+use lsp_types::MessageType;
 use lsp_types::Position;
 use lsp_types::PositionEncodingKind;
 use lsp_types::PublishDiagnosticsParams;
@@ -32,41 +36,471 @@ use lsp_types::ShowDocumentParams;
 use lsp_types::TextDocumentPositionParams;
 use lsp_types::TextDocumentSyncKind;
 use lsp_types::Url;
+use lsp_types::WorkspaceFolder;
+use maills::expand_path;
+use maills::find_addresses;
+use maills::fold;
+use maills::headers;
+use maills::names_at;
+use maills::notmuch_weights;
+use maills::phone_at;
+use maills::render;
+use maills::render::RenderOptions;
+use maills::render::Section;
+use maills::render_vcard;
+use maills::sent_weights;
+use maills::tel_query_at;
+use maills::tel_uri;
+use maills::vcard_schema;
+use maills::vcard_text::VcardText;
+use maills::Applied;
+use maills::AvatarService;
+use maills::Avatars;
+use maills::CategoryFilter;
+use maills::Conflict;
 use maills::ContactList;
-use maills::ContactSource as _;
+use maills::ContactListFormat;
+use maills::ContactSource;
+use maills::DefinitionSources;
+use maills::DocumentFilter;
+use maills::Duplicate;
+use maills::Encryption;
+use maills::ExcludePatterns;
+use maills::LineChange;
+use maills::Location;
 use maills::Mailbox;
+use maills::Match;
+use maills::MatchMode;
+use maills::MatchOptions;
+use maills::Matcher;
+use maills::MergeInto;
+use maills::MergeStrategy;
 use maills::OpenFiles;
+use maills::Profile;
+use maills::ProfileOptions;
+use maills::Profiles;
+use maills::ScanOptions;
+use maills::Scope;
 use maills::Sources;
+use maills::StructuredName;
+use maills::UsageKind;
+use maills::UsageLog;
 use maills::VCards;
+use maills::Watcher;
 use serde::Deserialize;
 use serde::Serialize;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+use std::time::Duration;
+use std::time::Instant;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::reload;
 
 const CREATE_CONTACT_COMMAND: &str = "create_contact";
+/// Merge the contacts of a conflict, given as its only argument, into the first of them.
+const MERGE_CONTACTS_COMMAND: &str = "merge_contacts";
+/// Replace the mailbox at a position in a document with a postal address of its contact.
+const INSERT_ADDRESS_COMMAND: &str = "insert_address";
+/// Append dated text to the notes of the contact of the mailbox at a position in a document.
+const ADD_NOTE_COMMAND: &str = "add_note";
+/// Custom request for the state of the server, e.g. conflicting contacts.
+const STATUS_REQUEST: &str = "maills/status";
+/// Custom notification that a contact was created, for editor plugins to react to.
+const CONTACT_CREATED_NOTIFICATION: &str = "maills/contactCreated";
+
+/// Latest version of the initialization options.
+const OPTIONS_VERSION: u32 = 1;
+
+/// Maximum number of completion items to send to the client.
+const COMPLETION_LIMIT: usize = 100;
+/// Maximum number of matches to keep for refining later completions.
+const COMPLETION_CACHE_LIMIT: usize = 1000;
+/// Maximum number of rendered contacts to keep.
+const RENDER_CACHE_LIMIT: usize = 1000;
+/// Number of threads answering read-only requests.
+const WORKERS: usize = 4;
+
+mod cli;
 
 #[derive(Debug, Clone, Parser)]
+#[clap(version, about)]
 struct Args {
     #[clap(long)]
     stdio: bool,
+    /// Configuration in the format of the initialization options, for the commands and clients
+    /// that don't send any [default: ~/.config/maills/config.json]
+    #[clap(long, global = true)]
+    config: Option<PathBuf>,
+    /// File to append logs to, instead of stderr.
+    #[clap(long, global = true)]
+    log_file: Option<PathBuf>,
+    /// Most verbose level to log: off, error, warn, info, debug or trace.
+    #[clap(long, global = true, default_value = "warn")]
+    log_level: LevelFilter,
+    /// Print the output of commands as JSON, for scripts and editor plugins.
+    #[clap(long, global = true)]
+    json: bool,
+    /// Run a command on the configured contacts instead of the server.
+    #[clap(subcommand)]
+    command: Option<Command>,
 }
 
-fn log(c: &Connection, message: impl Serialize) {
-    c.sender
-        .send(Message::Notification(Notification::new(
-            LogMessage::METHOD.to_string(),
-            message,
-        )))
-        .unwrap();
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Print the contacts matching the query in the format of mutt's `query_command`.
+    Query {
+        /// Text to match the names and emails of contacts against.
+        query: String,
+        /// Leave out the first line of the output, a message for mutt, e.g. for aerc's
+        /// `address-book-cmd`.
+        #[clap(long)]
+        no_header: bool,
+    },
+    /// Report addresses in the files that aren't in the contacts, like the diagnostics, failing if
+    /// there are any.
+    Check {
+        /// Files to check, `-` for stdin.
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+        #[clap(long, value_enum, default_value_t = CheckFormat::Text)]
+        format: CheckFormat,
+    },
+    /// Add a contact to the `default_write_source`, or else the first source that takes new ones,
+    /// as the server's code action does.
+    Add {
+        /// The contact's address, e.g. `"Jane Doe <jane@example.com>"`.
+        mailbox: String,
+    },
+    /// Report duplicate contacts in the sources, failing if there are any, like the conflicts
+    /// the server warns about.
+    Dedupe {
+        /// Merge the duplicates in writable sources into the first of each, as the server's code
+        /// action does.
+        #[clap(long)]
+        fix: bool,
+    },
+    /// Check the configuration file and each of its sources, printing any problems found and
+    /// how to fix them.
+    Doctor,
+    /// Print how many contacts and emails each source has, with its duplicates, largest files and
+    /// load time, then the addresses used most.
+    Stats,
+    /// Report addresses that are in more than one source, or that contacts give different names.
+    Duplicates,
+    /// Convert the contacts in a file to another format, e.g. to move them to a source that takes
+    /// new contacts. Doesn't need a configuration file.
+    Convert {
+        /// File to read the contacts from.
+        input: PathBuf,
+        #[clap(long, value_enum, default_value_t = ImportFormat::List)]
+        from: ImportFormat,
+        #[clap(long, value_enum)]
+        to: ExportFormat,
+        /// File to write the contacts to instead of stdout.
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print the shell's completions for maills, e.g. to source from its configuration.
+    Completions {
+        #[clap(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page for maills, or write it and those of the commands to a directory.
+    Man {
+        /// Directory to write `maills.1` and a page for each command to.
+        #[clap(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Print every contact from all of the sources once, earlier sources taking addresses that
+    /// several have.
+    Export {
+        #[clap(long, value_enum, default_value_t = ExportFormat::List)]
+        format: ExportFormat,
+        /// File to write the contacts to instead of stdout.
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
 }
 
-fn notify(c: &Connection, method: &str, params: impl Serialize) {
-    c.sender
-        .send(Message::Notification(Notification::new(
-            method.to_owned(),
-            params,
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CheckFormat {
+    /// A `path:line:column: message` line for each address.
+    Text,
+    /// A JSON array of the addresses with where they are.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ImportFormat {
+    /// A vcard file with any number of cards.
+    Vcf,
+    /// The JSON that `export` writes.
+    Json,
+    /// A contact list, detecting the format of each line.
+    List,
+    /// `Name email` lines, as from `mu cfind --format=plain`.
+    Plain,
+    /// Mutt aliases: `alias nick Name <email>`.
+    MuttAlias,
+    /// `Name <email>` lines from `notmuch address`.
+    Notmuch,
+    /// `"Name","email"` rows, as from `mu cfind --format=csv` or `export`.
+    Csv,
+    /// `email<TAB>Name` lines after a header, as from `mu cfind --format=mutt-ab`.
+    MuttAb,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    /// `name,email,source` rows after a header, one for each address.
+    Csv,
+    /// A JSON array of the contacts with all of their addresses.
+    Json,
+    /// A `Name <email>` line for each address, which a contact list reads back.
+    List,
+    /// A vcard for each contact.
+    Vcf,
+}
+
+impl Args {
+    /// The configuration file to read: the one given with `--config`, or the default one.
+    fn config_path(&self) -> Result<Option<PathBuf>, String> {
+        match &self.config {
+            Some(path) => expand_path(path).map(Some),
+            None => Ok(dirs::config_dir().map(|dir| dir.join("maills").join("config.json"))),
+        }
+    }
+
+    /// Read the configuration file, if there is one.
+    ///
+    /// Only a file given with `--config` has to exist.
+    fn config(&self) -> Result<Option<InitializationOptions>, String> {
+        let Some(path) = self.config_path()? else {
+            return Ok(None);
+        };
+        if self.config.is_none() && !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+        let value = serde_json::from_str(&content)
+            .map_err(|err| format!("Invalid JSON in {}: {err}", path.display()))?;
+        InitializationOptions::parse(value)
+            .map(Some)
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+/// Logs of what the server is doing, whose level the client can raise with `$/setTrace`.
+struct Logging {
+    /// The level given on the command line.
+    level: LevelFilter,
+    handle: reload::Handle<LevelFilter, tracing_subscriber::Registry>,
+    tracer: Tracer,
+}
+
+/// The client's trace setting, for sending it how long the server takes as `$/logTrace`
+/// notifications.
+#[derive(Clone, Default)]
+struct Tracer {
+    value: Arc<RwLock<lsp_types::TraceValue>>,
+}
+
+impl Tracer {
+    /// The `$/logTrace` notification of the message, unless tracing is off, with the verbose
+    /// details when it is verbose.
+    fn log_trace(
+        &self,
+        message: String,
+        verbose: impl FnOnce() -> Option<String>,
+    ) -> Option<Message> {
+        let value = *self.value.read().unwrap_or_else(PoisonError::into_inner);
+        let verbose = match value {
+            lsp_types::TraceValue::Off => return None,
+            lsp_types::TraceValue::Messages => None,
+            lsp_types::TraceValue::Verbose => verbose(),
+        };
+        Some(Message::Notification(Notification::new(
+            lsp_types::notification::LogTrace::METHOD.to_owned(),
+            lsp_types::LogTraceParams { message, verbose },
         )))
-        .unwrap();
+    }
+}
+
+impl Logging {
+    fn init(args: &Args) -> Result<Self, String> {
+        let writer = match &args.log_file {
+            Some(path) => {
+                let path = expand_path(path)?;
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|err| format!("Failed to open log file {}: {err}", path.display()))?;
+                BoxMakeWriter::new(Mutex::new(file))
+            }
+            // stdout is for the protocol
+            None => BoxMakeWriter::new(std::io::stderr),
+        };
+        let (filter, handle) = reload::Layer::new(args.log_level);
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_ansi(false)
+                    // how long each request took, including any time on a worker
+                    .with_span_events(FmtSpan::CLOSE),
+            )
+            .init();
+        Ok(Self {
+            level: args.log_level,
+            handle,
+            tracer: Tracer::default(),
+        })
+    }
+
+    /// Follow the client's trace setting: `messages` logs at least debug and `verbose` logs
+    /// everything.
+    fn set_trace(&self, trace: lsp_types::TraceValue) {
+        let level = match trace {
+            lsp_types::TraceValue::Off => self.level,
+            lsp_types::TraceValue::Messages => self.level.max(LevelFilter::DEBUG),
+            lsp_types::TraceValue::Verbose => LevelFilter::TRACE,
+        };
+        if let Err(err) = self.handle.modify(|filter| *filter = level) {
+            tracing::warn!("Failed to change the log level: {err}");
+        }
+        *self
+            .tracer
+            .value
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = trace;
+    }
+}
+
+fn log(c: &Connection, typ: MessageType, message: impl Into<String>) {
+    // a disconnected client is noticed by the main loop
+    let _ = c.sender.send(log_message(typ, message));
+}
+
+fn notify(c: &Connection, typ: MessageType, message: impl Into<String>) {
+    let _ = c.sender.send(show_message(typ, message));
+}
+
+/// A message for the client to log.
+fn log_message(typ: MessageType, message: impl Into<String>) -> Message {
+    Message::Notification(Notification::new(
+        LogMessage::METHOD.to_owned(),
+        lsp_types::LogMessageParams {
+            typ,
+            message: message.into(),
+        },
+    ))
+}
+
+/// A message for the client to show to the user.
+fn show_message(typ: MessageType, message: impl Into<String>) -> Message {
+    Message::Notification(Notification::new(
+        ShowMessage::METHOD.to_owned(),
+        lsp_types::ShowMessageParams {
+            typ,
+            message: message.into(),
+        },
+    ))
+}
+
+fn send(c: &Connection, messages: Vec<Message>) -> Result<(), String> {
+    for message in messages {
+        c.sender
+            .send(message)
+            .map_err(|err| format!("Failed to send to the client: {err}"))?;
+    }
+    Ok(())
+}
+
+/// The format to show hovers in: the client's preference, or markdown if it has none.
+fn hover_format(capabilities: &ClientCapabilities) -> lsp_types::MarkupKind {
+    capabilities
+        .text_document
+        .as_ref()
+        .and_then(|t| t.hover.as_ref())
+        .and_then(|h| h.content_format.as_ref())
+        .map_or(lsp_types::MarkupKind::Markdown, |formats| {
+            formats
+                .first()
+                .cloned()
+                .unwrap_or(lsp_types::MarkupKind::PlainText)
+        })
+}
+
+/// Content in the format, converting the markdown render to plain text if need be.
+fn markup(format: &lsp_types::MarkupKind, markdown: String) -> lsp_types::MarkupContent {
+    let value = match format {
+        lsp_types::MarkupKind::Markdown => markdown,
+        lsp_types::MarkupKind::PlainText => render::to_plaintext(&markdown),
+    };
+    lsp_types::MarkupContent {
+        kind: format.clone(),
+        value,
+    }
+}
+
+/// The response to a goto request, with a single location as a scalar.
+///
+/// Clients that support links get them instead, from the range of the origin if known.
+fn goto_response(
+    id: RequestId,
+    locations: impl IntoIterator<Item = Location>,
+    links: Option<Option<Range>>,
+) -> Message {
+    let mut locations = locations
+        .into_iter()
+        .filter_map(|l| lsp_types::Location::try_from(l).ok())
+        .collect::<Vec<_>>();
+    if let Some(origin) = links {
+        if locations.is_empty() {
+            return response_empty(id);
+        }
+        let links = locations
+            .into_iter()
+            .map(|l| lsp_types::LocationLink {
+                origin_selection_range: origin,
+                target_uri: l.uri,
+                target_range: l.range,
+                target_selection_range: l.range,
+            })
+            .collect();
+        return response_ok(id, lsp_types::GotoDefinitionResponse::Link(links));
+    }
+    match locations.len() {
+        0 => response_empty(id),
+        1 => {
+            let resp = lsp_types::GotoDefinitionResponse::Scalar(locations.remove(0));
+            response_ok(id, resp)
+        }
+        _ => {
+            let resp = lsp_types::GotoDefinitionResponse::Array(locations);
+            response_ok(id, resp)
+        }
+    }
 }
 
 fn response_empty(id: RequestId) -> Message {
@@ -85,10 +519,82 @@ fn response_err(id: RequestId, code: i32, message: String) -> Message {
     Message::Response(Response::new_err(id, code, message))
 }
 
+/// Failure to handle a message, sent back as an error response to requests and logged for
+/// notifications, so the server keeps going.
+#[derive(Debug)]
+enum Error {
+    /// The params don't fit the method.
+    InvalidParams(String),
+    /// The document isn't open and couldn't be read from disk, or changes to it were missed.
+    UnknownDocument(String),
+    /// The sources are still loading so the request can't be done yet.
+    Loading,
+    UnknownCommand(String),
+    /// Handling the request panicked.
+    Internal(String),
+}
+
+impl Error {
+    fn code(&self) -> ErrorCode {
+        match self {
+            Error::InvalidParams(_) | Error::UnknownDocument(_) => ErrorCode::InvalidParams,
+            Error::Loading => ErrorCode::ContentModified,
+            Error::UnknownCommand(_) => ErrorCode::InvalidRequest,
+            Error::Internal(_) => ErrorCode::InternalError,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidParams(err) => write!(f, "invalid params: {err}"),
+            Error::UnknownDocument(uri) => write!(f, "unknown document {uri}"),
+            Error::Loading => write!(f, "contacts are still loading"),
+            Error::UnknownCommand(command) => write!(f, "unknown command {command}"),
+            Error::Internal(err) => write!(f, "internal error: {err}"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::InvalidParams(err.to_string())
+    }
+}
+
+/// The messages answering a request, or an error response if handling it failed.
+fn request_messages(
+    id: RequestId,
+    method: &str,
+    result: Result<Vec<Message>, Error>,
+) -> Vec<Message> {
+    result.unwrap_or_else(|err| {
+        tracing::warn!(%method, "Failed to handle request: {err}");
+        vec![
+            log_message(
+                MessageType::ERROR,
+                format!("Failed to handle {method}: {err}"),
+            ),
+            response_err(id, err.code() as i32, err.to_string()),
+        ]
+    })
+}
+
+/// The message a thread panicked with.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_default()
+}
+
 fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
         hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
         definition_provider: Some(lsp_types::OneOf::Left(true)),
+        type_definition_provider: Some(lsp_types::TypeDefinitionProviderCapability::Simple(true)),
         completion_provider: Some(lsp_types::CompletionOptions {
             resolve_provider: Some(true),
             ..Default::default()
@@ -97,27 +603,59 @@ fn server_capabilities() -> ServerCapabilities {
             lsp_types::TextDocumentSyncOptions {
                 open_close: Some(true),
                 change: Some(TextDocumentSyncKind::INCREMENTAL),
+                save: Some(lsp_types::TextDocumentSyncSaveOptions::Supported(true)),
                 ..Default::default()
             },
         )),
         code_action_provider: Some(lsp_types::CodeActionProviderCapability::Simple(true)),
+        document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
         execute_command_provider: Some(ExecuteCommandOptions {
-            commands: vec![CREATE_CONTACT_COMMAND.to_owned()],
+            commands: vec![
+                CREATE_CONTACT_COMMAND.to_owned(),
+                MERGE_CONTACTS_COMMAND.to_owned(),
+                INSERT_ADDRESS_COMMAND.to_owned(),
+                ADD_NOTE_COMMAND.to_owned(),
+            ],
             ..Default::default()
         }),
+        workspace: Some(lsp_types::WorkspaceServerCapabilities {
+            workspace_folders: Some(lsp_types::WorkspaceFoldersServerCapabilities {
+                supported: Some(true),
+                change_notifications: Some(lsp_types::OneOf::Left(true)),
+            }),
+            file_operations: None,
+        }),
         ..Default::default()
     }
 }
 
-fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads) {
-    let (connection, io) = if stdio {
+fn connect(
+    args: &Args,
+    logging: &Logging,
+) -> Result<
+    (
+        InitializationOptions,
+        ClientCapabilities,
+        Vec<WorkspaceFolder>,
+        Connection,
+        IoThreads,
+    ),
+    String,
+> {
+    let (connection, io) = if args.stdio {
         Connection::stdio()
     } else {
-        panic!("No connection mode given, e.g. --stdio");
+        return Err("No connection mode given, e.g. --stdio".to_owned());
     };
-    let (id, params) = connection.initialize_start().unwrap();
+    let (id, params) = connection
+        .initialize_start()
+        .map_err(|err| format!("Failed to initialize: {err}"))?;
     let mut caps = server_capabilities();
-    let init_params = serde_json::from_value::<InitializeParams>(params).unwrap();
+    let init_params = serde_json::from_value::<InitializeParams>(params)
+        .map_err(|err| format!("Invalid initialize params: {err}"))?;
+    if let Some(trace) = init_params.trace {
+        logging.set_trace(trace);
+    }
     if let Some(general) = &init_params.capabilities.general {
         let pe = general
             .position_encodings
@@ -129,26 +667,43 @@ fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads)
             .unwrap_or(PositionEncodingKind::UTF16);
         caps.position_encoding = Some(pe);
     }
-    let init_opts = if let Some(io) = &init_params.initialization_options {
-        match serde_json::from_value::<InitializationOptions>(io.clone()) {
-            Ok(v) => v,
+    // carry on without contacts rather than leaving the editor without a server
+    let init_opts = match init_params.initialization_options {
+        Some(io) => match InitializationOptions::parse(io) {
+            Ok(v) => {
+                for conflict in v.conflicts() {
+                    notify(&connection, MessageType::WARNING, conflict);
+                }
+                v
+            }
             Err(err) => {
+                notify(&connection, MessageType::ERROR, err);
+                InitializationOptions::default()
+            }
+        },
+        None => match args.config() {
+            Ok(Some(v)) => {
+                for conflict in v.conflicts() {
+                    notify(&connection, MessageType::WARNING, conflict);
+                }
+                v
+            }
+            Ok(None) => {
                 notify(
                     &connection,
-                    ShowMessage::METHOD,
-                    format!("Invalid initialization options: {err}"),
+                    MessageType::ERROR,
+                    "No initialization options or configuration file given, need it for vcard \
+                     directory location at least",
                 );
-                panic!("Invalid initialization options: {err}")
+                InitializationOptions::default()
             }
-        }
-    } else {
-        notify(
-            &connection,
-            ShowMessage::METHOD,
-            "No initialization options given, need it for vcard directory location at least",
-        );
-        panic!("No initialization options given, need it for vcard directory location at least")
+            Err(err) => {
+                notify(&connection, MessageType::ERROR, err);
+                InitializationOptions::default()
+            }
+        },
     };
+    let workspace_folders = init_params.workspace_folders.unwrap_or_default();
     if !init_opts.enable_completion.unwrap_or(true) {
         caps.completion_provider = None;
     }
@@ -162,294 +717,2277 @@ fn connect(stdio: bool) -> (lsp_types::InitializeParams, Connection, IoThreads)
     if !init_opts.enable_goto_definition.unwrap_or(true) {
         caps.definition_provider = None;
     }
+    if !init_opts.enable_goto_type_definition.unwrap_or(true) {
+        caps.type_definition_provider = None;
+    }
+    if init_opts.full_sync(&init_params.capabilities) {
+        if let Some(lsp_types::TextDocumentSyncCapability::Options(options)) =
+            &mut caps.text_document_sync
+        {
+            options.change = Some(TextDocumentSyncKind::FULL);
+        }
+    }
     let init_result = InitializeResult {
         capabilities: caps,
         server_info: Some(ServerInfo {
             name: "maills".to_owned(),
-            version: None,
+            version: Some(env!("CARGO_PKG_VERSION").to_owned()),
         }),
     };
     connection
         .initialize_finish(id, serde_json::to_value(init_result).unwrap())
-        .unwrap();
+        .map_err(|err| format!("Failed to initialize: {err}"))?;
     // log(&c, format!("{:?}", params.initialization_options));
-    (init_params, connection, io)
+    Ok((
+        init_opts,
+        init_params.capabilities,
+        workspace_folders,
+        connection,
+        io,
+    ))
 }
 
 struct Server {
-    sources: Sources,
+    shared: Arc<Shared>,
+    workers: Workers,
+    /// Configuration of the sources, in priority order.
+    source_options: Vec<SourceOptions>,
+    /// Paths of the workspace folders, which some sources are loaded for.
+    workspace_folders: Vec<PathBuf>,
+    cache_dir: PathBuf,
+    /// Number of sources still loading.
+    loading: usize,
+    loaded_sender: Sender<(usize, Option<PathBuf>, LoadedSource)>,
+    /// Receives sources as they finish loading, or why they failed to, with their position in
+    /// the configuration and the workspace folder they are for.
+    loaded: Receiver<(usize, Option<PathBuf>, LoadedSource)>,
     open_files: OpenFiles,
-    diagnostics: Vec<Diagnostic>,
+    /// Which documents to be active in, by their paths.
+    document_filter: DocumentFilter,
+    /// Documents opened that the filter leaves out, which are ignored until they are closed.
+    ignored_documents: HashSet<String>,
+    /// How to behave in documents of each language.
+    profiles: Profiles,
+    /// Names of the trailers of commit messages to complete and check mailboxes in.
+    trailers: Vec<String>,
+    /// The user's own mailboxes, that mail is sent from.
+    identities: Vec<Mailbox>,
+    /// Days ahead to note the birthdays of recipients in, if at all.
+    birthday_window: Option<u32>,
+    /// Diagnostics for addresses that aren't in the contacts, by file.
+    diagnostics: HashMap<String, Vec<Diagnostic>>,
+    match_options: MatchOptions,
+    group_addresses: bool,
+    /// Whether to insert names that aren't ASCII as encoded-words.
+    encoded_words: bool,
+    completion_separator: Option<String>,
+    name_completion: bool,
+    conflict_diagnostics: bool,
+    /// Whether to say how many addresses are in several sources once they have loaded.
+    duplicate_summary: bool,
+    /// Which contact to merge duplicates into, `None` to ask.
+    merge_into: Option<MergeInto>,
+    render_options: RenderOptions,
+    /// The format the client shows hovers in.
+    hover_format: lsp_types::MarkupKind,
+    /// Which sources goto definition goes to contacts in.
+    definition_sources: DefinitionSources,
+    /// Whether the client takes links in responses to goto definition.
+    definition_links: bool,
+    /// Whether the client takes links in responses to goto type definition.
+    type_definition_links: bool,
+    watcher: Option<Watcher>,
+    /// How often to check the sources for changes, for those that can't be watched.
+    refresh_interval: Option<Duration>,
+    completion_cache: Option<CompletionCache>,
+    /// Where addresses have been used, when tracking usage.
+    usage_log: Option<Arc<UsageLog>>,
+    /// Whether to rank completions by the mail with each address in the notmuch database.
+    notmuch_frecency: bool,
+    /// The addresses the notmuch weights were last counted for, so they are only counted again
+    /// once the contacts change.
+    notmuch_emails: Option<Vec<String>>,
+    /// Whether the notmuch weights are being counted.
+    counting_notmuch: Arc<AtomicBool>,
+    /// Recipients already recorded as sent to for each open file, so saving again doesn't count
+    /// them twice.
+    recorded_recipients: HashMap<String, HashSet<String>>,
+    /// Whether to ask which source to add a contact to when several can take it, as none is
+    /// the default, or the configured one isn't a source.
+    ask_write_source: bool,
+    /// What to do when a document is saved.
+    on_save: Vec<OnSave>,
+    /// Unknown recipients already asked about for each open file, so saving again doesn't ask
+    /// again.
+    asked_recipients: HashMap<String, HashSet<String>>,
+    /// Counts changes to the sources, so cached results can tell when they are out of date.
+    generation: u64,
+    logging: Logging,
+    /// Requests sent to the client, to act on their responses.
+    pending_requests: HashMap<RequestId, PendingRequest>,
+    next_request_id: i32,
     shutdown: bool,
 }
 
-#[derive(Serialize, Deserialize)]
-struct InitializationOptions {
-    vcard_dir: Option<PathBuf>,
-    contact_list_file: Option<PathBuf>,
-    contact_list_diagnostics: Option<bool>,
-    enable_completion: Option<bool>,
-    enable_hover: Option<bool>,
-    enable_code_actions: Option<bool>,
-    enable_goto_definition: Option<bool>,
+/// State read by the workers, while the main loop holds the rest and makes all changes.
+struct Shared {
+    sources: RwLock<Sources>,
+    render_cache: Mutex<RenderCache>,
 }
 
-impl Server {
-    fn new(c: &Connection, params: lsp_types::InitializeParams) -> Self {
-        let init_opts = if let Some(io) = params.initialization_options {
-            match serde_json::from_value::<InitializationOptions>(io) {
-                Ok(v) => v,
-                Err(err) => {
-                    notify(
-                        c,
-                        ShowMessage::METHOD,
-                        format!("Invalid initialization options: {err}"),
-                    );
-                    panic!("Invalid initialization options: {err}")
+impl Shared {
+    fn sources(&self) -> RwLockReadGuard<'_, Sources> {
+        // only a panic during a change poisons the lock, which the sources survive like any other
+        self.sources.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn sources_mut(&self) -> RwLockWriteGuard<'_, Sources> {
+        self.sources.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Render the contact for the mailbox from the sources for the document, reusing the last
+    /// render until the sources or avatars change.
+    fn render(
+        &self,
+        document: Option<PathBuf>,
+        mailbox: Mailbox,
+        generation: (u64, u64, u64),
+    ) -> String {
+        let key = (document, mailbox);
+        {
+            let mut cache = self.render_cache();
+            if cache.generation < generation || cache.renders.len() >= RENDER_CACHE_LIMIT {
+                *cache = RenderCache {
+                    generation,
+                    renders: HashMap::new(),
+                };
+            }
+            if let Some(render) = cache.renders.get(&key) {
+                if cache.generation == generation {
+                    return render.clone();
                 }
             }
-        } else {
-            notify(
-                c,
-                ShowMessage::METHOD,
-                "No initialization options given, need it for vcard directory location at least",
-            );
-            panic!("No initialization options given, need it for vcard directory location at least")
-        };
-        let mut sources = Sources::default();
-        if let Some(vcard_dir) = init_opts.vcard_dir {
-            let vcard_root = if vcard_dir.starts_with("~/") {
-                dirs::home_dir()
-                    .unwrap()
-                    .join(vcard_dir.strip_prefix("~/").unwrap())
-            } else {
-                vcard_dir
-            };
-            sources.sources.push(Box::new(VCards::new(vcard_root)));
         }
-
-        if let Some(contact_list_file) = init_opts.contact_list_file {
-            let contact_list_file = if contact_list_file.starts_with("~/") {
-                dirs::home_dir()
-                    .unwrap()
-                    .join(contact_list_file.strip_prefix("~/").unwrap())
-            } else {
-                contact_list_file
-            };
-            let contact_list_diagnostics = init_opts.contact_list_diagnostics.unwrap_or(false);
-            sources.sources.push(Box::new(ContactList::new(
-                contact_list_file,
-                contact_list_diagnostics,
-            )));
+        // rendered without the cache locked so other workers aren't held up
+        let render = self.sources().for_document(key.0.as_deref()).render(&key.1);
+        let mut cache = self.render_cache();
+        if cache.generation == generation {
+            cache.renders.insert(key, render.clone());
         }
+        render
+    }
 
-        if sources.sources.is_empty() {
-            panic!("Initialization options must specify at least one of `vcard_dir` or `contact_list_file`");
-        }
+    /// Mailboxes of the contacts with the first of the names that a source has, for names
+    /// mentioned in prose.
+    fn named_mailboxes(
+        &self,
+        document: Option<&Path>,
+        names: &[String],
+        options: MatchOptions,
+    ) -> Vec<Mailbox> {
+        let sources = self.sources();
+        let sources = sources.for_document(document);
+        names
+            .iter()
+            .map(|n| sources.find_by_name(n, options))
+            .find(|matches| !matches.is_empty())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| m.mailbox)
+            .collect()
+    }
 
-        Self {
-            sources,
-            open_files: OpenFiles::default(),
-            diagnostics: Vec::new(),
-            shutdown: false,
-        }
+    fn render_cache(&self) -> std::sync::MutexGuard<'_, RenderCache> {
+        self.render_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
     }
+}
 
-    fn serve(mut self, c: Connection) -> Result<(), String> {
-        loop {
-            match c.receiver.recv().unwrap() {
-                Message::Request(r) => {
-                    // log(&c, format!("Got request {r:?}"));
-                    if self.shutdown {
-                        c.sender
-                            .send(response_err(
-                                r.id,
-                                ErrorCode::InvalidRequest as i32,
-                                String::from("received request after shutdown"),
-                            ))
-                            .unwrap();
-                        continue;
-                    }
+/// A request answered by a worker.
+struct Job {
+    id: RequestId,
+    method: String,
+    /// The span of the request, for the worker to handle it in.
+    span: tracing::Span,
+    handle: Box<dyn FnOnce() -> Result<Vec<Message>, Error> + Send>,
+}
 
-                    let messages = match &r.method[..] {
-                        lsp_types::request::HoverRequest::METHOD => self.handle_hover_request(r),
-                        lsp_types::request::GotoDefinition::METHOD => {
-                            self.handle_goto_definition_request(r)
-                        }
-                        lsp_types::request::Completion::METHOD => self.handle_completion_request(r),
-                        lsp_types::request::ResolveCompletionItem::METHOD => {
-                            self.handle_resolve_completion_item_request(r)
-                        }
-                        lsp_types::request::CodeActionRequest::METHOD => {
-                            self.handle_code_action_request(r)
-                        }
-                        lsp_types::request::ExecuteCommand::METHOD => {
-                            self.handle_execute_command_request(r)
-                        }
-                        lsp_types::request::Shutdown::METHOD => {
-                            self.shutdown = true;
-                            vec![response_empty(r.id)]
-                        }
-                        _ => {
-                            log(&c, format!("Unmatched request received: {}", r.method));
-                            vec![]
-                        }
-                    };
-                    for message in messages {
-                        c.sender.send(message).unwrap();
-                    }
-                }
-                Message::Response(r) => log(&c, format!("Unmatched response received: {}", r.id)),
-                Message::Notification(n) => {
-                    let messages = match &n.method[..] {
-                        lsp_types::notification::DidOpenTextDocument::METHOD => {
-                            self.handle_did_open_text_document_notification(n)
-                        }
-                        lsp_types::notification::DidChangeTextDocument::METHOD => {
-                            self.handle_did_change_text_document_notification(n)
-                        }
-                        lsp_types::notification::DidCloseTextDocument::METHOD => {
-                            self.handle_did_close_text_document_notification(n)
-                        }
-                        lsp_types::notification::Exit::METHOD => {
-                            if self.shutdown {
-                                return Ok(());
-                            } else {
-                                return Err(String::from(
-                                    "Received exit notification before shutdown request",
-                                ));
-                            }
-                        }
-                        _ => {
-                            log(&c, format!("Unmatched notification received: {}", n.method));
-                            Vec::new()
+/// Threads answering read-only requests, such as hovers, and sending the responses straight to
+/// the client, so a slow one doesn't hold up the main loop.
+///
+/// Anything reading the open files is done before handing the request over, so the workers only
+/// need the sources.
+struct Workers {
+    jobs: crossbeam_channel::Sender<Job>,
+    /// Whether a request was handed over since this was last taken, so is traced by the worker.
+    spawned: Cell<bool>,
+}
+
+impl Workers {
+    fn new(count: usize, client: crossbeam_channel::Sender<Message>, tracer: Tracer) -> Self {
+        let (jobs, receiver) = crossbeam_channel::unbounded::<Job>();
+        for _ in 0..count {
+            let receiver = receiver.clone();
+            let client = client.clone();
+            let tracer = tracer.clone();
+            // they stop once the server drops the sender
+            std::thread::spawn(move || {
+                for job in receiver {
+                    let _span = job.span.enter();
+                    let start = Instant::now();
+                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job.handle))
+                        .unwrap_or_else(|panic| Err(Error::Internal(panic_message(&*panic))));
+                    let elapsed = start.elapsed();
+                    tracing::debug!(method = %job.method, ?elapsed, "Handled request on a worker");
+                    let trace = tracer.log_trace(
+                        format!("Handled {} on a worker in {elapsed:?}", job.method),
+                        || None,
+                    );
+                    for message in request_messages(job.id, &job.method, result)
+                        .into_iter()
+                        .chain(trace)
+                    {
+                        if client.send(message).is_err() {
+                            return;
                         }
-                    };
-                    for message in messages {
-                        c.sender.send(message).unwrap()
                     }
                 }
-            }
+            });
+        }
+        Self {
+            jobs,
+            spawned: Cell::new(false),
         }
     }
 
-    fn handle_hover_request(&mut self, request: Request) -> Vec<Message> {
-        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)
-            .unwrap();
-
-        let mailbox = self.get_mailbox_from_document(&tdp);
-        let response = if let Some(mailbox) = mailbox {
-            let text = self.sources.render(&mailbox);
-            let resp = lsp_types::Hover {
-                contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
-                    kind: lsp_types::MarkupKind::Markdown,
-                    value: text,
-                }),
-                range: None,
-            };
-            response_ok(request.id, resp)
-        } else {
-            response_empty(request.id)
-        };
+    /// Handle the request on a worker.
+    fn spawn(
+        &self,
+        id: RequestId,
+        method: &str,
+        handle: impl FnOnce() -> Result<Vec<Message>, Error> + Send + 'static,
+    ) {
+        self.spawned.set(true);
+        // the workers only stop after the server
+        let _ = self.jobs.send(Job {
+            id,
+            method: method.to_owned(),
+            span: tracing::Span::current(),
+            handle: Box::new(handle),
+        });
+    }
 
-        vec![response]
+    /// Whether a request was handed over to a worker since this was last called.
+    fn take_spawned(&self) -> bool {
+        self.spawned.replace(false)
     }
+}
 
-    fn handle_goto_definition_request(&mut self, request: Request) -> Vec<Message> {
-        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)
-            .unwrap();
+/// A request sent to the client that is waiting for a response.
+enum PendingRequest {
+    /// Asked whether to create a vcard directory that doesn't exist.
+    CreateDirectory(PathBuf),
+    /// Asked to insert a postal address into a document.
+    InsertAddress,
+    /// Asked to show a document, needing nothing from the reply.
+    ShowDocument,
+    /// Asked which of the contacts of a conflict to merge the others into.
+    Merge(Conflict),
+    /// Asked which source to create the contact of a mailbox in, of the sources by their
+    /// positions in the configuration with the titles they were offered by.
+    CreateContact {
+        document: Option<PathBuf>,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+        targets: Vec<(usize, String)>,
+    },
+    /// Asked whether to add the unknown recipients of a saved message to the contacts.
+    AddRecipients {
+        document: Option<PathBuf>,
+        mailboxes: Vec<Mailbox>,
+    },
+    /// Asked whether to create the contact of a mailbox to add a note to.
+    AddNote {
+        document: Option<PathBuf>,
+        mailbox: Mailbox,
+        note: String,
+    },
+}
 
-        let mut locations = self
-            .get_mailbox_from_document(&tdp)
-            .map(|mailbox| self.sources.locations(&mailbox))
-            .unwrap_or_default();
-        let response = match locations.len() {
-            0 => response_empty(request.id),
-            1 => {
-                let resp = lsp_types::GotoDefinitionResponse::Scalar(locations.remove(0).into());
-                response_ok(request.id, resp)
-            }
-            _ => {
-                let resp = lsp_types::GotoDefinitionResponse::Array(
-                    locations.into_iter().map(|p| p.into()).collect(),
-                );
-                response_ok(request.id, resp)
-            }
-        };
+/// Response to the status request.
+#[derive(Serialize)]
+struct Status {
+    version: &'static str,
+    /// Whether sources are still loading, so results may be incomplete.
+    loading: bool,
+    conflicts: Vec<Conflict>,
+    /// Addresses in more than one source, or that contacts give different names.
+    duplicates: Vec<Duplicate>,
+}
+
+/// All matches of the last completion query, used to answer queries that extend it without
+/// searching the sources again.
+struct CompletionCache {
+    uri: String,
+    line: u32,
+    query: String,
+    matches: Vec<Match>,
+}
 
-        vec![response]
+impl CompletionCache {
+    fn refines(&self, uri: &str, line: u32, query: &str) -> bool {
+        self.uri == uri && self.line == line && query.starts_with(&self.query)
     }
+}
 
-    fn handle_completion_request(&mut self, request: Request) -> Vec<Message> {
-        let mut tdp =
-            serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)
-                .unwrap();
+/// Rendered contacts, as hover and completion resolve are often requested repeatedly for the
+/// same ones.
+#[derive(Default)]
+struct RenderCache {
+    /// Generation of the sources, avatars and usage log the renders are from.
+    generation: (u64, u64, u64),
+    /// Renders by document, as it decides the sources, and mailbox.
+    renders: HashMap<(Option<PathBuf>, Mailbox), String>,
+}
 
-        tdp.position.character = tdp.position.character.saturating_sub(1);
-        let response = match self.get_word_from_document(&tdp) {
-            Some(word) => {
-                let limit = 100;
-                let lower_word = word.to_lowercase();
-                let matches = self.sources.find_matching(lower_word);
-                let completion_items = matches
-                    .map(|(source, mailbox)| CompletionItem {
-                        label: mailbox.to_string(),
-                        kind: Some(CompletionItemKind::TEXT),
-                        label_details: Some(lsp_types::CompletionItemLabelDetails {
-                            detail: Some(source.to_owned()),
-                            description: None,
-                        }),
-                        ..Default::default()
-                    })
-                    .take(limit)
-                    .collect::<Vec<_>>();
-                let resp = lsp_types::CompletionResponse::List(CompletionList {
-                    is_incomplete: completion_items.len() == limit,
-                    items: completion_items,
-                });
-                response_ok(request.id, resp)
-            }
-            None => response_empty(request.id),
-        };
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InitializationOptions {
+    /// Version of the options, for newer ones to be told apart.
+    version: Option<u32>,
+    #[serde(default)]
+    sources: Vec<SourceOptions>,
+    vcard_dir: Option<PathBuf>,
+    contact_list_file: Option<PathBuf>,
+    contact_list_diagnostics: Option<bool>,
+    contact_list_writable: Option<bool>,
+    contact_list_format: Option<ContactListFormat>,
+    contact_list_exclude: Option<Vec<String>>,
+    enable_completion: Option<bool>,
+    enable_hover: Option<bool>,
+    enable_code_actions: Option<bool>,
+    enable_goto_definition: Option<bool>,
+    enable_goto_type_definition: Option<bool>,
+    full_sync: Option<bool>,
+    definition_sources: Option<DefinitionSources>,
+    match_mode: Option<MatchMode>,
+    strip_diacritics: Option<bool>,
+    vcard_completion_kind: Option<String>,
+    contact_list_completion_kind: Option<String>,
+    group_addresses: Option<bool>,
+    encoded_words: Option<bool>,
+    completion_separator: Option<String>,
+    name_completion: Option<bool>,
+    hover_sections: Option<Vec<Section>>,
+    hover_extensions: Option<Vec<String>>,
+    hover_max_items: Option<usize>,
+    hover_note_length: Option<usize>,
+    watch_files: Option<bool>,
+    refresh_interval_secs: Option<u64>,
+    cache_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+    track_usage: Option<bool>,
+    notmuch_frecency: Option<bool>,
+    sent_maildirs: Option<Vec<PathBuf>>,
+    profiles: Option<HashMap<String, ProfileOptions>>,
+    commit_trailers: Option<Vec<String>>,
+    identities: Option<Vec<String>>,
+    birthday_diagnostics: Option<u32>,
+    duplicate_summary: Option<bool>,
+    on_save: Option<Vec<OnSave>>,
+    default_write_source: Option<String>,
+    merge_strategy: Option<MergeStrategy>,
+    merge_preferred_path: Option<PathBuf>,
+    include_paths: Option<Vec<String>>,
+    exclude_paths: Option<Vec<String>>,
+    avatars: Option<AvatarService>,
+    vcard_categories: Option<Vec<String>>,
+    vcard_exclude_categories: Option<Vec<String>>,
+    vcard_new_contact_file: Option<PathBuf>,
+    vcard_conflict_diagnostics: Option<bool>,
+    vcard_follow_symlinks: Option<bool>,
+    vcard_include_hidden: Option<bool>,
+    vcard_extensions: Option<Vec<String>>,
+    vcard_encrypted_extensions: Option<Vec<String>>,
+    vcard_decrypt_command: Option<Vec<String>>,
+    vcard_encrypt_command: Option<Vec<String>>,
+    vcard_encrypt_new: Option<bool>,
+}
 
-        vec![response]
-    }
+/// Something to do when a document is saved.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum OnSave {
+    /// Ask whether to add the recipients of the message that aren't in the contacts.
+    PromptAddUnknown,
+    /// Add the recipients of the message that aren't in the contacts to the sources that
+    /// `harvest` them, without asking.
+    HarvestRecipients,
+}
 
-    fn handle_resolve_completion_item_request(&mut self, request: Request) -> Vec<Message> {
-        let mut ci = serde_json::from_value::<lsp_types::CompletionItem>(request.params).unwrap();
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SourceType {
+    Vcard,
+    ContactList,
+}
 
-        let mailbox = Mailbox::from_str(&ci.label).unwrap();
-        let doc = self.sources.render(&mailbox);
-        ci.documentation = Some(lsp_types::Documentation::MarkupContent(
-            lsp_types::MarkupContent {
-                kind: lsp_types::MarkupKind::Markdown,
-                value: doc,
-            },
-        ));
-        let response = response_ok(request.id, ci);
+/// Configuration of a single contact source.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SourceOptions {
+    #[serde(rename = "type")]
+    source_type: SourceType,
+    path: Option<PathBuf>,
+    /// A `file://` url, as an alternative to `path`.
+    url: Option<Url>,
+    diagnostics: Option<bool>,
+    read_only: Option<bool>,
+    label: Option<String>,
+    format: Option<ContactListFormat>,
+    priority: Option<i32>,
+    completion_kind: Option<String>,
+    exclude: Option<Vec<String>>,
+    categories: Option<Vec<String>>,
+    exclude_categories: Option<Vec<String>>,
+    new_contact_file: Option<PathBuf>,
+    follow_symlinks: Option<bool>,
+    include_hidden: Option<bool>,
+    extensions: Option<Vec<String>>,
+    encrypted_extensions: Option<Vec<String>>,
+    decrypt_command: Option<Vec<String>>,
+    encrypt_command: Option<Vec<String>>,
+    encrypt_new: Option<bool>,
+    disk_index: Option<bool>,
+    harvest: Option<bool>,
+    /// Names or paths of the workspace folders to use the source in.
+    workspace_folders: Option<Vec<String>>,
+}
 
-        vec![response]
+impl SourceOptions {
+    fn new(source_type: SourceType, path: PathBuf) -> Self {
+        Self {
+            source_type,
+            path: Some(path),
+            url: None,
+            diagnostics: None,
+            read_only: None,
+            label: None,
+            format: None,
+            priority: None,
+            completion_kind: None,
+            exclude: None,
+            categories: None,
+            exclude_categories: None,
+            new_contact_file: None,
+            follow_symlinks: None,
+            include_hidden: None,
+            extensions: None,
+            encrypted_extensions: None,
+            decrypt_command: None,
+            encrypt_command: None,
+            encrypt_new: None,
+            disk_index: None,
+            harvest: None,
+            workspace_folders: None,
+        }
     }
 
-    fn handle_code_action_request(&mut self, request: Request) -> Vec<Message> {
-        let cap = serde_json::from_value::<lsp_types::CodeActionParams>(request.params).unwrap();
-
-        let tdp = TextDocumentPositionParams {
-            text_document: cap.text_document,
-            position: cap.range.start,
-        };
+    /// Options that are valid on their own but don't make sense together.
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        if self.path.is_some() && self.url.is_some() {
+            conflicts.push("`path` and `url` are both set, using `path`".to_owned());
+        }
+        let (other_type, other_options) = match self.source_type {
+            SourceType::Vcard => (
+                "contact_list",
+                [
+                    ("format", self.format.is_some()),
+                    ("exclude", self.exclude.is_some()),
+                    ("disk_index", self.disk_index.is_some()),
+                ]
+                .to_vec(),
+            ),
+            SourceType::ContactList => (
+                "vcard",
+                [
+                    ("categories", self.categories.is_some()),
+                    ("exclude_categories", self.exclude_categories.is_some()),
+                    ("new_contact_file", self.new_contact_file.is_some()),
+                    ("follow_symlinks", self.follow_symlinks.is_some()),
+                    ("include_hidden", self.include_hidden.is_some()),
+                    ("extensions", self.extensions.is_some()),
+                    ("encrypted_extensions", self.encrypted_extensions.is_some()),
+                    ("decrypt_command", self.decrypt_command.is_some()),
+                    ("encrypt_command", self.encrypt_command.is_some()),
+                    ("encrypt_new", self.encrypt_new.is_some()),
+                ]
+                .to_vec(),
+            ),
+        };
+        for (option, _) in other_options.iter().filter(|(_, set)| *set) {
+            conflicts.push(format!("`{option}` only applies to {other_type} sources"));
+        }
+        if self.encrypt_new == Some(true) && self.encrypt_command.is_none() {
+            conflicts.push("`encrypt_new` needs an `encrypt_command`".to_owned());
+        }
+        if self.read_only == Some(true) && self.new_contact_file.is_some() {
+            conflicts.push("`new_contact_file` is unused as the source is `read_only`".to_owned());
+        }
+        // contact lists are read only unless they are set not to be
+        let read_only = self
+            .read_only
+            .unwrap_or(matches!(self.source_type, SourceType::ContactList));
+        if self.harvest == Some(true) && read_only {
+            conflicts.push("`harvest` needs the source not to be `read_only`".to_owned());
+        }
+        conflicts
+    }
+
+    /// Whether the source is loaded for each workspace folder, as it is limited to some or has a
+    /// path relative to them.
+    fn per_folder(&self) -> bool {
+        self.workspace_folders.is_some() || source_path(self).is_ok_and(|p| p.is_relative())
+    }
+
+    /// Whether to use the source in the workspace folder with the name and path.
+    fn applies_to(&self, name: &str, path: &Path) -> bool {
+        self.workspace_folders.as_ref().is_none_or(|folders| {
+            folders
+                .iter()
+                .any(|f| f == name || expand_path(Path::new(f)).is_ok_and(|f| f == path))
+        })
+    }
+
+    /// The options for the source in the workspace folder, with a relative path from it.
+    fn in_folder(&self, folder: &Path) -> Result<Self, String> {
+        Ok(Self {
+            path: Some(folder.join(source_path(self)?)),
+            url: None,
+            ..self.clone()
+        })
+    }
+}
+
+impl InitializationOptions {
+    /// Parse the options, failing on unknown keys and wrong types so mistakes don't go unnoticed.
+    fn parse(value: serde_json::Value) -> Result<Self, String> {
+        if let Some(version) = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .filter(|v| *v > OPTIONS_VERSION as u64)
+        {
+            return Err(format!(
+                "Initialization options are version {version}, but only up to version \
+                 {OPTIONS_VERSION} is supported, maills needs updating"
+            ));
+        }
+        serde_json::from_value::<Self>(value.clone()).map_err(|err| {
+            // point at the source at fault, there may be many
+            let source_err = value
+                .get("sources")
+                .and_then(|s| s.as_array())
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .find_map(|(i, source)| {
+                    let err = serde_json::from_value::<SourceOptions>(source.clone()).err()?;
+                    Some(format!("`sources[{i}]`: {err}"))
+                });
+            format!(
+                "Invalid initialization options: {}",
+                source_err.unwrap_or_else(|| err.to_string())
+            )
+        })
+    }
+
+    /// Options that are valid on their own but don't make sense together, e.g. ones that would be
+    /// ignored.
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        match (self.merge_strategy, &self.merge_preferred_path) {
+            (Some(MergeStrategy::PreferPath), None) => conflicts
+                .push("the `prefer_path` merge_strategy needs a `merge_preferred_path`".to_owned()),
+            (Some(MergeStrategy::PreferPath), Some(_)) | (_, None) => {}
+            (_, Some(_)) => conflicts.push(
+                "`merge_preferred_path` only applies to the `prefer_path` merge_strategy"
+                    .to_owned(),
+            ),
+        }
+        let legacy = [
+            (
+                "vcard_dir",
+                self.vcard_dir.is_some(),
+                [
+                    (
+                        "vcard_completion_kind",
+                        self.vcard_completion_kind.is_some(),
+                    ),
+                    ("vcard_categories", self.vcard_categories.is_some()),
+                    (
+                        "vcard_exclude_categories",
+                        self.vcard_exclude_categories.is_some(),
+                    ),
+                    (
+                        "vcard_new_contact_file",
+                        self.vcard_new_contact_file.is_some(),
+                    ),
+                    (
+                        "vcard_follow_symlinks",
+                        self.vcard_follow_symlinks.is_some(),
+                    ),
+                    ("vcard_include_hidden", self.vcard_include_hidden.is_some()),
+                    ("vcard_extensions", self.vcard_extensions.is_some()),
+                    (
+                        "vcard_encrypted_extensions",
+                        self.vcard_encrypted_extensions.is_some(),
+                    ),
+                    (
+                        "vcard_decrypt_command",
+                        self.vcard_decrypt_command.is_some(),
+                    ),
+                    (
+                        "vcard_encrypt_command",
+                        self.vcard_encrypt_command.is_some(),
+                    ),
+                    ("vcard_encrypt_new", self.vcard_encrypt_new.is_some()),
+                ]
+                .to_vec(),
+            ),
+            (
+                "contact_list_file",
+                self.contact_list_file.is_some(),
+                [
+                    (
+                        "contact_list_diagnostics",
+                        self.contact_list_diagnostics.is_some(),
+                    ),
+                    (
+                        "contact_list_writable",
+                        self.contact_list_writable.is_some(),
+                    ),
+                    ("contact_list_format", self.contact_list_format.is_some()),
+                    ("contact_list_exclude", self.contact_list_exclude.is_some()),
+                    (
+                        "contact_list_completion_kind",
+                        self.contact_list_completion_kind.is_some(),
+                    ),
+                ]
+                .to_vec(),
+            ),
+        ];
+        for (main, main_set, options) in legacy {
+            for (option, _) in options.iter().filter(|(_, set)| *set && !main_set) {
+                conflicts.push(format!("`{option}` is ignored without `{main}`"));
+            }
+        }
+        if self
+            .on_save
+            .iter()
+            .flatten()
+            .any(|o| *o == OnSave::HarvestRecipients)
+            && !self.sources.iter().any(|s| s.harvest == Some(true))
+        {
+            conflicts.push(
+                "the `harvest_recipients` on_save needs a source with `harvest` set".to_owned(),
+            );
+        }
+        for (i, source) in self.sources.iter().enumerate() {
+            conflicts.extend(
+                source
+                    .conflicts()
+                    .into_iter()
+                    .map(|c| format!("`sources[{i}]`: {c}")),
+            );
+        }
+        conflicts
+    }
+
+    /// All of the sources, higher priority ones first, otherwise keeping the configured order.
+    fn source_options(&self) -> Vec<SourceOptions> {
+        let mut sources = self.legacy_sources();
+        sources.extend(self.sources.iter().cloned());
+        sources.sort_by_key(|o| std::cmp::Reverse(o.priority.unwrap_or(0)));
+        sources
+    }
+
+    fn match_options(&self) -> MatchOptions {
+        MatchOptions {
+            mode: self.match_mode.unwrap_or_default(),
+            strip_diacritics: self.strip_diacritics.unwrap_or(true),
+        }
+    }
+
+    /// Which contact to merge duplicates into, `None` if the user is to be asked for each
+    /// conflict.
+    fn merge_into(&self) -> Result<Option<MergeInto>, String> {
+        let preferred_path = self
+            .merge_preferred_path
+            .as_deref()
+            .map(expand_path)
+            .transpose()
+            .map_err(|err| format!("Invalid merge_preferred_path: {err}"))?;
+        Ok(self
+            .merge_strategy
+            .unwrap_or_default()
+            .into(preferred_path.as_deref()))
+    }
+
+    /// The configured `cache_dir`, or the default one if there isn't one.
+    fn cache_dir(&self) -> Result<PathBuf, String> {
+        match &self.cache_dir {
+            Some(cache_dir) => {
+                expand_path(cache_dir).map_err(|err| format!("Invalid cache_dir: {err}"))
+            }
+            None => Ok(default_cache_dir()),
+        }
+    }
+
+    /// The configured `data_dir`, or the default one if there isn't one.
+    fn data_dir(&self) -> Result<PathBuf, String> {
+        match &self.data_dir {
+            Some(data_dir) => {
+                expand_path(data_dir).map_err(|err| format!("Invalid data_dir: {err}"))
+            }
+            None => Ok(default_data_dir()),
+        }
+    }
+
+    /// The position among [`source_options`](Self::source_options) of the `default_write_source`,
+    /// the first with it as its label, or otherwise its type.
+    fn write_source(&self) -> Result<Option<usize>, String> {
+        let Some(name) = &self.default_write_source else {
+            return Ok(None);
+        };
+        let sources = self.source_options();
+        sources
+            .iter()
+            .position(|o| o.label.as_ref() == Some(name))
+            .or_else(|| {
+                sources.iter().position(|o| {
+                    serde_json::to_value(o.source_type).is_ok_and(|t| t == name.as_str())
+                })
+            })
+            .map(Some)
+            .ok_or_else(|| {
+                format!("Invalid default_write_source: no source has the label or type {name:?}")
+            })
+    }
+
+    /// Whether to ask the client to send the whole document on each change, as configured or
+    /// when it says nothing of syncing documents, as simple clients may only do that.
+    fn full_sync(&self, capabilities: &ClientCapabilities) -> bool {
+        self.full_sync.unwrap_or_else(|| {
+            capabilities
+                .text_document
+                .as_ref()
+                .is_none_or(|t| t.synchronization.is_none())
+        })
+    }
+
+    /// Load the usage log from the data directory, unless usage isn't tracked.
+    fn usage_log(&self) -> Result<Option<UsageLog>, String> {
+        if !self.track_usage.unwrap_or(true) {
+            return Ok(None);
+        }
+        let path = self.data_dir()?.join("usage.db");
+        UsageLog::load(path.clone())
+            .map(Some)
+            .map_err(|err| format!("Failed to load usage from {}: {err}", path.display()))
+    }
+
+    /// Sources from the deprecated `vcard_*` and `contact_list_*` options.
+    fn legacy_sources(&self) -> Vec<SourceOptions> {
+        let mut sources = Vec::new();
+        if let Some(vcard_dir) = &self.vcard_dir {
+            let mut options = SourceOptions::new(SourceType::Vcard, vcard_dir.clone());
+            options.completion_kind = self.vcard_completion_kind.clone();
+            options.categories = self.vcard_categories.clone();
+            options.exclude_categories = self.vcard_exclude_categories.clone();
+            options.new_contact_file = self.vcard_new_contact_file.clone();
+            options.follow_symlinks = self.vcard_follow_symlinks;
+            options.include_hidden = self.vcard_include_hidden;
+            options.extensions = self.vcard_extensions.clone();
+            options.encrypted_extensions = self.vcard_encrypted_extensions.clone();
+            options.decrypt_command = self.vcard_decrypt_command.clone();
+            options.encrypt_command = self.vcard_encrypt_command.clone();
+            options.encrypt_new = self.vcard_encrypt_new;
+            sources.push(options);
+        }
+        if let Some(contact_list_file) = &self.contact_list_file {
+            let mut options =
+                SourceOptions::new(SourceType::ContactList, contact_list_file.clone());
+            options.diagnostics = self.contact_list_diagnostics;
+            options.read_only = self.contact_list_writable.map(|w| !w);
+            options.format = self.contact_list_format;
+            options.exclude = self.contact_list_exclude.clone();
+            options.completion_kind = self.contact_list_completion_kind.clone();
+            sources.push(options);
+        }
+        sources
+    }
+}
+
+/// Loads a contact source, which can take a while for large ones.
+type SourceLoader = Box<dyn FnOnce() -> Box<dyn ContactSource + Send + Sync> + Send>;
+
+/// A loaded source, or why it failed to load.
+type LoadedSource = Result<Box<dyn ContactSource + Send + Sync>, String>;
+
+fn default_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("maills")
+}
+
+fn default_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("maills")
+}
+
+/// The path of a source, from its `path` or `url`.
+fn source_path(options: &SourceOptions) -> Result<PathBuf, String> {
+    Ok(match (&options.path, &options.url) {
+        (Some(path), _) => expand_path(path)?,
+        (None, Some(url)) => url
+            .to_file_path()
+            .map_err(|()| format!("Unsupported source url {url}, only file urls are supported"))?,
+        (None, None) => return Err("Sources need a `path` or `url`".to_owned()),
+    })
+}
+
+/// Check the options of a source, returning how to load it or why it can't be.
+fn build_source(
+    options: &SourceOptions,
+    render_options: &RenderOptions,
+    avatars: Option<&Arc<Avatars>>,
+    cache_dir: &Path,
+) -> Result<SourceLoader, String> {
+    let path = source_path(options)?;
+    let new_contact_file = options
+        .new_contact_file
+        .as_deref()
+        .map(expand_path)
+        .transpose()?;
+    let kind = options
+        .completion_kind
+        .as_deref()
+        .map(completion_kind)
+        .transpose()?;
+    let options = options.clone();
+    Ok(match options.source_type {
+        SourceType::Vcard => {
+            let render_options = render_options.clone();
+            Box::new(move || {
+                let mut vcards = VCards::new(path)
+                    .with_diagnostics(options.diagnostics.unwrap_or(true))
+                    .with_writable(!options.read_only.unwrap_or(false))
+                    .with_harvest(options.harvest.unwrap_or(false));
+                if let Some(label) = options.label {
+                    vcards = vcards.with_label(label);
+                }
+                if options.follow_symlinks.is_some()
+                    || options.include_hidden.is_some()
+                    || options.extensions.is_some()
+                {
+                    let defaults = ScanOptions::default();
+                    vcards = vcards.with_scan_options(ScanOptions {
+                        follow_symlinks: options
+                            .follow_symlinks
+                            .unwrap_or(defaults.follow_symlinks),
+                        include_hidden: options.include_hidden.unwrap_or(defaults.include_hidden),
+                        extensions: options.extensions.unwrap_or(defaults.extensions),
+                    });
+                }
+                if let Some(decrypt_command) = options.decrypt_command {
+                    vcards = vcards.with_encryption(Encryption {
+                        extensions: options
+                            .encrypted_extensions
+                            .unwrap_or_else(|| vec!["gpg".to_owned(), "age".to_owned()]),
+                        decrypt_command,
+                        encrypt_command: options.encrypt_command,
+                        encrypt_new: options.encrypt_new.unwrap_or(false),
+                    });
+                }
+                if let Some(kind) = kind {
+                    vcards = vcards.with_completion_kind(kind);
+                }
+                vcards = vcards.with_render_options(render_options);
+                if options.categories.is_some() || options.exclude_categories.is_some() {
+                    vcards = vcards.with_category_filter(CategoryFilter {
+                        include: options.categories.unwrap_or_default(),
+                        exclude: options.exclude_categories.unwrap_or_default(),
+                    });
+                }
+                if let Some(file) = new_contact_file {
+                    vcards = vcards.with_new_contact_file(file);
+                }
+                Box::new(vcards)
+            })
+        }
+        SourceType::ContactList => {
+            let read_only = options.read_only.unwrap_or(true);
+            if read_only && !path.exists() {
+                // writable lists are created when the first contact is added
+                return Err(format!(
+                    "Contact list {} doesn't exist, leaving it out",
+                    path.display()
+                ));
+            }
+            let exclude = options
+                .exclude
+                .as_deref()
+                .map(ExcludePatterns::new)
+                .transpose()
+                .map_err(|err| format!("Invalid exclude pattern: {err}"))?;
+            let avatars = avatars.cloned();
+            let index_dir = cache_dir.join("index");
+            Box::new(move || {
+                let diagnostics = options.diagnostics.unwrap_or(false);
+                let mut contact_list = if options.disk_index.unwrap_or(false) {
+                    ContactList::indexed(
+                        path,
+                        diagnostics,
+                        options.format.unwrap_or_default(),
+                        index_dir,
+                    )
+                } else {
+                    let mut contact_list = ContactList::new(path, diagnostics);
+                    if let Some(format) = options.format {
+                        contact_list = contact_list.with_format(format);
+                    }
+                    contact_list
+                }
+                .with_writable(!read_only)
+                .with_harvest(options.harvest.unwrap_or(false));
+                if let Some(label) = options.label {
+                    contact_list = contact_list.with_label(label);
+                }
+                if let Some(exclude) = exclude {
+                    contact_list = contact_list.with_exclude(exclude);
+                }
+                if let Some(kind) = kind {
+                    contact_list = contact_list.with_completion_kind(kind);
+                }
+                if let Some(avatars) = avatars {
+                    contact_list = contact_list.with_avatars(avatars);
+                }
+                Box::new(contact_list)
+            })
+        }
+    })
+}
+
+impl Server {
+    fn new(
+        c: &Connection,
+        init_opts: InitializationOptions,
+        capabilities: &ClientCapabilities,
+        workspace_folders: Vec<WorkspaceFolder>,
+        logging: Logging,
+    ) -> Self {
+        let merge_into = init_opts.merge_into().unwrap_or_else(|err| {
+            notify(c, MessageType::ERROR, err);
+            Some(MergeInto::First)
+        });
+        let cache_dir = init_opts.cache_dir().unwrap_or_else(|err| {
+            notify(c, MessageType::ERROR, err);
+            default_cache_dir()
+        });
+        let avatars = init_opts
+            .avatars
+            .map(|service| Arc::new(Avatars::new(service, cache_dir.join("avatars"))));
+        let mut render_options = RenderOptions {
+            avatars,
+            max_items: init_opts.hover_max_items,
+            note_length: init_opts.hover_note_length,
+            ..Default::default()
+        };
+        if let Some(sections) = &init_opts.hover_sections {
+            render_options.sections = sections.clone();
+        }
+        if let Some(extensions) = &init_opts.hover_extensions {
+            render_options.extensions = extensions.clone();
+        }
+        if !init_opts.legacy_sources().is_empty() {
+            log(
+                c,
+                MessageType::WARNING,
+                "The vcard_* and contact_list_* options are deprecated, use `sources` instead",
+            );
+        }
+        let source_options = init_opts.source_options();
+        if source_options.is_empty() {
+            notify(
+                c,
+                MessageType::ERROR,
+                "Initialization options must specify at least one source",
+            );
+        }
+
+        let watcher = if init_opts.watch_files.unwrap_or(true) {
+            match Watcher::new(&[]) {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    log(
+                        c,
+                        MessageType::ERROR,
+                        format!("Failed to watch contact files: {err}"),
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let profiles = Profiles::new(init_opts.profiles.clone().unwrap_or_default())
+            .unwrap_or_else(|err| {
+                notify(c, MessageType::ERROR, format!("Invalid profiles: {err}"));
+                Profiles::default()
+            });
+        let document_filter = DocumentFilter::new(
+            init_opts.include_paths.as_deref().unwrap_or_default(),
+            init_opts.exclude_paths.as_deref().unwrap_or_default(),
+        )
+        .unwrap_or_else(|err| {
+            notify(
+                c,
+                MessageType::ERROR,
+                format!("Invalid document paths: {err}"),
+            );
+            DocumentFilter::default()
+        });
+        let usage_log = init_opts
+            .usage_log()
+            .unwrap_or_else(|err| {
+                notify(c, MessageType::ERROR, err);
+                None
+            })
+            .map(Arc::new);
+        let mut sources = Sources::default();
+        if let Some(usage_log) = &usage_log {
+            sources.set_usage_log(usage_log.clone());
+        }
+        let write_source = init_opts.write_source().unwrap_or_else(|err| {
+            notify(c, MessageType::ERROR, err);
+            None
+        });
+        sources.set_write_source(write_source);
+
+        let (loaded_sender, loaded) = crossbeam_channel::unbounded();
+        let mut server = Self {
+            shared: Arc::new(Shared {
+                sources: RwLock::new(sources),
+                render_cache: Mutex::new(RenderCache::default()),
+            }),
+            workers: Workers::new(WORKERS, c.sender.clone(), logging.tracer.clone()),
+            source_options,
+            workspace_folders: Vec::new(),
+            cache_dir,
+            loading: 0,
+            loaded_sender,
+            loaded,
+            open_files: OpenFiles::default(),
+            document_filter,
+            ignored_documents: HashSet::new(),
+            profiles,
+            identities: init_opts
+                .identities
+                .iter()
+                .flatten()
+                .filter_map(|i| Mailbox::from_str(i).ok())
+                .collect(),
+            birthday_window: init_opts.birthday_diagnostics,
+            trailers: init_opts
+                .commit_trailers
+                .clone()
+                .unwrap_or_else(|| headers::DEFAULT_TRAILERS.map(str::to_owned).to_vec()),
+            diagnostics: HashMap::new(),
+            match_options: init_opts.match_options(),
+            group_addresses: init_opts.group_addresses.unwrap_or(false),
+            encoded_words: init_opts.encoded_words.unwrap_or(false),
+            completion_separator: init_opts.completion_separator,
+            name_completion: init_opts.name_completion.unwrap_or(false),
+            conflict_diagnostics: init_opts.vcard_conflict_diagnostics.unwrap_or(false),
+            duplicate_summary: init_opts.duplicate_summary.unwrap_or(false),
+            merge_into,
+            render_options,
+            hover_format: hover_format(capabilities),
+            definition_sources: init_opts.definition_sources.unwrap_or_default(),
+            definition_links: capabilities
+                .text_document
+                .as_ref()
+                .and_then(|t| t.definition?.link_support)
+                .unwrap_or(false),
+            type_definition_links: capabilities
+                .text_document
+                .as_ref()
+                .and_then(|t| t.type_definition?.link_support)
+                .unwrap_or(false),
+            watcher,
+            refresh_interval: init_opts
+                .refresh_interval_secs
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            completion_cache: None,
+            usage_log,
+            notmuch_frecency: init_opts.notmuch_frecency.unwrap_or(false),
+            notmuch_emails: None,
+            counting_notmuch: Arc::new(AtomicBool::new(false)),
+            recorded_recipients: HashMap::new(),
+            ask_write_source: write_source.is_none(),
+            on_save: init_opts.on_save.clone().unwrap_or_default(),
+            asked_recipients: HashMap::new(),
+            generation: 0,
+            logging,
+            pending_requests: HashMap::new(),
+            next_request_id: 1,
+            shutdown: false,
+        };
+        let sent_maildirs = init_opts
+            .sent_maildirs
+            .iter()
+            .flatten()
+            .map(|path| expand_path(path).map_err(|err| format!("Invalid sent_maildirs: {err}")))
+            .collect::<Result<Vec<_>, _>>();
+        match sent_maildirs {
+            Ok(maildirs) if maildirs.is_empty() => {}
+            Ok(maildirs) => server.load_sent_weights(maildirs),
+            Err(err) => notify(c, MessageType::ERROR, err),
+        }
+
+        // without workspace folders, relative paths are from the working directory as usual
+        let mut messages = Vec::new();
+        for i in 0..server.source_options.len() {
+            let options = &server.source_options[i];
+            let unscoped = workspace_folders.is_empty() && options.workspace_folders.is_none();
+            if unscoped || !options.per_folder() {
+                messages.extend(server.load_source(i, None));
+            }
+        }
+        for folder in workspace_folders {
+            messages.extend(server.add_workspace_folder(folder));
+        }
+        let _ = send(c, messages);
+        server
+    }
+
+    /// Start loading a source in the background, for the documents in the folder if given.
+    ///
+    /// Sources are loaded in the background so that requests are answered straight away, from
+    /// whatever has loaded so far.
+    fn load_source(&mut self, i: usize, folder: Option<&Path>) -> Vec<Message> {
+        let options = match folder {
+            Some(folder) => match self.source_options[i].in_folder(folder) {
+                Ok(options) => options,
+                Err(err) => return vec![show_message(MessageType::ERROR, err)],
+            },
+            None => self.source_options[i].clone(),
+        };
+        let mut messages = Vec::new();
+        if let (SourceType::Vcard, Ok(path)) = (options.source_type, source_path(&options)) {
+            if !path.exists() {
+                // the source still loads, empty until the directory is there
+                let message = format!(
+                    "Contacts directory {} doesn't exist, create it?",
+                    path.display()
+                );
+                messages.push(self.request(
+                    lsp_types::request::ShowMessageRequest::METHOD,
+                    lsp_types::ShowMessageRequestParams {
+                        typ: MessageType::WARNING,
+                        message,
+                        actions: Some(vec![lsp_types::MessageActionItem {
+                            title: "Create".to_owned(),
+                            properties: HashMap::new(),
+                        }]),
+                    },
+                    PendingRequest::CreateDirectory(path),
+                ));
+            }
+        }
+        // a misconfigured source is left out, keeping the others
+        let load = match build_source(
+            &options,
+            &self.render_options,
+            self.render_options.avatars.as_ref(),
+            &self.cache_dir,
+        ) {
+            Ok(load) => load,
+            Err(err) => {
+                messages.push(show_message(MessageType::ERROR, err));
+                return messages;
+            }
+        };
+        self.loading += 1;
+        let loaded_sender = self.loaded_sender.clone();
+        let path = source_path(&options).unwrap_or_default();
+        let folder = folder.map(Path::to_owned);
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            let source = std::panic::catch_unwind(std::panic::AssertUnwindSafe(load))
+                .map_err(|panic| panic_message(&*panic));
+            match &source {
+                Ok(source) => tracing::info!(
+                    path = %path.display(),
+                    contacts = source.contact_count(),
+                    elapsed = ?start.elapsed(),
+                    "Loaded contact source"
+                ),
+                Err(err) => tracing::error!(
+                    path = %path.display(),
+                    "Failed to load contact source: {err}"
+                ),
+            }
+            let _ = loaded_sender.send((i, folder, source));
+        });
+        messages
+    }
+
+    /// Load the sources for a workspace folder.
+    fn add_workspace_folder(&mut self, folder: WorkspaceFolder) -> Vec<Message> {
+        let Ok(path) = folder.uri.to_file_path() else {
+            return vec![log_message(
+                MessageType::WARNING,
+                format!("Ignoring workspace folder {}, not a local path", folder.uri),
+            )];
+        };
+        let mut messages = Vec::new();
+        for i in 0..self.source_options.len() {
+            let options = &self.source_options[i];
+            if options.per_folder() && options.applies_to(&folder.name, &path) {
+                messages.extend(self.load_source(i, Some(&path)));
+            }
+        }
+        self.workspace_folders.push(path);
+        messages
+    }
+
+    /// Drop the sources of a workspace folder.
+    fn remove_workspace_folder(&mut self, folder: WorkspaceFolder) {
+        let Ok(path) = folder.uri.to_file_path() else {
+            return;
+        };
+        self.workspace_folders.retain(|f| *f != path);
+        if self.shared.sources_mut().remove_folder(&path) {
+            self.sources_changed();
+        }
+    }
+
+    /// A request to the client, remembering it to handle the response.
+    fn request(
+        &mut self,
+        method: &str,
+        params: impl Serialize,
+        pending: PendingRequest,
+    ) -> Message {
+        let id = RequestId::from(self.next_request_id);
+        self.next_request_id += 1;
+        self.pending_requests.insert(id.clone(), pending);
+        Message::Request(Request::new(id, method.to_owned(), params))
+    }
+
+    fn handle_response(&mut self, pending: PendingRequest, response: Response) -> Vec<Message> {
+        match pending {
+            PendingRequest::CreateDirectory(path) => {
+                let chosen = response
+                    .result
+                    .and_then(|r| serde_json::from_value::<lsp_types::MessageActionItem>(r).ok());
+                if chosen.is_none() {
+                    return Vec::new();
+                }
+                if let Err(err) = std::fs::create_dir_all(&path) {
+                    return vec![show_message(
+                        MessageType::ERROR,
+                        format!("Failed to create {}: {err}", path.display()),
+                    )];
+                }
+                let mut messages = Vec::new();
+                if let Some(watcher) = &mut self.watcher {
+                    if let Err(err) = watcher.watch(std::slice::from_ref(&path)) {
+                        messages.push(log_message(
+                            MessageType::ERROR,
+                            format!("Failed to watch contact files: {err}"),
+                        ));
+                    }
+                }
+                messages.extend(self.handle_changed_files(vec![path]));
+                messages
+            }
+            PendingRequest::CreateContact {
+                document,
+                mailbox,
+                name,
+                targets,
+            } => {
+                let chosen = response
+                    .result
+                    .and_then(|r| serde_json::from_value::<lsp_types::MessageActionItem>(r).ok());
+                let Some(position) = chosen.and_then(|chosen| {
+                    targets
+                        .iter()
+                        .find(|(_, title)| *title == chosen.title)
+                        .map(|(position, _)| *position)
+                }) else {
+                    return Vec::new();
+                };
+                let created = self.shared.sources_mut().create_contact_at(
+                    position,
+                    document.as_deref(),
+                    mailbox.clone(),
+                    name,
+                );
+                self.created(mailbox, created)
+            }
+            PendingRequest::AddRecipients {
+                document,
+                mailboxes,
+            } => {
+                let chosen = response
+                    .result
+                    .and_then(|r| serde_json::from_value::<lsp_types::MessageActionItem>(r).ok());
+                if chosen.is_none() {
+                    return Vec::new();
+                }
+                let mut messages = Vec::new();
+                let mut sources = self.shared.sources_mut();
+                for mailbox in mailboxes {
+                    if let Some((source, location)) =
+                        sources.create_contact_for(document.as_deref(), mailbox.clone(), None)
+                    {
+                        messages.push(contact_created(mailbox, source, &location));
+                    }
+                }
+                drop(sources);
+                self.sources_changed();
+                messages.extend(self.publish_diagnostics());
+                messages
+            }
+            PendingRequest::AddNote {
+                document,
+                mailbox,
+                note,
+            } => {
+                let chosen = response
+                    .result
+                    .and_then(|r| serde_json::from_value::<lsp_types::MessageActionItem>(r).ok());
+                if chosen.is_none() {
+                    return Vec::new();
+                }
+                let mut sources = self.shared.sources_mut();
+                let created =
+                    sources.create_contact_for(document.as_deref(), mailbox.clone(), None);
+                let location = sources.add_note_for(document.as_deref(), &mailbox, &note);
+                drop(sources);
+                self.sources_changed();
+                let mut messages = self.note_added(&mailbox, location);
+                if let Some((source, location)) = created {
+                    messages.push(contact_created(mailbox, source, &location));
+                }
+                messages
+            }
+            PendingRequest::Merge(conflict) => {
+                let chosen = response
+                    .result
+                    .and_then(|r| serde_json::from_value::<lsp_types::MessageActionItem>(r).ok());
+                let Some(i) = chosen.and_then(|chosen| {
+                    conflict
+                        .locations
+                        .iter()
+                        .position(|l| location_title(l) == chosen.title)
+                }) else {
+                    return Vec::new();
+                };
+                self.merge(&conflict, &MergeInto::Location(i))
+            }
+            PendingRequest::ShowDocument => Vec::new(),
+            PendingRequest::InsertAddress => {
+                let Some(response) = response.result.and_then(|r| {
+                    serde_json::from_value::<lsp_types::ApplyWorkspaceEditResponse>(r).ok()
+                }) else {
+                    return Vec::new();
+                };
+                if response.applied {
+                    return Vec::new();
+                }
+                vec![show_message(
+                    MessageType::ERROR,
+                    format!(
+                        "Failed to insert the postal address: {}",
+                        response.failure_reason.unwrap_or_default()
+                    ),
+                )]
+            }
+        }
+    }
+
+    fn serve(mut self, c: Connection) -> Result<(), String> {
+        let changes = self
+            .watcher
+            .as_ref()
+            .map_or_else(crossbeam_channel::never, |w| w.receiver.clone());
+        let loaded = self.loaded.clone();
+        let refresh = self
+            .refresh_interval
+            .map_or_else(crossbeam_channel::never, crossbeam_channel::tick);
+        loop {
+            let message = crossbeam_channel::select! {
+                recv(c.receiver) -> message => match message {
+                    Ok(message) => message,
+                    Err(_) => return Err(String::from("Client disconnected")),
+                },
+                recv(loaded) -> source => {
+                    // the server holds a sender, so this never disconnects
+                    if let Ok((i, folder, source)) = source {
+                        send(&c, self.handle_loaded_source(i, folder, source))?;
+                    }
+                    continue;
+                }
+                recv(changes) -> changed => {
+                    // take any other pending changes too, editors and sync tools make several
+                    let mut changed = changed.unwrap_or_default();
+                    changed.extend(changes.try_iter().flatten());
+                    send(&c, self.handle_changed_files(changed))?;
+                    continue;
+                }
+                recv(refresh) -> _ => {
+                    // requests are handled in between, so never see a partly refreshed source
+                    let paths = self.shared.sources().watched_paths();
+                    send(&c, self.handle_changed_files(paths))?;
+                    continue;
+                }
+            };
+            match message {
+                Message::Request(r) => {
+                    // log(&c, format!("Got request {r:?}"));
+                    if self.shutdown {
+                        send(
+                            &c,
+                            vec![response_err(
+                                r.id,
+                                ErrorCode::InvalidRequest as i32,
+                                String::from("received request after shutdown"),
+                            )],
+                        )?;
+                        continue;
+                    }
+
+                    if self.ignores(&r.params) {
+                        send(&c, vec![response_empty(r.id)])?;
+                        continue;
+                    }
+
+                    let id = r.id.clone();
+                    let method = r.method.clone();
+                    let span = tracing::debug_span!("request", %method);
+                    let _span = span.enter();
+                    let start = Instant::now();
+                    let result = match &r.method[..] {
+                        lsp_types::request::HoverRequest::METHOD => self.handle_hover_request(r),
+                        lsp_types::request::GotoDefinition::METHOD => {
+                            self.handle_goto_definition_request(r)
+                        }
+                        lsp_types::request::GotoTypeDefinition::METHOD => {
+                            self.handle_goto_type_definition_request(r)
+                        }
+                        lsp_types::request::Completion::METHOD => self.handle_completion_request(r),
+                        lsp_types::request::ResolveCompletionItem::METHOD => {
+                            self.handle_resolve_completion_item_request(r)
+                        }
+                        lsp_types::request::CodeActionRequest::METHOD => {
+                            self.handle_code_action_request(r)
+                        }
+                        lsp_types::request::ExecuteCommand::METHOD => {
+                            self.handle_execute_command_request(r)
+                        }
+                        lsp_types::request::Formatting::METHOD => self.handle_formatting_request(r),
+                        STATUS_REQUEST => {
+                            let status = Status {
+                                version: env!("CARGO_PKG_VERSION"),
+                                loading: self.loading > 0,
+                                conflicts: self.shared.sources().conflicts(),
+                                duplicates: self.shared.sources().duplicates(),
+                            };
+                            Ok(vec![response_ok(r.id, status)])
+                        }
+                        lsp_types::request::Shutdown::METHOD => {
+                            self.shutdown = true;
+                            let mut messages = self.save_usage();
+                            messages.push(response_empty(r.id));
+                            Ok(messages)
+                        }
+                        _ => {
+                            log(
+                                &c,
+                                MessageType::WARNING,
+                                format!("Unmatched request received: {}", r.method),
+                            );
+                            Ok(vec![])
+                        }
+                    };
+                    let elapsed = start.elapsed();
+                    let mut messages = request_messages(id, &method, result);
+                    // the worker traces the requests it handles, which only start here
+                    if !self.workers.take_spawned() {
+                        tracing::debug!(%method, ?elapsed, "Handled request");
+                        messages.extend(
+                            self.logging
+                                .tracer
+                                .log_trace(format!("Handled {method} in {elapsed:?}"), || None),
+                        );
+                    }
+                    send(&c, messages)?;
+                }
+                Message::Response(r) => match self.pending_requests.remove(&r.id) {
+                    Some(pending) => send(&c, self.handle_response(pending, r))?,
+                    None => log(
+                        &c,
+                        MessageType::WARNING,
+                        format!("Unmatched response received: {}", r.id),
+                    ),
+                },
+                Message::Notification(n) => {
+                    let method = n.method.clone();
+                    let span = tracing::debug_span!("notification", %method);
+                    let _span = span.enter();
+                    let start = Instant::now();
+                    let result = match &n.method[..] {
+                        lsp_types::notification::DidOpenTextDocument::METHOD => {
+                            self.handle_did_open_text_document_notification(n)
+                        }
+                        lsp_types::notification::DidChangeTextDocument::METHOD => {
+                            self.handle_did_change_text_document_notification(n)
+                        }
+                        lsp_types::notification::DidSaveTextDocument::METHOD => {
+                            self.handle_did_save_text_document_notification(n)
+                        }
+                        lsp_types::notification::DidCloseTextDocument::METHOD => {
+                            self.handle_did_close_text_document_notification(n)
+                        }
+                        lsp_types::notification::DidChangeWorkspaceFolders::METHOD => {
+                            self.handle_did_change_workspace_folders_notification(n)
+                        }
+                        lsp_types::notification::SetTrace::METHOD => {
+                            serde_json::from_value::<lsp_types::SetTraceParams>(n.params)
+                                .map(|params| {
+                                    self.logging.set_trace(params.value);
+                                    Vec::new()
+                                })
+                                .map_err(Error::from)
+                        }
+                        lsp_types::notification::Exit::METHOD => {
+                            if self.shutdown {
+                                return Ok(());
+                            } else {
+                                return Err(String::from(
+                                    "Received exit notification before shutdown request",
+                                ));
+                            }
+                        }
+                        _ => {
+                            log(
+                                &c,
+                                MessageType::WARNING,
+                                format!("Unmatched notification received: {}", n.method),
+                            );
+                            Ok(Vec::new())
+                        }
+                    };
+                    let elapsed = start.elapsed();
+                    tracing::debug!(%method, ?elapsed, "Handled notification");
+                    let mut messages = result.unwrap_or_else(|err| {
+                        tracing::warn!(%method, "Failed to handle notification: {err}");
+                        log(
+                            &c,
+                            MessageType::ERROR,
+                            format!("Failed to handle {method}: {err}"),
+                        );
+                        Vec::new()
+                    });
+                    messages.extend(
+                        self.logging
+                            .tracer
+                            .log_trace(format!("Handled {method} in {elapsed:?}"), || None),
+                    );
+                    send(&c, messages)?;
+                }
+            }
+        }
+    }
+
+    fn handle_hover_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)?;
+
+        if vcard_schema::is_vcard_file(Path::new(tdp.text_document.uri.path())) {
+            return Ok(vec![self.vcard_file_hover(request.id, &tdp)?]);
+        }
+
+        let (span, names) = self.get_mailbox_or_names_from_document(&tdp);
+        if span.is_none() && names.is_empty() {
+            return Ok(match self.get_phone_from_document(&tdp) {
+                Some(phone) => self.phone_hover(request.id, &request.method, &tdp, phone),
+                None => vec![response_empty(request.id)],
+            });
+        }
+        let mailbox = span.map(|(_, mailbox)| mailbox);
+        if let Some(mailbox) = &mailbox {
+            self.record_usage(&mailbox.email, UsageKind::Used);
+        }
+        let document = tdp.text_document.uri.to_file_path().ok();
+        let shared = self.shared.clone();
+        let generation = self.render_generation();
+        let options = self.match_options;
+        let format = self.hover_format.clone();
+        let id = request.id;
+        self.workers.spawn(id.clone(), &request.method, move || {
+            let mailboxes = match mailbox {
+                Some(mailbox) => vec![mailbox],
+                None => shared.named_mailboxes(document.as_deref(), &names, options),
+            };
+            let value = mailboxes
+                .into_iter()
+                .map(|m| shared.render(document.clone(), m, generation))
+                .filter(|r| !r.is_empty())
+                .join("\n\n---\n\n");
+            if value.is_empty() {
+                return Ok(vec![response_empty(id)]);
+            }
+            let resp = lsp_types::Hover {
+                contents: lsp_types::HoverContents::Markup(markup(&format, value)),
+                range: None,
+            };
+            Ok(vec![response_ok(id, resp)])
+        });
+
+        Ok(Vec::new())
+    }
+
+    /// Hover on a phone number, rendering the contacts that have it.
+    fn phone_hover(
+        &mut self,
+        id: RequestId,
+        method: &str,
+        tdp: &TextDocumentPositionParams,
+        phone: String,
+    ) -> Vec<Message> {
+        let document = tdp.text_document.uri.to_file_path().ok();
+        let shared = self.shared.clone();
+        let format = self.hover_format.clone();
+        self.workers.spawn(id.clone(), method, move || {
+            let value = shared
+                .sources()
+                .for_document(document.as_deref())
+                .render_phone(&phone);
+            if value.is_empty() {
+                return Ok(vec![response_empty(id)]);
+            }
+            let resp = lsp_types::Hover {
+                contents: lsp_types::HoverContents::Markup(markup(&format, value)),
+                range: None,
+            };
+            Ok(vec![response_ok(id, resp)])
+        });
+        Vec::new()
+    }
+
+    /// Hover in a vcard file, rendering the card under the cursor as it is in the buffer.
+    fn vcard_file_hover(
+        &mut self,
+        id: RequestId,
+        tdp: &TextDocumentPositionParams,
+    ) -> Result<Message, Error> {
+        let content = self.open_file(tdp.text_document.uri.as_ref())?;
+        let text = VcardText::parse(content);
+        let rendered = text
+            .card_at_line(tdp.position.line as usize)
+            .and_then(|card| vcard4::parse_loose(text.card_text(card)).ok())
+            .and_then(|vcards| vcards.into_iter().next())
+            .map(|vcard| render_vcard(&vcard, &self.render_options));
+        Ok(match rendered {
+            Some(value) => response_ok(
+                id,
+                lsp_types::Hover {
+                    contents: lsp_types::HoverContents::Markup(markup(&self.hover_format, value)),
+                    range: None,
+                },
+            ),
+            None => response_empty(id),
+        })
+    }
+
+    fn handle_goto_definition_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)?;
+
+        // cards refer to related contacts and group members by their UID
+        if vcard_schema::is_vcard_file(Path::new(tdp.text_document.uri.path())) {
+            let uid = self
+                .open_files
+                .line(tdp.text_document.uri.as_ref(), tdp.position.line as usize)
+                .and_then(vcard_schema::uid_reference);
+            if let Some(uid) = uid {
+                let locations = self.shared.sources().uid_locations(&uid);
+                return Ok(vec![goto_response(request.id, locations, None)]);
+            }
+        }
+
+        let (span, names) = self.get_mailbox_or_names_from_document(&tdp);
+        if span.is_none() && names.is_empty() {
+            return Ok(vec![response_empty(request.id)]);
+        }
+        let document = tdp.text_document.uri.to_file_path().ok();
+        let shared = self.shared.clone();
+        let options = self.match_options;
+        let links = self.definition_links;
+        let definition_sources = self.definition_sources;
+        let id = request.id;
+        self.workers.spawn(id.clone(), &request.method, move || {
+            let (origin, mailboxes) = match span {
+                Some((range, mailbox)) => (Some(range), vec![mailbox]),
+                None => (
+                    None,
+                    shared.named_mailboxes(document.as_deref(), &names, options),
+                ),
+            };
+            let sources = shared.sources();
+            let sources = sources.for_document(document.as_deref());
+            let locations = mailboxes
+                .iter()
+                .flat_map(|m| sources.definition_locations(m, definition_sources));
+            Ok(vec![goto_response(id, locations, links.then_some(origin))])
+        });
+
+        Ok(Vec::new())
+    }
+
+    /// Go to the groups the mailbox at the position is a member of.
+    fn handle_goto_type_definition_request(
+        &mut self,
+        request: Request,
+    ) -> Result<Vec<Message>, Error> {
+        let tdp = serde_json::from_value::<lsp_types::TextDocumentPositionParams>(request.params)?;
+
+        let Some((origin, mailbox)) = self.get_mailbox_span_from_document(&tdp) else {
+            return Ok(vec![response_empty(request.id)]);
+        };
+        let document = tdp.text_document.uri.to_file_path().ok();
+        let shared = self.shared.clone();
+        let links = self.type_definition_links;
+        let id = request.id;
+        self.workers.spawn(id.clone(), &request.method, move || {
+            let locations = shared
+                .sources()
+                .for_document(document.as_deref())
+                .group_locations(&mailbox);
+            Ok(vec![goto_response(
+                id,
+                locations,
+                links.then_some(Some(origin)),
+            )])
+        });
+
+        Ok(Vec::new())
+    }
+
+    fn handle_completion_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let cp = serde_json::from_value::<lsp_types::CompletionParams>(request.params)?;
+        let invoked = cp
+            .context
+            .is_some_and(|c| c.trigger_kind == lsp_types::CompletionTriggerKind::INVOKED);
+        let mut tdp = cp.text_document_position;
+
+        if vcard_schema::is_vcard_file(Path::new(tdp.text_document.uri.path())) {
+            let line = self
+                .open_files
+                .line(tdp.text_document.uri.as_ref(), tdp.position.line as usize)
+                .unwrap_or_default();
+            let items =
+                vcard_schema::complete(line, tdp.position.line, tdp.position.character as usize);
+            return Ok(vec![response_ok(request.id, items)]);
+        }
+
+        let uri = tdp.text_document.uri.as_str();
+        let profile = self.profile(uri);
+        if (profile.completion_on_demand && !invoked)
+            || !self.in_scope(uri, profile, tdp.position.line as usize)
+        {
+            return Ok(vec![response_empty(request.id)]);
+        }
+
+        tdp.position.character = tdp.position.character.saturating_sub(1);
+        // how long the sources took to match, when they were asked
+        let mut trace = None;
+        let response = match self.get_query_from_document(&tdp) {
+            Some(query) => {
+                let word = query.text.clone();
+                let uri = tdp.text_document.uri.to_string();
+                let document = tdp.text_document.uri.to_file_path().ok();
+                let line = tdp.position.line;
+                let matcher = Matcher::new(&word, self.match_options);
+                if !self.identities.is_empty() && headers::is_from_header(&query.line) {
+                    let items = self.identity_completion_items(&query, line, &matcher);
+                    let resp = lsp_types::CompletionResponse::List(CompletionList {
+                        is_incomplete: false,
+                        items,
+                    });
+                    return Ok(vec![response_ok(request.id, resp)]);
+                }
+                if query.phone {
+                    let items =
+                        self.phone_completion_items(&query, line, &matcher, document.as_deref());
+                    let resp = lsp_types::CompletionResponse::List(CompletionList {
+                        is_incomplete: self.loading > 0,
+                        items,
+                    });
+                    return Ok(vec![response_ok(request.id, resp)]);
+                }
+                let matches = match self.completion_cache.take() {
+                    Some(cache) if cache.refines(&uri, line, &word) => {
+                        let mut matches = cache
+                            .matches
+                            .into_iter()
+                            .filter(|m| m.matches(&matcher))
+                            .collect::<Vec<_>>();
+                        self.shared
+                            .sources()
+                            .for_document(document.as_deref())
+                            .sort_matches(&mut matches, &matcher);
+                        matches
+                    }
+                    _ => {
+                        let (matches, timings) = self
+                            .shared
+                            .sources()
+                            .for_document(document.as_deref())
+                            .find_matching_timed(&matcher);
+                        for (source, elapsed) in &timings {
+                            tracing::debug!(%source, ?elapsed, "Found matches");
+                        }
+                        trace = self.logging.tracer.log_trace(
+                            format!(
+                                "Found {} matches for {word:?} in {:?}",
+                                matches.len(),
+                                timings.iter().map(|(_, e)| *e).sum::<Duration>()
+                            ),
+                            || {
+                                Some(
+                                    timings
+                                        .iter()
+                                        .map(|(source, elapsed)| format!("{source}: {elapsed:?}"))
+                                        .join("\n"),
+                                )
+                            },
+                        );
+                        matches
+                            .into_iter()
+                            .take(COMPLETION_CACHE_LIMIT + 1)
+                            .collect::<Vec<_>>()
+                    }
+                };
+                tracing::debug!(query = %word, matches = matches.len(), "Completing");
+                // more matches may come from sources that are still loading
+                let is_incomplete = matches.len() > COMPLETION_LIMIT || self.loading > 0;
+                let mut completion_items = matches
+                    .iter()
+                    // a trailer names a single person
+                    .filter(|m| !query.trailer || m.members.is_empty())
+                    // when grouping, only the preferred address of each contact gets an item
+                    .filter(|m| {
+                        !self.group_addresses
+                            || m.addresses.first().is_none_or(|a| *a == m.mailbox.email)
+                    })
+                    .take(COMPLETION_LIMIT)
+                    .map(|m| {
+                        let grouped = self.group_addresses && m.addresses.len() > 1;
+                        let (label, new_text, description) = if m.members.is_empty() {
+                            let (label, new_text) = if query.trailer {
+                                (m.mailbox.to_trailer(), m.mailbox.to_trailer())
+                            } else {
+                                (m.mailbox.to_string(), self.mailbox_text(&m.mailbox))
+                            };
+                            let description =
+                                grouped.then(|| format!("{} addresses", m.addresses.len()));
+                            (label, new_text, description)
+                        } else {
+                            // a group expands to all of its members
+                            (
+                                m.mailbox.name.clone().unwrap_or_default(),
+                                m.members.iter().map(|m| self.mailbox_text(m)).join(", "),
+                                Some(format!("{} members", m.members.len())),
+                            )
+                        };
+                        let (text_edit, additional_text_edits) =
+                            self.completion_edits(&query, line, &new_text);
+                        CompletionItem {
+                            label,
+                            kind: Some(m.kind),
+                            label_details: Some(lsp_types::CompletionItemLabelDetails {
+                                detail: Some(m.source.clone()),
+                                description,
+                            }),
+                            insert_text: (!m.members.is_empty()).then_some(new_text),
+                            data: Some(
+                                serde_json::to_value(CompletionItemData {
+                                    document: document.clone(),
+                                    addresses: if grouped {
+                                        m.addresses.clone()
+                                    } else {
+                                        Vec::new()
+                                    },
+                                    members: m.members.clone(),
+                                    ..Default::default()
+                                })
+                                .unwrap(),
+                            ),
+                            text_edit,
+                            additional_text_edits,
+                            ..Default::default()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if self.name_completion && invoked && !query.address_header && !query.trailer {
+                    // offer just the names, for mentioning people in prose
+                    let name_items = matches
+                        .iter()
+                        .filter_map(|m| Some((m, m.mailbox.name.as_ref()?)))
+                        .unique_by(|(_, name)| *name)
+                        .take(COMPLETION_LIMIT)
+                        .map(|(m, name)| {
+                            let (text_edit, _) = self.completion_edits(&query, line, name);
+                            CompletionItem {
+                                label: name.clone(),
+                                kind: Some(m.kind),
+                                label_details: Some(lsp_types::CompletionItemLabelDetails {
+                                    detail: Some(m.source.clone()),
+                                    description: Some("name only".to_owned()),
+                                }),
+                                data: Some(
+                                    serde_json::to_value(CompletionItemData {
+                                        mailbox: Some(m.mailbox.clone()),
+                                        document: document.clone(),
+                                        ..Default::default()
+                                    })
+                                    .unwrap(),
+                                ),
+                                text_edit,
+                                ..Default::default()
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    completion_items.extend(name_items);
+                }
+                // keep our ordering rather than letting the client sort by label
+                for (i, item) in completion_items.iter_mut().enumerate() {
+                    item.sort_text = Some(format!("{i:04}"));
+                }
+                if matches.len() <= COMPLETION_CACHE_LIMIT && self.loading == 0 {
+                    // the full result set is known so can be refined as the query grows
+                    self.completion_cache = Some(CompletionCache {
+                        uri,
+                        line,
+                        query: word,
+                        matches,
+                    });
+                }
+                let resp = lsp_types::CompletionResponse::List(CompletionList {
+                    is_incomplete,
+                    items: completion_items,
+                });
+                response_ok(request.id, resp)
+            }
+            None => response_empty(request.id),
+        };
+
+        Ok(std::iter::once(response).chain(trace).collect())
+    }
+
+    /// Completions of the phone numbers of the contacts matching the query, as `tel:` URIs.
+    fn phone_completion_items(
+        &self,
+        query: &CompletionQuery,
+        line: u32,
+        matcher: &Matcher,
+        document: Option<&Path>,
+    ) -> Vec<CompletionItem> {
+        let sources = self.shared.sources();
+        let sources = sources.for_document(document);
+        sources
+            .find_contacts(matcher)
+            .into_iter()
+            .filter(|c| !c.is_group())
+            .flat_map(|c| {
+                let name = c
+                    .name()
+                    .or(c.emails().next())
+                    .unwrap_or_default()
+                    .to_owned();
+                let kind = c.meta.kind;
+                c.meta
+                    .phones
+                    .into_iter()
+                    .map(move |phone| (name.clone(), kind, phone))
+            })
+            .unique_by(|(_, _, phone)| phone.number.clone())
+            .take(COMPLETION_LIMIT)
+            .enumerate()
+            .map(|(i, (name, kind, phone))| {
+                let (text_edit, _) = self.completion_edits(query, line, &tel_uri(&phone.number));
+                CompletionItem {
+                    label: phone.number.clone(),
+                    kind: Some(kind),
+                    label_details: Some(lsp_types::CompletionItemLabelDetails {
+                        detail: Some(name.clone()),
+                        description: phone.kind,
+                    }),
+                    // typing the contact's name after `tel:` keeps their numbers
+                    filter_text: Some(format!("tel:{name} {}", phone.number)),
+                    sort_text: Some(format!("{i:04}")),
+                    text_edit,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// The mailbox as it is inserted into documents.
+    fn mailbox_text(&self, mailbox: &Mailbox) -> String {
+        if self.encoded_words {
+            mailbox.to_encoded()
+        } else {
+            mailbox.to_string()
+        }
+    }
+
+    /// Completions of the identities matching the query, for the `From:` header.
+    fn identity_completion_items(
+        &self,
+        query: &CompletionQuery,
+        line: u32,
+        matcher: &Matcher,
+    ) -> Vec<CompletionItem> {
+        self.identities
+            .iter()
+            .filter(|i| {
+                matcher.matches(
+                    &i.name.as_deref().into_iter().collect::<Vec<_>>(),
+                    &[&i.email],
+                )
+            })
+            .enumerate()
+            .map(|(i, identity)| {
+                let label = identity.to_string();
+                let (text_edit, additional_text_edits) =
+                    self.completion_edits(query, line, &self.mailbox_text(identity));
+                CompletionItem {
+                    label,
+                    kind: Some(CompletionItemKind::VALUE),
+                    label_details: Some(lsp_types::CompletionItemLabelDetails {
+                        detail: Some("Identity".to_owned()),
+                        description: None,
+                    }),
+                    sort_text: Some(format!("{i:04}")),
+                    text_edit,
+                    additional_text_edits,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Edits to insert a completion for the query, replacing the query (or the mailbox it is in)
+    /// and appending a separator in address headers if configured.
+    fn completion_edits(
+        &self,
+        query: &CompletionQuery,
+        line: u32,
+        new_text: &str,
+    ) -> (
+        Option<lsp_types::CompletionTextEdit>,
+        Option<Vec<lsp_types::TextEdit>>,
+    ) {
+        let Some((start, end)) = query.replace else {
+            return (None, None);
+        };
+        let mut additional_edits = Vec::new();
+        let mut new_text = new_text.to_owned();
+        if query.address_header {
+            // fold long headers onto continuation lines rather than go over the line length
+            let before = query.line.chars().take(start).collect::<String>();
+            let (break_before, folded) = headers::fold_insertion(&before, &new_text);
+            if break_before {
+                let trimmed = before.trim_end().chars().count();
+                additional_edits.push(lsp_types::TextEdit {
+                    range: Range::new(
+                        Position::new(line, trimmed as u32),
+                        Position::new(line, start as u32),
+                    ),
+                    new_text: "\n ".to_owned(),
+                });
+            }
+            new_text = folded;
+        }
+        let end_position = Position::new(line, end as u32);
+        let edit = lsp_types::TextEdit {
+            range: Range::new(Position::new(line, start as u32), end_position),
+            new_text,
+        };
+        if let Some(sep) = self.completion_separator.as_ref().filter(|_| {
+            // mail is sent from a single address
+            query.address_header
+                && !headers::is_from_header(&query.line)
+                && !headers::separator_follows(&query.line, end)
+        }) {
+            additional_edits.push(lsp_types::TextEdit {
+                range: Range::new(end_position, end_position),
+                new_text: sep.clone(),
+            });
+        }
+        (
+            Some(lsp_types::CompletionTextEdit::Edit(edit)),
+            (!additional_edits.is_empty()).then_some(additional_edits),
+        )
+    }
+
+    fn handle_resolve_completion_item_request(
+        &mut self,
+        request: Request,
+    ) -> Result<Vec<Message>, Error> {
+        let mut ci = serde_json::from_value::<lsp_types::CompletionItem>(request.params)?;
+
+        let data = ci
+            .data
+            .take()
+            .and_then(|d| serde_json::from_value::<CompletionItemData>(d).ok())
+            .unwrap_or_default();
+        // groups list their members rather than render a contact
+        let mailbox =
+            match data.mailbox {
+                _ if !data.members.is_empty() => None,
+                Some(mailbox) => Some(mailbox),
+                None => Some(Mailbox::from_str(&ci.label).map_err(|_| {
+                    Error::InvalidParams(format!("{:?} is not a mailbox", ci.label))
+                })?),
+            };
+        for m in mailbox.iter().chain(&data.members) {
+            self.record_usage(&m.email, UsageKind::Used);
+        }
+        let shared = self.shared.clone();
+        let generation = self.render_generation();
+        let id = request.id;
+        self.workers.spawn(id.clone(), &request.method, move || {
+            let mut doc = match mailbox {
+                Some(mailbox) => shared.render(data.document, mailbox, generation),
+                None => {
+                    let mut lines = Vec::new();
+                    render::list_section(
+                        &mut lines,
+                        "Members",
+                        data.members.iter().map(|m| m.to_string()),
+                    );
+                    lines.join("\n")
+                }
+            };
+            if !data.addresses.is_empty() {
+                let addresses = data
+                    .addresses
+                    .iter()
+                    .map(|a| format!("- {a}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                doc = format!("Addresses:\n{addresses}\n\n{doc}");
+            }
+            ci.documentation = Some(lsp_types::Documentation::MarkupContent(
+                lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: doc,
+                },
+            ));
+            Ok(vec![response_ok(id, ci)])
+        });
+
+        Ok(Vec::new())
+    }
+
+    fn handle_code_action_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let cap = serde_json::from_value::<lsp_types::CodeActionParams>(request.params)?;
+
+        let tdp = TextDocumentPositionParams {
+            text_document: cap.text_document,
+            position: cap.range.start,
+        };
 
         let mut action_list = Vec::new();
-        if let Some(mailbox) = self.get_mailbox_from_document(&tdp) {
-            let args = serde_json::to_value(CreateContactCommandArguments { mailbox }).unwrap();
+        let profile = self.profile(tdp.text_document.uri.as_str());
+        let in_scope = self.in_scope(
+            tdp.text_document.uri.as_str(),
+            profile,
+            tdp.position.line as usize,
+        );
+        let mailbox = self.get_mailbox_from_document(&tdp);
+        // addresses on the lines that address the message stay as they are
+        if let Some(mailbox) = mailbox
+            .as_ref()
+            .filter(|_| profile.scope == Scope::Everywhere || !in_scope)
+        {
+            let document = document_path(tdp.text_document.uri.as_str());
+            let addresses = self
+                .shared
+                .sources()
+                .for_document(document.as_deref())
+                .postal_addresses(mailbox);
+            for (index, address) in addresses.into_iter().enumerate() {
+                let title = match address.kind {
+                    Some(kind) => format!("Insert {kind} postal address"),
+                    None => "Insert postal address".to_owned(),
+                };
+                let args = serde_json::to_value(InsertAddressCommandArguments {
+                    document: tdp.text_document.uri.clone(),
+                    position: tdp.position,
+                    index,
+                })
+                .unwrap();
+                action_list.push(lsp_types::CodeActionOrCommand::CodeAction(
+                    lsp_types::CodeAction {
+                        title: title.clone(),
+                        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+                        command: Some(lsp_types::Command {
+                            title,
+                            command: INSERT_ADDRESS_COMMAND.to_owned(),
+                            arguments: Some(vec![args]),
+                        }),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+        if let Some(mailbox) = mailbox.filter(|_| in_scope) {
+            let args = serde_json::to_value(CreateContactCommandArguments {
+                mailbox,
+                name: None,
+                document: Some(tdp.text_document.uri.clone()),
+            })
+            .unwrap();
             let fixed_diagnostics = self
                 .diagnostics
-                .iter()
+                .get(tdp.text_document.uri.as_str())
+                .into_iter()
+                .flatten()
                 .filter(|d| in_range(&d.range, &cap.range.start))
                 .cloned()
                 .collect::<Vec<_>>();
+            let document = document_path(tdp.text_document.uri.as_str());
+            let targets = self.shared.sources().write_targets(document.as_deref());
+            let title = match targets.as_slice() {
+                // which one is asked when adding it
+                [_, _, ..] if self.ask_write_source => "Add to contacts...".to_owned(),
+                [(_, target), ..] => format!("Add to {target}"),
+                [] => "Add to contacts".to_owned(),
+            };
             let action = lsp_types::CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
-                title: "Add to contacts".to_owned(),
+                title: title.clone(),
                 kind: Some(CodeActionKind::QUICKFIX),
                 diagnostics: if fixed_diagnostics.is_empty() {
                     None
@@ -457,7 +2995,7 @@ impl Server {
                     Some(fixed_diagnostics)
                 },
                 command: Some(lsp_types::Command {
-                    title: "Add to contacts".to_owned(),
+                    title,
                     command: CREATE_CONTACT_COMMAND.to_owned(),
                     arguments: Some(vec![args]),
                 }),
@@ -465,64 +3003,559 @@ impl Server {
             });
             action_list.push(action);
         }
+        if let Some(path) = document_path(tdp.text_document.uri.as_str()) {
+            for conflict in self.shared.sources().conflicts() {
+                let here = conflict.locations.iter().any(|l| {
+                    l.path == path
+                        && lsp_types::Location::try_from(l.clone())
+                            .is_ok_and(|l| in_range(&l.range, &tdp.position))
+                });
+                if !here {
+                    continue;
+                }
+                let title = format!("Merge duplicates: {}", conflict.message);
+                action_list.push(lsp_types::CodeActionOrCommand::CodeAction(
+                    lsp_types::CodeAction {
+                        title: title.clone(),
+                        kind: Some(CodeActionKind::QUICKFIX),
+                        command: Some(lsp_types::Command {
+                            title,
+                            command: MERGE_CONTACTS_COMMAND.to_owned(),
+                            arguments: Some(vec![serde_json::to_value(conflict).unwrap()]),
+                        }),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
         let response = response_ok(request.id, action_list);
 
-        vec![response]
+        Ok(vec![response])
     }
 
-    fn handle_execute_command_request(&mut self, request: Request) -> Vec<Message> {
-        let mut cap =
-            serde_json::from_value::<lsp_types::ExecuteCommandParams>(request.params).unwrap();
+    fn handle_execute_command_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let mut cap = serde_json::from_value::<lsp_types::ExecuteCommandParams>(request.params)?;
 
         let mut messages = Vec::new();
-        let response = match cap.command.as_str() {
+        match cap.command.as_str() {
+            CREATE_CONTACT_COMMAND if self.loading > 0 => return Err(Error::Loading),
             CREATE_CONTACT_COMMAND => {
-                let arg = cap.arguments.swap_remove(0);
-                match serde_json::from_value::<CreateContactCommandArguments>(arg) {
-                    Ok(args) => {
-                        let path = self.sources.create_contact(args.mailbox);
-                        if let Some(path) = path {
-                            let params = ShowDocumentParams {
-                                uri: Url::from_file_path(path).unwrap(),
-                                external: None,
-                                take_focus: None,
-                                selection: None,
-                            };
-                            messages.push(Message::Request(lsp_server::Request {
-                                id: RequestId::from(0),
-                                method: lsp_types::request::ShowDocument::METHOD.to_owned(),
-                                params: serde_json::to_value(params).unwrap(),
-                            }));
+                if cap.arguments.is_empty() {
+                    return Err(Error::InvalidParams("missing arguments".to_owned()));
+                }
+                let args = serde_json::from_value::<CreateContactCommandArguments>(
+                    cap.arguments.swap_remove(0),
+                )?;
+                let document = args.document.and_then(|uri| uri.to_file_path().ok());
+                let mut targets = self.shared.sources().write_targets(document.as_deref());
+                if self.ask_write_source && targets.len() > 1 {
+                    // the titles are all the response has to tell the sources apart by
+                    let labels = targets.iter().map(|(_, l)| l.clone()).collect::<Vec<_>>();
+                    for (position, label) in &mut targets {
+                        if labels.iter().filter(|l| *l == label).count() > 1 {
+                            *label = format!("{label} (source {})", *position + 1);
                         }
-                        response_empty(request.id)
                     }
-                    _ => response_err(
-                        request.id,
-                        ErrorCode::InvalidRequest as i32,
-                        String::from("invalid arguments"),
-                    ),
+                    let actions = targets
+                        .iter()
+                        .map(|(_, title)| lsp_types::MessageActionItem {
+                            title: title.clone(),
+                            properties: HashMap::new(),
+                        })
+                        .collect();
+                    messages.push(self.request(
+                        lsp_types::request::ShowMessageRequest::METHOD,
+                        lsp_types::ShowMessageRequestParams {
+                            typ: MessageType::INFO,
+                            message: format!("Add {} to:", args.mailbox),
+                            actions: Some(actions),
+                        },
+                        PendingRequest::CreateContact {
+                            document,
+                            mailbox: args.mailbox,
+                            name: args.name,
+                            targets,
+                        },
+                    ));
+                } else {
+                    let created = self.shared.sources_mut().create_contact_for(
+                        document.as_deref(),
+                        args.mailbox.clone(),
+                        args.name,
+                    );
+                    messages.extend(self.created(args.mailbox, created));
                 }
             }
-            _ => response_err(
-                request.id,
-                ErrorCode::InvalidRequest as i32,
-                String::from("unknown command"),
-            ),
+            MERGE_CONTACTS_COMMAND if self.loading > 0 => return Err(Error::Loading),
+            MERGE_CONTACTS_COMMAND => {
+                if cap.arguments.is_empty() {
+                    return Err(Error::InvalidParams("missing arguments".to_owned()));
+                }
+                let conflict = serde_json::from_value::<Conflict>(cap.arguments.swap_remove(0))?;
+                match self.merge_into.clone() {
+                    Some(into) => messages.extend(self.merge(&conflict, &into)),
+                    None => {
+                        let actions = conflict
+                            .locations
+                            .iter()
+                            .map(|l| lsp_types::MessageActionItem {
+                                title: location_title(l),
+                                properties: HashMap::new(),
+                            })
+                            .collect();
+                        messages.push(self.request(
+                            lsp_types::request::ShowMessageRequest::METHOD,
+                            lsp_types::ShowMessageRequestParams {
+                                typ: MessageType::INFO,
+                                message: format!(
+                                    "Merge the duplicates into which contact? {}",
+                                    conflict.message
+                                ),
+                                actions: Some(actions),
+                            },
+                            PendingRequest::Merge(conflict),
+                        ));
+                    }
+                }
+            }
+            INSERT_ADDRESS_COMMAND if self.loading > 0 => return Err(Error::Loading),
+            INSERT_ADDRESS_COMMAND => {
+                if cap.arguments.is_empty() {
+                    return Err(Error::InvalidParams("missing arguments".to_owned()));
+                }
+                let args = serde_json::from_value::<InsertAddressCommandArguments>(
+                    cap.arguments.swap_remove(0),
+                )?;
+                let tdp = TextDocumentPositionParams {
+                    text_document: lsp_types::TextDocumentIdentifier {
+                        uri: args.document.clone(),
+                    },
+                    position: args.position,
+                };
+                let Some((range, mailbox)) = self.get_mailbox_span_from_document(&tdp) else {
+                    return Err(Error::InvalidParams(
+                        "no mailbox at the position".to_owned(),
+                    ));
+                };
+                let document = args.document.to_file_path().ok();
+                let address = self
+                    .shared
+                    .sources()
+                    .for_document(document.as_deref())
+                    .postal_addresses(&mailbox)
+                    .into_iter()
+                    .nth(args.index);
+                match address {
+                    Some(address) => {
+                        let params = lsp_types::ApplyWorkspaceEditParams {
+                            label: Some("Insert postal address".to_owned()),
+                            edit: lsp_types::WorkspaceEdit {
+                                changes: Some(HashMap::from([(
+                                    args.document,
+                                    vec![lsp_types::TextEdit {
+                                        range,
+                                        new_text: address.lines.join("\n"),
+                                    }],
+                                )])),
+                                ..Default::default()
+                            },
+                        };
+                        messages.push(self.request(
+                            lsp_types::request::ApplyWorkspaceEdit::METHOD,
+                            params,
+                            PendingRequest::InsertAddress,
+                        ));
+                    }
+                    None => messages.push(show_message(
+                        MessageType::WARNING,
+                        format!("{mailbox} has no postal address"),
+                    )),
+                }
+            }
+            ADD_NOTE_COMMAND if self.loading > 0 => return Err(Error::Loading),
+            ADD_NOTE_COMMAND => {
+                if cap.arguments.is_empty() {
+                    return Err(Error::InvalidParams("missing arguments".to_owned()));
+                }
+                let args = serde_json::from_value::<AddNoteCommandArguments>(
+                    cap.arguments.swap_remove(0),
+                )?;
+                if args.note.trim().is_empty() {
+                    return Err(Error::InvalidParams("empty note".to_owned()));
+                }
+                let tdp = TextDocumentPositionParams {
+                    text_document: lsp_types::TextDocumentIdentifier {
+                        uri: args.document.clone(),
+                    },
+                    position: args.position,
+                };
+                let Some(mailbox) = self.get_mailbox_from_document(&tdp) else {
+                    return Err(Error::InvalidParams(
+                        "no mailbox at the position".to_owned(),
+                    ));
+                };
+                let document = args.document.to_file_path().ok();
+                let location = self.shared.sources_mut().add_note_for(
+                    document.as_deref(),
+                    &mailbox,
+                    &args.note,
+                );
+                if location.is_some() {
+                    self.sources_changed();
+                    messages.extend(self.note_added(&mailbox, location));
+                } else {
+                    messages.push(self.request(
+                        lsp_types::request::ShowMessageRequest::METHOD,
+                        lsp_types::ShowMessageRequestParams {
+                            typ: MessageType::INFO,
+                            message: format!(
+                                "{mailbox} isn't in a contact that can be edited, create one for the note?"
+                            ),
+                            actions: Some(vec![lsp_types::MessageActionItem {
+                                title: "Create".to_owned(),
+                                properties: HashMap::new(),
+                            }]),
+                        },
+                        PendingRequest::AddNote {
+                            document,
+                            mailbox,
+                            note: args.note,
+                        },
+                    ));
+                }
+            }
+            command => return Err(Error::UnknownCommand(command.to_owned())),
         };
-        messages.push(response);
+        messages.push(response_empty(request.id));
+
+        Ok(messages)
+    }
+
+    /// Tell the client about a contact created by the user, showing it to them.
+    fn created(&mut self, mailbox: Mailbox, created: Option<(String, Location)>) -> Vec<Message> {
+        self.sources_changed();
+        let mut messages = Vec::new();
+        if let Some((source, location)) = created {
+            messages.push(contact_created(mailbox, source, &location));
+            let has_line = location.line.is_some();
+            if let Ok(location) = lsp_types::Location::try_from(location) {
+                let params = ShowDocumentParams {
+                    uri: location.uri,
+                    external: None,
+                    take_focus: None,
+                    selection: has_line.then_some(location.range),
+                };
+                messages.push(self.request(
+                    lsp_types::request::ShowDocument::METHOD,
+                    params,
+                    PendingRequest::ShowDocument,
+                ));
+            }
+        }
+        messages
+    }
+
+    /// Merge the contacts of the conflict, showing the one they were merged into.
+    fn merge(&mut self, conflict: &Conflict, into: &MergeInto) -> Vec<Message> {
+        let mut messages = Vec::new();
+        let location = self.shared.sources_mut().merge(conflict, into);
+        self.sources_changed();
+        match location.map(lsp_types::Location::try_from) {
+            Some(Ok(location)) => {
+                messages.extend(self.publish_diagnostics());
+                let params = ShowDocumentParams {
+                    uri: location.uri,
+                    external: None,
+                    take_focus: None,
+                    selection: Some(location.range),
+                };
+                messages.push(self.request(
+                    lsp_types::request::ShowDocument::METHOD,
+                    params,
+                    PendingRequest::ShowDocument,
+                ));
+            }
+            Some(Err(_)) => messages.extend(self.publish_diagnostics()),
+            None => messages.push(show_message(
+                MessageType::WARNING,
+                format!(
+                    "Couldn't merge {}, the contacts changed or their source is read only",
+                    conflict.message
+                ),
+            )),
+        }
+        messages
+    }
+
+    /// Tell the user where the note of the mailbox's contact was added, or that it couldn't be.
+    fn note_added(&self, mailbox: &Mailbox, location: Option<Location>) -> Vec<Message> {
+        vec![match location {
+            Some(location) => show_message(
+                MessageType::INFO,
+                format!("Added the note to {}", location.path.display()),
+            ),
+            None => show_message(
+                MessageType::WARNING,
+                format!("Couldn't add the note to {mailbox}, its source is read only"),
+            ),
+        }]
+    }
+
+    /// Reload sources after their files changed outside of the editor, refreshing diagnostics of
+    /// open files if any changed.
+    fn handle_changed_files(&mut self, changed: Vec<PathBuf>) -> Vec<Message> {
+        if !self.shared.sources_mut().reload(&changed) {
+            return Vec::new();
+        }
+        tracing::info!(
+            ?changed,
+            contacts = self.shared.sources().contact_count(),
+            "Reloaded contact sources"
+        );
+        self.sources_changed();
+        self.publish_diagnostics()
+    }
 
+    /// Forget results from the sources after they changed.
+    fn sources_changed(&mut self) {
+        self.completion_cache = None;
+        self.generation += 1;
+    }
+
+    /// The generation of the sources, avatars and usage log, for renders to be cached against.
+    fn render_generation(&self) -> (u64, u64, u64) {
+        let avatars_generation = self
+            .render_options
+            .avatars
+            .as_ref()
+            .map_or(0, |avatars| avatars.generation());
+        let usage_generation = self.usage_log.as_ref().map_or(0, |log| log.generation());
+        (self.generation, avatars_generation, usage_generation)
+    }
+
+    /// Record a use of the address in the usage log, if usage is tracked.
+    fn record_usage(&self, email: &str, kind: UsageKind) {
+        if let Some(log) = &self.usage_log {
+            log.record(email, kind, chrono::Local::now().date_naive());
+        }
+    }
+
+    /// Write the usage log to its file, if usage is tracked.
+    fn save_usage(&self) -> Vec<Message> {
+        match self.usage_log.as_ref().map(|log| log.save()) {
+            Some(Err(err)) => vec![log_message(
+                MessageType::ERROR,
+                format!("Failed to save usage: {err}"),
+            )],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Add a source that finished loading, in its configured position, or report why it failed
+    /// to.
+    ///
+    /// Once all of them are loaded, conflicts are reported and the diagnostics of open files,
+    /// which were skipped while loading, are published.
+    fn handle_loaded_source(
+        &mut self,
+        i: usize,
+        folder: Option<PathBuf>,
+        source: LoadedSource,
+    ) -> Vec<Message> {
+        let mut messages = Vec::new();
+        self.loading -= 1;
+        match source {
+            // the folder was removed while it loaded
+            Ok(_)
+                if folder
+                    .as_ref()
+                    .is_some_and(|f| !self.workspace_folders.contains(f)) => {}
+            Ok(source) => {
+                if let Some(watcher) = &mut self.watcher {
+                    // missing paths are watched once they're created
+                    let paths = source
+                        .watched_paths()
+                        .into_iter()
+                        .filter(|p| p.exists())
+                        .collect::<Vec<_>>();
+                    if let Err(err) = watcher.watch(&paths) {
+                        messages.push(log_message(
+                            MessageType::ERROR,
+                            format!("Failed to watch contact files: {err}"),
+                        ));
+                    }
+                }
+                let priority = self.source_options[i].priority.unwrap_or(0);
+                self.shared.sources_mut().add(source, i, priority, folder);
+                self.sources_changed();
+            }
+            Err(err) => messages.push(show_message(
+                MessageType::ERROR,
+                format!("Failed to load contact source: {err}"),
+            )),
+        }
+        if self.loading > 0 {
+            return messages;
+        }
+
+        messages.push(log_message(
+            MessageType::INFO,
+            format!("Loaded {} contact sources", self.shared.sources().len()),
+        ));
+        let conflicts = self.shared.sources().conflicts();
+        if let Some(conflict) = conflicts.first() {
+            messages.push(show_message(
+                MessageType::WARNING,
+                format!(
+                    "Found {} conflicting contacts, e.g. {}, see {STATUS_REQUEST} for all of them",
+                    conflicts.len(),
+                    conflict.message
+                ),
+            ));
+        }
+        if self.duplicate_summary {
+            let duplicates = self.shared.sources().duplicates();
+            if let Some(duplicate) = duplicates.first() {
+                messages.push(show_message(
+                    MessageType::INFO,
+                    format!(
+                        "Found {} addresses in several sources or with different names, e.g. {}, see {STATUS_REQUEST} for all of them",
+                        duplicates.len(),
+                        duplicate.message()
+                    ),
+                ));
+            }
+        }
+        messages.extend(self.publish_diagnostics());
+        if self.notmuch_frecency {
+            self.load_notmuch_weights();
+        }
         messages
     }
 
+    /// Scan the sent maildirs for the addresses mail was sent to in the background, to rank
+    /// completions by once done.
+    fn load_sent_weights(&self, maildirs: Vec<PathBuf>) {
+        let shared = self.shared.clone();
+        let cache = self.cache_dir.join("sent");
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            match sent_weights(&maildirs, &cache) {
+                Ok(weights) => {
+                    tracing::info!(
+                        addresses = weights.len(),
+                        elapsed = ?start.elapsed(),
+                        "Loaded sent weights"
+                    );
+                    shared.sources_mut().set_weights("sent", weights);
+                }
+                Err(err) => tracing::warn!("Failed to scan sent maildirs: {err}"),
+            }
+        });
+    }
+
+    /// Count the mail with the addresses of the contacts in the notmuch database in the
+    /// background, to rank completions by once done.
+    ///
+    /// Nothing is counted while a count is still going, or if the addresses are the same as last
+    /// time.
+    fn load_notmuch_weights(&mut self) {
+        if self.counting_notmuch.load(Ordering::Acquire) {
+            return;
+        }
+        let emails = self
+            .shared
+            .sources()
+            .distinct_contacts()
+            .into_iter()
+            .filter(|m| m.members.is_empty())
+            .flat_map(|m| m.addresses)
+            .collect::<Vec<_>>();
+        if self.notmuch_emails.as_ref() == Some(&emails) {
+            return;
+        }
+        self.notmuch_emails = Some(emails.clone());
+        self.counting_notmuch.store(true, Ordering::Release);
+        let counting = self.counting_notmuch.clone();
+        let shared = self.shared.clone();
+        let cache = self.cache_dir.join("notmuch-weights");
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            match notmuch_weights(&emails, &cache, chrono::Local::now().date_naive()) {
+                Ok(weights) => {
+                    tracing::info!(
+                        addresses = weights.len(),
+                        elapsed = ?start.elapsed(),
+                        "Loaded notmuch weights"
+                    );
+                    shared.sources_mut().set_weights("notmuch", weights);
+                }
+                Err(err) => tracing::warn!("Failed to load notmuch weights: {err}"),
+            }
+            counting.store(false, Ordering::Release);
+        });
+    }
+
+    /// Publish fresh diagnostics for all open files.
+    fn publish_diagnostics(&mut self) -> Vec<Message> {
+        self.open_files
+            .uris()
+            .into_iter()
+            .filter_map(|uri| {
+                let diagnostics = self.refresh_diagnostics(&uri);
+                Some(Message::Notification(Notification::new(
+                    PublishDiagnostics::METHOD.to_owned(),
+                    PublishDiagnosticsParams {
+                        uri: Url::parse(&uri).ok()?,
+                        diagnostics,
+                        version: None,
+                    },
+                )))
+            })
+            .collect()
+    }
+
+    fn handle_formatting_request(&mut self, request: Request) -> Result<Vec<Message>, Error> {
+        let dfp = serde_json::from_value::<lsp_types::DocumentFormattingParams>(request.params)?;
+        let uri = dfp.text_document.uri;
+        if !vcard_schema::is_vcard_file(Path::new(uri.path())) {
+            return Ok(vec![response_empty(request.id)]);
+        }
+        let content = self.open_file(uri.as_ref())?;
+        let formatted = vcard_schema::format(content);
+        let edits = if formatted == content {
+            Vec::new()
+        } else {
+            let end = content.len();
+            let end = self
+                .open_files
+                .position(uri.as_ref(), end)
+                .ok_or_else(|| Error::UnknownDocument(uri.to_string()))?;
+            vec![lsp_types::TextEdit {
+                range: Range::new(Position::new(0, 0), end),
+                new_text: formatted,
+            }]
+        };
+        Ok(vec![response_ok(request.id, edits)])
+    }
+
     fn handle_did_open_text_document_notification(
         &mut self,
         notification: Notification,
-    ) -> Vec<Message> {
+    ) -> Result<Vec<Message>, Error> {
         let dotdp =
-            serde_json::from_value::<lsp_types::DidOpenTextDocumentParams>(notification.params)
-                .unwrap();
+            serde_json::from_value::<lsp_types::DidOpenTextDocumentParams>(notification.params)?;
+        let path = dotdp.text_document.uri.to_file_path().ok();
+        if !self.document_filter.includes(path.as_deref()) {
+            tracing::debug!(uri = %dotdp.text_document.uri, "Ignoring document");
+            self.ignored_documents
+                .insert(dotdp.text_document.uri.to_string());
+            return Ok(Vec::new());
+        }
         self.open_files.add(
             dotdp.text_document.uri.to_string(),
+            Some(dotdp.text_document.language_id),
+            Some(dotdp.text_document.version),
             dotdp.text_document.text,
         );
         let diagnostics = self.refresh_diagnostics(dotdp.text_document.uri.as_ref());
@@ -534,7 +3567,7 @@ impl Server {
                 version: Some(dotdp.text_document.version),
             },
         ));
-        vec![message]
+        Ok(vec![message])
         // log(
         //     &c,
         //     format!(
@@ -547,34 +3580,189 @@ impl Server {
     fn handle_did_change_text_document_notification(
         &mut self,
         notification: Notification,
-    ) -> Vec<Message> {
+    ) -> Result<Vec<Message>, Error> {
         let dctdp =
-            serde_json::from_value::<lsp_types::DidChangeTextDocumentParams>(notification.params)
-                .unwrap();
+            serde_json::from_value::<lsp_types::DidChangeTextDocumentParams>(notification.params)?;
         let doc = dctdp.text_document.uri.to_string();
-        self.open_files.apply_changes(&doc, dctdp.content_changes);
-        let diagnostics = self.refresh_diagnostics(dctdp.text_document.uri.as_ref());
+        if self.ignored_documents.contains(&doc) {
+            return Ok(Vec::new());
+        }
+        let version = dctdp.text_document.version;
+        let applied = self
+            .open_files
+            .apply_changes(&doc, version, dctdp.content_changes)
+            .ok_or_else(|| Error::UnknownDocument(doc.clone()))?;
+        let mut messages = Vec::new();
+        let diagnostics = match applied {
+            Applied::Lines(changes) => self.update_diagnostics(&doc, Some(changes)),
+            Applied::Full => self.update_diagnostics(&doc, None),
+            Applied::Stale => return Ok(messages),
+            Applied::Lost => {
+                // positions in the content the server has would be wrong, so there are none
+                // until the client sends all of it
+                tracing::warn!(%doc, version, "Missed changes to the document");
+                self.diagnostics.remove(&doc);
+                messages.push(show_message(
+                    MessageType::WARNING,
+                    format!(
+                        "Lost track of changes to {doc}, reopen it for maills to work in it again"
+                    ),
+                ));
+                Vec::new()
+            }
+        };
         let message = Message::Notification(Notification::new(
             PublishDiagnostics::METHOD.to_owned(),
             PublishDiagnosticsParams {
                 uri: dctdp.text_document.uri,
                 diagnostics,
-                version: Some(dctdp.text_document.version),
+                version: Some(version),
             },
         ));
-        vec![message]
+        messages.push(message);
+        Ok(messages)
         // log(&c, format!("got change document notification for {doc:?}"))
     }
 
+    /// Record the recipients of a saved message as sent to, once each for the document.
+    fn handle_did_save_text_document_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<Vec<Message>, Error> {
+        let dstdp =
+            serde_json::from_value::<lsp_types::DidSaveTextDocumentParams>(notification.params)?;
+        let uri = dstdp.text_document.uri.to_string();
+        if self.ignored_documents.contains(&uri) {
+            return Ok(Vec::new());
+        }
+        let mut messages = Vec::new();
+        if self.usage_log.is_some() {
+            let recipients = headers::recipient_addresses(self.open_file(&uri)?)
+                .into_iter()
+                .map(|a| fold(a, false))
+                .collect::<Vec<_>>();
+            let mut recorded = self.recorded_recipients.remove(&uri).unwrap_or_default();
+            for recipient in recipients {
+                if recorded.insert(recipient.clone()) {
+                    self.record_usage(&recipient, UsageKind::Sent);
+                }
+            }
+            self.recorded_recipients.insert(uri.clone(), recorded);
+            messages.extend(self.save_usage());
+        }
+        // every recipient would look unknown until the sources have loaded
+        if self.loading > 0 {
+            return Ok(messages);
+        }
+        if self.on_save.contains(&OnSave::HarvestRecipients) {
+            messages.extend(self.harvest_recipients(&uri)?);
+        }
+        if self.on_save.contains(&OnSave::PromptAddUnknown) {
+            messages.extend(self.prompt_add_unknown(&uri)?);
+        }
+        Ok(messages)
+    }
+
+    /// Add the recipients of the message that aren't in the contacts to the sources that harvest
+    /// them.
+    fn harvest_recipients(&mut self, uri: &str) -> Result<Vec<Message>, Error> {
+        let mailboxes = headers::recipient_mailboxes(self.open_file(uri)?);
+        let document = document_path(uri);
+        let mut messages = Vec::new();
+        let mut sources = self.shared.sources_mut();
+        for mailbox in mailboxes {
+            // sources without diagnostics don't count their contacts as known, but they are
+            if sources
+                .for_document(document.as_deref())
+                .has_contact(&mailbox.email)
+            {
+                continue;
+            }
+            if let Some((source, location)) =
+                sources.harvest_for(document.as_deref(), mailbox.clone())
+            {
+                tracing::info!(%mailbox, %source, "Harvested recipient");
+                messages.push(contact_created(mailbox, source, &location));
+            }
+        }
+        drop(sources);
+        if !messages.is_empty() {
+            self.sources_changed();
+            messages.extend(self.publish_diagnostics());
+        }
+        Ok(messages)
+    }
+
+    /// Ask whether to add the recipients of the message that aren't in the contacts, unless
+    /// they were asked about already.
+    fn prompt_add_unknown(&mut self, uri: &str) -> Result<Vec<Message>, Error> {
+        let mailboxes = headers::recipient_mailboxes(self.open_file(uri)?);
+        let document = document_path(uri);
+        let sources = self.shared.sources();
+        let asked = self.asked_recipients.entry(uri.to_owned()).or_default();
+        let unknown = mailboxes
+            .into_iter()
+            .filter(|m| {
+                !sources
+                    .for_document(document.as_deref())
+                    .has_contact(&m.email)
+            })
+            .filter(|m| asked.insert(fold(&m.email, false)))
+            .collect::<Vec<_>>();
+        drop(sources);
+        let message = match unknown.as_slice() {
+            [] => return Ok(Vec::new()),
+            [mailbox] => format!("{mailbox} isn't in the contacts, add them?"),
+            mailboxes => format!(
+                "{} recipients aren't in the contacts, add them? {}",
+                mailboxes.len(),
+                mailboxes.iter().join(", ")
+            ),
+        };
+        Ok(vec![self.request(
+            lsp_types::request::ShowMessageRequest::METHOD,
+            lsp_types::ShowMessageRequestParams {
+                typ: MessageType::INFO,
+                message,
+                actions: Some(vec![lsp_types::MessageActionItem {
+                    title: "Add".to_owned(),
+                    properties: HashMap::new(),
+                }]),
+            },
+            PendingRequest::AddRecipients {
+                document,
+                mailboxes: unknown,
+            },
+        )])
+    }
+
     fn handle_did_close_text_document_notification(
         &mut self,
         notification: Notification,
-    ) -> Vec<Message> {
+    ) -> Result<Vec<Message>, Error> {
         let dctdp =
-            serde_json::from_value::<lsp_types::DidCloseTextDocumentParams>(notification.params)
-                .unwrap();
+            serde_json::from_value::<lsp_types::DidCloseTextDocumentParams>(notification.params)?;
+        if self
+            .ignored_documents
+            .remove(dctdp.text_document.uri.as_str())
+        {
+            return Ok(Vec::new());
+        }
+        // a draft can be closed without being saved again, e.g. once the mail client sends it
+        let mut messages = Vec::new();
+        if self.on_save.contains(&OnSave::HarvestRecipients) && self.loading == 0 {
+            messages.extend(
+                self.harvest_recipients(dctdp.text_document.uri.as_str())
+                    .unwrap_or_default(),
+            );
+        }
         self.open_files.remove(dctdp.text_document.uri.as_ref());
-        Vec::new()
+        self.diagnostics.remove(dctdp.text_document.uri.as_str());
+        self.recorded_recipients
+            .remove(dctdp.text_document.uri.as_str());
+        self.asked_recipients
+            .remove(dctdp.text_document.uri.as_str());
+        Ok(messages)
         // log(
         //     &c,
         //     format!(
@@ -584,74 +3772,401 @@ impl Server {
         // );
     }
 
+    /// Swap the sources of removed workspace folders for those of added ones.
+    fn handle_did_change_workspace_folders_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<Vec<Message>, Error> {
+        let params = serde_json::from_value::<lsp_types::DidChangeWorkspaceFoldersParams>(
+            notification.params,
+        )?;
+        let generation = self.generation;
+        for folder in params.event.removed {
+            self.remove_workspace_folder(folder);
+        }
+        let mut messages = Vec::new();
+        for folder in params.event.added {
+            messages.extend(self.add_workspace_folder(folder));
+        }
+        // added sources publish diagnostics once they have loaded
+        if self.generation != generation && self.loading == 0 {
+            messages.extend(self.publish_diagnostics());
+        }
+        Ok(messages)
+    }
+
+    /// The content of the document, which [`OpenFiles`] reads from disk if it isn't open.
+    fn open_file(&mut self, uri: &str) -> Result<&str, Error> {
+        self.open_files
+            .get(uri)
+            .ok_or_else(|| Error::UnknownDocument(uri.to_owned()))
+    }
+
     fn get_mailbox_from_document(
         &mut self,
         tdp: &lsp_types::TextDocumentPositionParams,
     ) -> Option<Mailbox> {
-        let content = self.open_files.get(tdp.text_document.uri.as_ref());
-        get_mailbox_from_content(
-            content,
-            tdp.position.line as usize,
-            tdp.position.character as usize,
-        )
+        self.get_mailbox_span_from_document(tdp)
+            .map(|(_, mailbox)| mailbox)
     }
 
-    fn get_word_from_document(
+    /// The phone number at the position, normalized.
+    fn get_phone_from_document(
         &mut self,
         tdp: &lsp_types::TextDocumentPositionParams,
     ) -> Option<String> {
-        let content = self.open_files.get(tdp.text_document.uri.as_ref());
-        get_word_from_content(
-            content,
-            tdp.position.line as usize,
+        let line = self
+            .open_files
+            .line(tdp.text_document.uri.as_ref(), tdp.position.line as usize)?;
+        phone_at(line, tdp.position.character as usize).map(|(_, _, phone)| phone)
+    }
+
+    /// The mailbox at the position, along with the range it spans.
+    fn get_mailbox_span_from_document(
+        &mut self,
+        tdp: &lsp_types::TextDocumentPositionParams,
+    ) -> Option<(Range, Mailbox)> {
+        let line = self
+            .open_files
+            .line(tdp.text_document.uri.as_ref(), tdp.position.line as usize)?;
+        let (start, end, mailbox) = Mailbox::span_at(line, tdp.position.character as usize)?;
+        let position = |character: usize| Position {
+            line: tdp.position.line,
+            character: character as u32,
+        };
+        Some((
+            Range {
+                start: position(start),
+                end: position(end),
+            },
+            mailbox,
+        ))
+    }
+
+    /// The mailbox at the position and the range it spans, or else the names it may be on.
+    fn get_mailbox_or_names_from_document(
+        &mut self,
+        tdp: &lsp_types::TextDocumentPositionParams,
+    ) -> (Option<(Range, Mailbox)>, Vec<String>) {
+        if let Some(span) = self.get_mailbox_span_from_document(tdp) {
+            return (Some(span), Vec::new());
+        }
+        let names = self
+            .open_files
+            .line(tdp.text_document.uri.as_ref(), tdp.position.line as usize)
+            .map(|line| names_at(line, tdp.position.character as usize))
+            .unwrap_or_default();
+        (None, names)
+    }
+
+    fn get_query_from_document(
+        &mut self,
+        tdp: &lsp_types::TextDocumentPositionParams,
+    ) -> Option<CompletionQuery> {
+        let uri = tdp.text_document.uri.as_str();
+        let index = tdp.position.line as usize;
+        let continued = headers::continues_address_header(self.open_files.get(uri)?, index);
+        let line = self.open_files.line(uri, index)?;
+        get_query_from_line(
+            line,
             tdp.position.character as usize,
+            continued,
+            &self.trailers,
+        )
+    }
+
+    /// Whether the params of a request are for a document that is ignored.
+    fn ignores(&self, params: &serde_json::Value) -> bool {
+        params
+            .pointer("/textDocument/uri")
+            .and_then(|uri| Url::parse(uri.as_str()?).ok())
+            .is_some_and(|uri| self.ignored_documents.contains(uri.as_str()))
+    }
+
+    /// The profile for the document, from the language it was opened with.
+    fn profile(&self, uri: &str) -> Profile {
+        self.profiles.get(
+            self.open_files.language_id(uri),
+            document_path(uri).as_deref(),
         )
     }
 
+    /// Whether the line of the document is in the scope of its profile.
+    fn in_scope(&mut self, uri: &str, profile: Profile, line: usize) -> bool {
+        if profile.scope == Scope::Everywhere {
+            return true;
+        }
+        let Some(text) = self.open_files.get(uri) else {
+            return false;
+        };
+        let lines = profile.scope.lines(text, profile.mbox, &self.trailers);
+        text.lines()
+            .nth(line)
+            .is_some_and(|l| lines.includes(line, l))
+    }
+
     fn refresh_diagnostics(&mut self, file: &str) -> Vec<Diagnostic> {
-        let content = self.open_files.get(file);
-        // from https://www.regular-expressions.info/email.html
-        let re = regex::Regex::new(r"(?i)\b([A-Z0-9._%+-~/]+@[A-Z0-9.-]+\.[A-Z]{2,})\b").unwrap();
-        let mut email_locations = Vec::new();
-        for mtch in re.find_iter(content) {
-            let start = mtch.start();
-            let end = mtch.end();
-            let email = mtch.as_str();
-            email_locations.push((email, start, end));
-        }
-        let diagnostics = email_locations
-            .iter()
-            .filter(|(e, _, _)| !self.sources.contains(e))
-            .map(|(_, start, end)| {
-                let li = LineIndex::new(content);
-                let start = li.line_col(TextSize::new(*start as u32));
-                let end = li.line_col(TextSize::new(*end as u32));
-                Diagnostic {
-                    range: Range::new(
-                        Position::new(start.line, start.col),
-                        Position::new(end.line, end.col),
-                    ),
+        self.update_diagnostics(file, None)
+    }
+
+    /// Diagnostics for the file after it changed, only scanning the changed lines for addresses
+    /// when they are known.
+    fn update_diagnostics(
+        &mut self,
+        file: &str,
+        changes: Option<Vec<LineChange>>,
+    ) -> Vec<Diagnostic> {
+        if Url::parse(file).is_ok_and(|u| vcard_schema::is_vcard_file(Path::new(u.path()))) {
+            // addresses being added to a card aren't in the contacts until it is saved
+            let mut diagnostics = self
+                .open_files
+                .get(file)
+                .map(vcard_schema::validate)
+                .unwrap_or_default();
+            self.diagnostics.remove(file);
+            if self.conflict_diagnostics {
+                diagnostics.extend(self.conflict_diagnostics(file));
+            }
+            return diagnostics;
+        }
+        if self.loading > 0 {
+            // every address would look unknown, they're published once loading finishes
+            self.diagnostics.remove(file);
+            return Vec::new();
+        }
+
+        let profile = self.profile(file);
+        if !profile.diagnostics {
+            self.diagnostics.remove(file);
+            return Vec::new();
+        }
+        // changes can move lines in or out of the scope, so the whole file is checked again
+        let changes = changes.filter(|_| profile.scope == Scope::Everywhere);
+        let Some((scope_lines, header_lines)) = self.open_files.get(file).map(|t| {
+            (
+                profile.scope.lines(t, profile.mbox, &self.trailers),
+                Scope::Headers.lines(t, profile.mbox, &[]),
+            )
+        }) else {
+            return Vec::new();
+        };
+
+        let (mut diagnostics, lines) = match (self.diagnostics.remove(file), changes) {
+            (Some(mut diagnostics), Some(changes)) => {
+                // keep the diagnostics on untouched lines, moving them along with their lines
+                let mut lines = BTreeSet::new();
+                for change in changes {
+                    diagnostics.retain(|d| !change.replaced(d.range.start.line));
+                    for d in &mut diagnostics {
+                        d.range.start.line = change.shift(d.range.start.line);
+                        d.range.end.line = change.shift(d.range.end.line);
+                    }
+                    lines = lines
+                        .into_iter()
+                        .filter(|l| !change.replaced(*l))
+                        .map(|l| change.shift(l))
+                        .collect();
+                    lines.extend(change.start..change.start + change.inserted);
+                }
+                (diagnostics, lines)
+            }
+            _ => {
+                let lines =
+                    (0..self.open_files.line_count(file).unwrap_or_default() as u32).collect();
+                (Vec::new(), lines)
+            }
+        };
+        let document = document_path(file);
+        let sources = self.shared.sources();
+        let sources = sources.for_document(document.as_deref());
+        let today = chrono::Local::now().date_naive();
+        for line in lines {
+            let Some(text) = self.open_files.line(file, line as usize) else {
+                continue;
+            };
+            if !scope_lines.includes(line as usize, text) {
+                continue;
+            }
+            // the address sent from is checked against the identities rather than the contacts
+            let from = !self.identities.is_empty()
+                && headers::is_from_header(text)
+                && header_lines.includes(line as usize, text);
+            for (start, end, address) in find_addresses(text) {
+                let range = Range::new(
+                    Position::new(line, start as u32),
+                    Position::new(line, end as u32),
+                );
+                if from {
+                    if !self
+                        .identities
+                        .iter()
+                        .any(|i| fold(&i.email, false) == fold(address, false))
+                    {
+                        diagnostics.push(Diagnostic {
+                            range,
+                            severity: Some(DiagnosticSeverity::WARNING),
+                            message: "Address is not one of your identities".to_owned(),
+                            ..Default::default()
+                        });
+                    }
+                    continue;
+                }
+                if sources.contains(address) {
+                    let mailbox = Mailbox {
+                        name: None,
+                        email: address.to_owned(),
+                    };
+                    if let Some(birthday) = self.birthday_window.and_then(|window| {
+                        sources
+                            .birthday(&mailbox)
+                            .filter(|b| b.days_until(today) <= window)
+                    }) {
+                        let when = match birthday.days_until(today) {
+                            0 => "today".to_owned(),
+                            1 => "tomorrow".to_owned(),
+                            days => format!("in {days} days"),
+                        };
+                        let message = match birthday.age(birthday.next(today)) {
+                            Some(age) => format!("Birthday {when}, turning {age}"),
+                            None => format!("Birthday {when}"),
+                        };
+                        diagnostics.push(Diagnostic {
+                            range,
+                            severity: Some(DiagnosticSeverity::INFORMATION),
+                            message,
+                            ..Default::default()
+                        });
+                    }
+                    continue;
+                }
+                diagnostics.push(Diagnostic {
+                    range,
                     severity: Some(DiagnosticSeverity::HINT),
                     // source: todo!(),
                     message: "Address is not in contacts".to_owned(),
                     ..Default::default()
-                }
-            })
-            .collect::<Vec<_>>();
-        self.diagnostics = diagnostics.clone();
+                });
+            }
+        }
+        drop(sources);
+        diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+        self.diagnostics
+            .insert(file.to_owned(), diagnostics.clone());
+
+        if self.conflict_diagnostics {
+            diagnostics.extend(self.conflict_diagnostics(file));
+        }
+        diagnostics
+    }
+
+    /// Warnings on the contacts in the file that conflict with others.
+    fn conflict_diagnostics(&self, file: &str) -> Vec<Diagnostic> {
+        let Some(path) = document_path(file) else {
+            return Vec::new();
+        };
+        let mut diagnostics = Vec::new();
+        for conflict in self.shared.sources().conflicts() {
+            for location in conflict.locations.iter().filter(|l| l.path == path) {
+                let others = conflict
+                    .locations
+                    .iter()
+                    .filter(|l| *l != location)
+                    .map(|l| match l.line {
+                        Some(line) => format!("{}:{}", l.path.display(), line + 1),
+                        None => l.path.display().to_string(),
+                    })
+                    .join(", ");
+                let Ok(lsp_location) = lsp_types::Location::try_from(location.clone()) else {
+                    continue;
+                };
+                diagnostics.push(Diagnostic {
+                    range: lsp_location.range,
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    message: format!("{} (also at {others})", conflict.message),
+                    ..Default::default()
+                });
+            }
+        }
         diagnostics
     }
 }
 
-fn get_mailbox_from_content(content: &str, line: usize, character: usize) -> Option<Mailbox> {
-    let line = content.lines().nth(line)?;
-    Mailbox::from_line_at(line, character)
+/// Parse a completion item kind from its name, e.g. `Reference`.
+fn completion_kind(name: &str) -> Result<CompletionItemKind, String> {
+    CompletionItemKind::try_from(name)
+        .map_err(|err| format!("Invalid completion kind {name:?}: {err}"))
+}
+
+/// The text being completed at a position.
+struct CompletionQuery {
+    text: String,
+    /// Characters of the line to replace with the completion, when known.
+    replace: Option<(usize, usize)>,
+    /// Whether the query is in an address header, such as `To:`.
+    address_header: bool,
+    /// Whether the query is in a trailer naming a person, such as `Signed-off-by:`.
+    trailer: bool,
+    /// Whether the query is for a phone number, after `tel:`.
+    phone: bool,
+    line: String,
 }
 
-fn get_word_from_content(content: &str, line: usize, character: usize) -> Option<String> {
-    let line = content.lines().nth(line)?;
+/// The query at the character of the line, which continues an address header when `continued`.
+fn get_query_from_line(
+    line: &str,
+    character: usize,
+    continued: bool,
+    trailers: &[String],
+) -> Option<CompletionQuery> {
+    // completing inside an existing mailbox replaces all of it
+    let mailbox_span =
+        Mailbox::span_at(line, character).map(|(start, end, _)| (start, end.max(character + 1)));
+    let address_query = if continued {
+        headers::list_query_at(line, character)
+    } else {
+        headers::address_query_at(line, character)
+    };
+    if let Some((start, text)) = address_query {
+        return Some(CompletionQuery {
+            text,
+            replace: Some(mailbox_span.unwrap_or((start, character + 1))),
+            address_header: true,
+            trailer: false,
+            phone: false,
+            line: line.to_owned(),
+        });
+    }
+    if let Some((start, text)) = headers::trailer_query_at(line, character, trailers) {
+        // a trailer has a single mailbox, replaced in full
+        let end = line.chars().count().max(character + 1);
+        return Some(CompletionQuery {
+            text,
+            replace: Some((start, end)),
+            address_header: false,
+            trailer: true,
+            phone: false,
+            line: line.to_owned(),
+        });
+    }
+    if let Some((start, text)) = tel_query_at(line, character) {
+        return Some(CompletionQuery {
+            text,
+            replace: Some((start, character + 1)),
+            address_header: false,
+            trailer: false,
+            phone: true,
+            line: line.to_owned(),
+        });
+    }
     let word = get_word_from_line(line, character)?;
-    Some(word)
+    Some(CompletionQuery {
+        text: word,
+        replace: mailbox_span,
+        address_header: false,
+        trailer: false,
+        phone: false,
+        line: line.to_owned(),
+    })
 }
 
 const EMAIL_PUNC: &str = "._%+-@";
@@ -695,10 +4210,16 @@ fn get_word_from_line(line: &str, character: usize) -> Option<String> {
 
 fn main() {
     let args = Args::parse();
-    let (p, c, io) = connect(args.stdio);
-    let server = Server::new(&c, p);
-    let s = server.serve(c);
-    io.join().unwrap();
+    let s = Logging::init(&args).and_then(|logging| {
+        if let Some(command) = &args.command {
+            return cli::run(&args, command);
+        }
+        let (init_opts, capabilities, workspace_folders, c, io) = connect(&args, &logging)?;
+        let server = Server::new(&c, init_opts, &capabilities, workspace_folders, logging);
+        let s = server.serve(c);
+        io.join().map_err(|err| err.to_string())?;
+        s
+    });
     match s {
         Ok(()) => (),
         Err(s) => {
@@ -708,9 +4229,92 @@ fn main() {
     }
 }
 
+/// Extra data attached to completion items, used when resolving them.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CompletionItemData {
+    /// The document completed in, deciding the sources to render from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    document: Option<PathBuf>,
+    /// The mailbox of the item, when the label is not one (e.g. for names).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mailbox: Option<Mailbox>,
+    /// All addresses of a contact whose addresses were grouped into one item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    addresses: Vec<String>,
+    /// The members of a group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    members: Vec<Mailbox>,
+}
+
+/// Params of the [`CONTACT_CREATED_NOTIFICATION`].
+#[derive(Debug, Serialize)]
+struct ContactCreatedParams {
+    mailbox: Mailbox,
+    /// The label of the source the contact was created in.
+    source: String,
+    /// The file the contact was created in.
+    path: PathBuf,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct CreateContactCommandArguments {
     mailbox: Mailbox,
+    /// Structured name to use instead of splitting the mailbox's name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<StructuredName>,
+    /// The document the mailbox is in, deciding the sources to add it to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    document: Option<Url>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AddNoteCommandArguments {
+    /// The document with the mailbox of the contact.
+    document: Url,
+    /// A position in the mailbox.
+    position: Position,
+    /// The text to add, dated today.
+    note: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InsertAddressCommandArguments {
+    /// The document with the mailbox to replace.
+    document: Url,
+    /// A position in the mailbox.
+    position: Position,
+    /// Which of the contact's postal addresses to insert, the first by default.
+    #[serde(default)]
+    index: usize,
+}
+
+/// The [`CONTACT_CREATED_NOTIFICATION`] for a contact created in the source at the location.
+fn contact_created(mailbox: Mailbox, source: String, location: &Location) -> Message {
+    Message::Notification(Notification::new(
+        CONTACT_CREATED_NOTIFICATION.to_owned(),
+        ContactCreatedParams {
+            mailbox,
+            source,
+            path: location.path.clone(),
+        },
+    ))
+}
+
+/// A location for the user to pick, e.g. `jane.vcf:3`, with the line from 1.
+fn location_title(location: &Location) -> String {
+    let name = location.path.file_name().map_or_else(
+        || location.path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    );
+    match location.line {
+        Some(line) => format!("{name}:{}", line + 1),
+        None => name,
+    }
+}
+
+/// The path of the document, if it's a local file.
+fn document_path(uri: &str) -> Option<PathBuf> {
+    Url::parse(uri).ok()?.to_file_path().ok()
 }
 
 fn in_range(range: &Range, position: &Position) -> bool {
@@ -719,3 +4323,643 @@ fn in_range(range: &Range, position: &Position) -> bool {
         && (range.end.line > position.line
             || (range.end.line == position.line && range.end.character > position.character))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conflicts(value: serde_json::Value) -> Vec<String> {
+        InitializationOptions::parse(value).unwrap().conflicts()
+    }
+
+    fn parse_error(value: serde_json::Value) -> String {
+        InitializationOptions::parse(value).err().unwrap()
+    }
+
+    /// A directory for a test's files, removed when it is dropped.
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A server using a writable contact list of the contacts, with its files in a directory of
+    /// its own.
+    struct TestServer {
+        server: Server,
+        /// The server's end of the connection.
+        connection: Connection,
+        /// The client's end of the connection.
+        client: Connection,
+        dir: TempDir,
+    }
+
+    impl TestServer {
+        /// Start a server with the options on top of those for the contact list, waiting for it
+        /// to load.
+        fn new(contacts: &str, options: serde_json::Value) -> Self {
+            let mut test = Self::start(contacts, options);
+            test.load();
+            test
+        }
+
+        /// Start a server with the options on top of those for the contact list, its sources
+        /// loading in the background.
+        fn start(contacts: &str, options: serde_json::Value) -> Self {
+            let dir =
+                TempDir(std::env::temp_dir().join(format!("maills-{}", uuid::Uuid::new_v4())));
+            std::fs::create_dir_all(&dir.0).unwrap();
+            std::fs::write(dir.0.join("contacts"), contacts).unwrap();
+            let mut init = json!({
+                "sources": [{
+                    "type": "contact_list",
+                    "path": dir.0.join("contacts"),
+                    "read_only": false,
+                }],
+                "cache_dir": dir.0,
+                "data_dir": dir.0,
+                "track_usage": false,
+                "watch_files": false,
+            });
+            for (key, value) in options.as_object().unwrap() {
+                init[key] = value.clone();
+            }
+            let (connection, client) = Connection::memory();
+            let (_, handle) = reload::Layer::new(LevelFilter::OFF);
+            let logging = Logging {
+                level: LevelFilter::OFF,
+                handle,
+                tracer: Tracer::default(),
+            };
+            let server = Server::new(
+                &connection,
+                InitializationOptions::parse(init).unwrap(),
+                &ClientCapabilities::default(),
+                Vec::new(),
+                logging,
+            );
+            Self {
+                server,
+                connection,
+                client,
+                dir,
+            }
+        }
+
+        /// Wait for the sources to load.
+        fn load(&mut self) {
+            while self.server.loading > 0 {
+                let (i, folder, source) = self.server.loaded.recv().unwrap();
+                self.server.handle_loaded_source(i, folder, source);
+            }
+        }
+
+        /// The path of a file in the directory.
+        fn path(&self, name: &str) -> PathBuf {
+            self.dir.0.join(name)
+        }
+
+        /// Open a document of the text in the directory.
+        fn open(&mut self, name: &str, text: &str) -> Url {
+            let uri = Url::from_file_path(self.path(name)).unwrap();
+            let params = lsp_types::DidOpenTextDocumentParams {
+                text_document: lsp_types::TextDocumentItem::new(
+                    uri.clone(),
+                    "mail".to_owned(),
+                    1,
+                    text.to_owned(),
+                ),
+            };
+            self.server
+                .handle_did_open_text_document_notification(Notification::new(
+                    lsp_types::notification::DidOpenTextDocument::METHOD.to_owned(),
+                    params,
+                ))
+                .unwrap();
+            uri
+        }
+
+        /// Replace the range of the document, or all of it, with the text.
+        fn change(
+            &mut self,
+            uri: &Url,
+            version: i32,
+            range: Option<Range>,
+            text: &str,
+        ) -> Vec<Message> {
+            let params = lsp_types::DidChangeTextDocumentParams {
+                text_document: lsp_types::VersionedTextDocumentIdentifier::new(
+                    uri.clone(),
+                    version,
+                ),
+                content_changes: vec![lsp_types::TextDocumentContentChangeEvent {
+                    range,
+                    range_length: None,
+                    text: text.to_owned(),
+                }],
+            };
+            self.server
+                .handle_did_change_text_document_notification(Notification::new(
+                    lsp_types::notification::DidChangeTextDocument::METHOD.to_owned(),
+                    params,
+                ))
+                .unwrap()
+        }
+
+        fn save(&mut self, uri: &Url) -> Vec<Message> {
+            let params = lsp_types::DidSaveTextDocumentParams {
+                text_document: lsp_types::TextDocumentIdentifier::new(uri.clone()),
+                text: None,
+            };
+            self.server
+                .handle_did_save_text_document_notification(Notification::new(
+                    lsp_types::notification::DidSaveTextDocument::METHOD.to_owned(),
+                    params,
+                ))
+                .unwrap()
+        }
+
+        /// The completions after the character of the line.
+        fn complete(&mut self, uri: &Url, line: u32, character: u32) -> CompletionList {
+            let request = Request::new(
+                RequestId::from(1),
+                lsp_types::request::Completion::METHOD.to_owned(),
+                completion_params(uri, line, character),
+            );
+            let messages = self.server.handle_completion_request(request).unwrap();
+            let Some(Message::Response(response)) = messages.into_iter().next() else {
+                panic!("no response to the completion request");
+            };
+            completion_list(serde_json::from_value(response.result.unwrap_or_default()).unwrap())
+        }
+
+        /// Serve the client on a thread, until it disconnects.
+        fn serve(self) -> TestClient {
+            let Self {
+                server,
+                connection,
+                client,
+                dir,
+            } = self;
+            std::thread::spawn(move || server.serve(connection));
+            TestClient {
+                connection: client,
+                next_id: 1,
+                _dir: dir,
+            }
+        }
+    }
+
+    /// The client of a server serving on a thread.
+    struct TestClient {
+        connection: Connection,
+        next_id: i32,
+        _dir: TempDir,
+    }
+
+    impl TestClient {
+        /// Send a request and wait for its response.
+        fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+            let id = self.send::<R>(params);
+            self.response::<R>(id)
+        }
+
+        /// Send a request without waiting for its response.
+        fn send<R: lsp_types::request::Request>(&mut self, params: R::Params) -> RequestId {
+            let id = RequestId::from(self.next_id);
+            self.next_id += 1;
+            let request = Request::new(id.clone(), R::METHOD.to_owned(), params);
+            self.connection.sender.send(request.into()).unwrap();
+            id
+        }
+
+        fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+            let notification = Notification::new(N::METHOD.to_owned(), params);
+            self.connection.sender.send(notification.into()).unwrap();
+        }
+
+        /// Wait for the response to a request, skipping other messages.
+        fn response<R: lsp_types::request::Request>(&self, id: RequestId) -> R::Result {
+            loop {
+                let message = self
+                    .connection
+                    .receiver
+                    .recv_timeout(Duration::from_secs(10))
+                    .unwrap();
+                if let Message::Response(response) = message {
+                    if response.id == id {
+                        return serde_json::from_value(response.result.unwrap_or_default())
+                            .unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    fn completion_params(uri: &Url, line: u32, character: u32) -> lsp_types::CompletionParams {
+        lsp_types::CompletionParams {
+            text_document_position: TextDocumentPositionParams::new(
+                lsp_types::TextDocumentIdentifier::new(uri.clone()),
+                Position::new(line, character),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        }
+    }
+
+    fn completion_list(response: Option<lsp_types::CompletionResponse>) -> CompletionList {
+        match response {
+            Some(lsp_types::CompletionResponse::List(list)) => list,
+            Some(lsp_types::CompletionResponse::Array(items)) => CompletionList {
+                is_incomplete: false,
+                items,
+            },
+            None => CompletionList::default(),
+        }
+    }
+
+    fn labels(list: &CompletionList) -> Vec<&str> {
+        list.items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    #[test]
+    fn unknown_options() {
+        assert!(InitializationOptions::parse(json!({})).is_ok());
+        let err = parse_error(json!({ "vcard_dirs": "~/contacts" }));
+        assert!(err.contains("unknown field `vcard_dirs`"), "{err}");
+        let err = parse_error(json!({
+            "sources": [
+                { "type": "vcard", "path": "~/contacts" },
+                { "type": "contact_list", "paht": "~/aliases" },
+            ],
+        }));
+        assert!(
+            err.starts_with("Invalid initialization options: `sources[1]`: unknown field `paht`"),
+            "{err}"
+        );
+        let err = parse_error(json!({ "version": OPTIONS_VERSION + 1 }));
+        assert!(err.contains("maills needs updating"), "{err}");
+    }
+
+    #[test]
+    fn source_conflicts() {
+        let source = |options: serde_json::Value| conflicts(json!({ "sources": [options] }));
+        assert_eq!(
+            source(json!({ "type": "vcard", "path": "a", "diagnostics": false })),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            source(json!({ "type": "vcard", "path": "a", "url": "file:///b" })),
+            ["`sources[0]`: `path` and `url` are both set, using `path`"]
+        );
+        assert_eq!(
+            source(json!({ "type": "vcard", "path": "a", "format": "plain", "disk_index": true })),
+            [
+                "`sources[0]`: `format` only applies to contact_list sources",
+                "`sources[0]`: `disk_index` only applies to contact_list sources",
+            ]
+        );
+        assert_eq!(
+            source(
+                json!({ "type": "contact_list", "path": "a", "read_only": false, "categories": ["work"] })
+            ),
+            ["`sources[0]`: `categories` only applies to vcard sources"]
+        );
+        assert_eq!(
+            source(json!({ "type": "vcard", "path": "a", "encrypt_new": true })),
+            ["`sources[0]`: `encrypt_new` needs an `encrypt_command`"]
+        );
+        assert_eq!(
+            source(
+                json!({ "type": "vcard", "path": "a", "read_only": true, "new_contact_file": "b" })
+            ),
+            ["`sources[0]`: `new_contact_file` is unused as the source is `read_only`"]
+        );
+        // contact lists are read only by default
+        assert_eq!(
+            source(json!({ "type": "contact_list", "path": "a", "harvest": true })),
+            ["`sources[0]`: `harvest` needs the source not to be `read_only`"]
+        );
+        assert_eq!(
+            source(
+                json!({ "type": "contact_list", "path": "a", "harvest": true, "read_only": false })
+            ),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn option_conflicts() {
+        assert_eq!(conflicts(json!({})), Vec::<String>::new());
+        assert_eq!(
+            conflicts(json!({ "merge_strategy": "prefer_path" })),
+            ["the `prefer_path` merge_strategy needs a `merge_preferred_path`"]
+        );
+        assert_eq!(
+            conflicts(json!({ "merge_strategy": "newest", "merge_preferred_path": "a" })),
+            ["`merge_preferred_path` only applies to the `prefer_path` merge_strategy"]
+        );
+        assert_eq!(
+            conflicts(json!({ "merge_strategy": "prefer_path", "merge_preferred_path": "a" })),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            conflicts(json!({ "vcard_categories": ["work"], "contact_list_format": "plain" })),
+            [
+                "`vcard_categories` is ignored without `vcard_dir`",
+                "`contact_list_format` is ignored without `contact_list_file`",
+            ]
+        );
+        assert_eq!(
+            conflicts(json!({ "vcard_dir": "a", "vcard_categories": ["work"] })),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            conflicts(json!({ "on_save": ["harvest_recipients"] })),
+            ["the `harvest_recipients` on_save needs a source with `harvest` set"]
+        );
+    }
+
+    #[test]
+    fn completion_cache() {
+        let contacts = "Jane Doe jane@example.com\nJames Smith james@example.com\n";
+        let mut test = TestServer::new(contacts, json!({}));
+        let uri = test.open("mail", "To: ja\nCc: jan\n\nbody");
+        let list = test.complete(&uri, 0, 6);
+        assert!(!list.is_incomplete);
+        assert_eq!(labels(&list).len(), 2);
+
+        // the sources are gone, so a longer query can only be answered from the last matches
+        *test.server.shared.sources_mut() = Sources::default();
+        test.open("mail", "To: jan\nCc: jan\n\nbody");
+        let list = test.complete(&uri, 0, 7);
+        assert_eq!(labels(&list), ["Jane Doe <jane@example.com>"]);
+        // those are only for the line they were found for
+        assert!(test.complete(&uri, 1, 7).items.is_empty());
+    }
+
+    #[test]
+    fn completion_separator() {
+        let options = json!({ "completion_separator": ", " });
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", options);
+        let uri = test.open("mail", "To: ja\nCc: ja, bob@example.com\n\nbody");
+        let list = test.complete(&uri, 0, 6);
+        let end = Position::new(0, 6);
+        assert_eq!(
+            list.items[0].additional_text_edits,
+            Some(vec![lsp_types::TextEdit {
+                range: Range::new(end, end),
+                new_text: ", ".to_owned(),
+            }])
+        );
+        // one is already there
+        let list = test.complete(&uri, 1, 6);
+        assert_eq!(list.items[0].additional_text_edits, None);
+    }
+
+    #[test]
+    fn complete_in_mailbox() {
+        let line = "To: \"Jane\" <old@example.com>, bob@example.com";
+        let query = get_query_from_line(line, 6, false, &[]).unwrap();
+        assert_eq!(query.text, "Ja");
+        assert_eq!(query.replace, Some((4, 28)));
+        assert!(query.address_header);
+
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", json!({}));
+        let uri = test.open("mail", &format!("{line}\n\nbody"));
+        let list = test.complete(&uri, 0, 7);
+        assert_eq!(
+            list.items[0].text_edit,
+            Some(lsp_types::CompletionTextEdit::Edit(lsp_types::TextEdit {
+                range: Range::new(Position::new(0, 4), Position::new(0, 28)),
+                new_text: "Jane Doe <jane@example.com>".to_owned(),
+            }))
+        );
+    }
+
+    #[test]
+    fn lazy_loading() {
+        let mut test = TestServer::start("Jane Doe jane@example.com\n", json!({}));
+        let uri = test.open("mail", "To: ja\n\nbody");
+        // requests are answered straight away, from what has loaded so far
+        let list = test.complete(&uri, 0, 6);
+        assert!(list.is_incomplete);
+        assert!(list.items.is_empty());
+        let params = lsp_types::ExecuteCommandParams {
+            command: CREATE_CONTACT_COMMAND.to_owned(),
+            arguments: Vec::new(),
+            work_done_progress_params: Default::default(),
+        };
+        let request = Request::new(
+            RequestId::from(2),
+            lsp_types::request::ExecuteCommand::METHOD.to_owned(),
+            params,
+        );
+        assert!(matches!(
+            test.server.handle_execute_command_request(request),
+            Err(Error::Loading)
+        ));
+
+        test.load();
+        let list = test.complete(&uri, 0, 6);
+        assert!(!list.is_incomplete);
+        assert_eq!(labels(&list), ["Jane Doe <jane@example.com>"]);
+    }
+
+    #[test]
+    fn periodic_refresh() {
+        let options = json!({ "refresh_interval_secs": 1 });
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", options);
+        let uri = test.open("mail", "To: bo\n\nbody");
+        let contacts = "Jane Doe jane@example.com\nBob Smith bob@example.com\n";
+        std::fs::write(test.path("contacts"), contacts).unwrap();
+        let mut client = test.serve();
+        // the files aren't watched, so the change is only found when the sources are checked
+        let start = Instant::now();
+        loop {
+            let list = completion_list(
+                client.request::<lsp_types::request::Completion>(completion_params(&uri, 0, 6)),
+            );
+            if !list.items.is_empty() {
+                assert_eq!(labels(&list), ["Bob Smith <bob@example.com>"]);
+                break;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5), "not refreshed");
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn worker_requests() {
+        let contacts = "Jane Doe jane@example.com\nBob Smith bob@example.com\n";
+        let mut test = TestServer::new(contacts, json!({}));
+        let uri = test.open("mail", "To: jane@example.com\n\nbody");
+        let mut client = test.serve();
+        let hover_params = || lsp_types::HoverParams {
+            text_document_position_params: TextDocumentPositionParams::new(
+                lsp_types::TextDocumentIdentifier::new(uri.clone()),
+                Position::new(0, 6),
+            ),
+            work_done_progress_params: Default::default(),
+        };
+        let hover = |hover: Option<lsp_types::Hover>| {
+            serde_json::to_string(&hover.unwrap().contents).unwrap()
+        };
+
+        // a worker answers from the document as it was when asked, even after it changes
+        let id = client.send::<lsp_types::request::HoverRequest>(hover_params());
+        client.notify::<lsp_types::notification::DidChangeTextDocument>(
+            lsp_types::DidChangeTextDocumentParams {
+                text_document: lsp_types::VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+                content_changes: vec![lsp_types::TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: "To: bob@example.com\n\nbody".to_owned(),
+                }],
+            },
+        );
+        let first = hover(client.response::<lsp_types::request::HoverRequest>(id));
+        assert!(first.contains("jane@example.com"), "{first}");
+        let second = hover(client.request::<lsp_types::request::HoverRequest>(hover_params()));
+        assert!(second.contains("bob@example.com"), "{second}");
+    }
+
+    #[test]
+    fn completion_folds() {
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", json!({}));
+        let line = "To: alice@example.com, bob@example.com, carol@example.com, ja";
+        let uri = test.open("mail", &format!("{line}\n\nbody"));
+        let list = test.complete(&uri, 0, 61);
+        // the mailbox would go over the line length, so goes on a continuation line
+        assert_eq!(
+            list.items[0].additional_text_edits,
+            Some(vec![lsp_types::TextEdit {
+                range: Range::new(Position::new(0, 58), Position::new(0, 59)),
+                new_text: "\n ".to_owned(),
+            }])
+        );
+        assert_eq!(
+            list.items[0].text_edit,
+            Some(lsp_types::CompletionTextEdit::Edit(lsp_types::TextEdit {
+                range: Range::new(Position::new(0, 59), Position::new(0, 61)),
+                new_text: "Jane Doe <jane@example.com>".to_owned(),
+            }))
+        );
+    }
+
+    #[test]
+    fn full_sync() {
+        let options = |value| InitializationOptions::parse(value).unwrap();
+        let syncing = ClientCapabilities {
+            text_document: Some(lsp_types::TextDocumentClientCapabilities {
+                synchronization: Some(Default::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(options(json!({})).full_sync(&ClientCapabilities::default()));
+        assert!(!options(json!({})).full_sync(&syncing));
+        assert!(options(json!({ "full_sync": true })).full_sync(&syncing));
+
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", json!({}));
+        let uri = test.open("mail", "To: jane@example.com\n\nbody");
+        test.change(&uri, 2, None, "To: unknown@example.com\n\nbody");
+        assert_eq!(
+            test.server.open_files.line(uri.as_str(), 0),
+            Some("To: unknown@example.com")
+        );
+        assert_eq!(test.server.diagnostics[uri.as_str()].len(), 1);
+    }
+
+    #[test]
+    fn lost_changes() {
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", json!({}));
+        let uri = test.open("mail", "To: unknown@example.com\n\nbody");
+        assert_eq!(test.server.diagnostics[uri.as_str()].len(), 1);
+
+        // a change from before the document was opened means some were missed
+        let range = Range::new(Position::new(0, 4), Position::new(0, 11));
+        let messages = test.change(&uri, 1, Some(range), "jane");
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::Notification(n) if n.method == ShowMessage::METHOD
+        )));
+        assert!(!test.server.diagnostics.contains_key(uri.as_str()));
+        assert!(test.complete(&uri, 0, 6).items.is_empty());
+        // until the client sends all of it again
+        test.change(&uri, 3, None, "To: ja\n\nbody");
+        assert_eq!(test.server.diagnostics[uri.as_str()].len(), 0);
+        assert_eq!(
+            labels(&test.complete(&uri, 0, 6)),
+            ["Jane Doe <jane@example.com>"]
+        );
+    }
+
+    #[test]
+    fn prompt_add_unknown_on_save() {
+        let options = json!({ "on_save": ["prompt_add_unknown"] });
+        let mut test = TestServer::new("Jane Doe jane@example.com\n", options);
+        let uri = test.open(
+            "mail",
+            "To: jane@example.com, Bob Smith <bob@example.com>\n\nbody",
+        );
+        let messages = test.save(&uri);
+        let [Message::Request(request)] = messages.as_slice() else {
+            panic!("expected a request, got {messages:?}");
+        };
+        let params =
+            serde_json::from_value::<lsp_types::ShowMessageRequestParams>(request.params.clone())
+                .unwrap();
+        assert_eq!(
+            params.message,
+            "Bob Smith <bob@example.com> isn't in the contacts, add them?"
+        );
+        // they are only asked about once
+        assert!(test.save(&uri).is_empty());
+
+        let pending = test.server.pending_requests.remove(&request.id).unwrap();
+        let action = lsp_types::MessageActionItem {
+            title: "Add".to_owned(),
+            properties: HashMap::new(),
+        };
+        let messages = test
+            .server
+            .handle_response(pending, Response::new_ok(request.id.clone(), action));
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::Notification(n) if n.method == CONTACT_CREATED_NOTIFICATION
+        )));
+        let contacts = std::fs::read_to_string(test.path("contacts")).unwrap();
+        assert!(contacts.contains("bob@example.com"), "{contacts}");
+    }
+
+    #[test]
+    fn default_write_source() {
+        let write_source = |name: Option<&str>| {
+            InitializationOptions::parse(json!({
+                "sources": [
+                    { "type": "vcard", "path": "a" },
+                    { "type": "contact_list", "path": "b" },
+                    { "type": "contact_list", "path": "c", "label": "work" },
+                ],
+                "default_write_source": name,
+            }))
+            .unwrap()
+            .write_source()
+        };
+        assert_eq!(write_source(None), Ok(None));
+        assert_eq!(write_source(Some("work")), Ok(Some(2)));
+        // the first of the type
+        assert_eq!(write_source(Some("contact_list")), Ok(Some(1)));
+        assert_eq!(
+            write_source(Some("home")),
+            Err(
+                "Invalid default_write_source: no source has the label or type \"home\"".to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization as _};
+
+/// How a query is compared against the names and emails of contacts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// The name or email starts with the query.
+    Prefix,
+    /// The query appears anywhere in the name or email.
+    #[default]
+    Substring,
+    /// The characters of the query appear in order in the name or email.
+    Fuzzy,
+    /// The query starts any word of the name, or the local part of the email.
+    WordBoundary,
+}
+
+/// Options controlling how queries are matched, shared by all sources.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchOptions {
+    pub mode: MatchMode,
+    /// Ignore accents and other diacritics, so `jose` matches `José`.
+    pub strip_diacritics: bool,
+}
+
+/// Fold a string for comparison: lowercase it and, if `strip_diacritics` is set, decompose it
+/// (NFKD) and drop any combining marks.
+pub fn fold(s: &str, strip_diacritics: bool) -> String {
+    if strip_diacritics {
+        s.nfkd()
+            .filter(|c| !is_combining_mark(*c))
+            .flat_map(char::to_lowercase)
+            .collect()
+    } else {
+        s.to_lowercase()
+    }
+}
+
+/// A name or email folded ahead of time, so that matching it doesn't allocate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Folded {
+    lower: String,
+    /// Only kept when stripping diacritics changes anything, which is rare.
+    stripped: Option<String>,
+}
+
+impl Folded {
+    pub fn new(s: &str) -> Self {
+        let lower = fold(s, false);
+        let stripped = Some(fold(s, true)).filter(|stripped| *stripped != lower);
+        Self { lower, stripped }
+    }
+
+    /// The string as [`fold`] would give it.
+    pub fn get(&self, strip_diacritics: bool) -> &str {
+        match &self.stripped {
+            Some(stripped) if strip_diacritics => stripped,
+            _ => &self.lower,
+        }
+    }
+}
+
+/// A query to match contacts against, shared by all sources.
+///
+/// Queries with several words match a contact when the whole query matches one of its names or
+/// emails, or when every word matches at least one of them.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    query: String,
+    tokens: Vec<String>,
+    options: MatchOptions,
+}
+
+impl Matcher {
+    pub fn new(query: &str, options: MatchOptions) -> Self {
+        let query = fold(query.trim(), options.strip_diacritics);
+        let tokens = query.split_whitespace().map(|t| t.to_owned()).collect();
+        Self {
+            query,
+            tokens,
+            options,
+        }
+    }
+
+    pub fn options(&self) -> MatchOptions {
+        self.options
+    }
+
+    /// The folded words of the query.
+    pub fn tokens(&self) -> &[String] {
+        &self.tokens
+    }
+
+    /// Whether a contact with the given names (formatted name, nickname, ...) and emails matches
+    /// the query.
+    pub fn matches(&self, names: &[&str], emails: &[&str]) -> bool {
+        let names = names
+            .iter()
+            .map(|n| fold(n, self.options.strip_diacritics))
+            .collect::<Vec<_>>();
+        let emails = emails
+            .iter()
+            .map(|e| fold(e, self.options.strip_diacritics))
+            .collect::<Vec<_>>();
+        self.matches_fields(
+            names.iter().map(String::as_str),
+            emails.iter().map(String::as_str),
+        )
+    }
+
+    /// Like [`Matcher::matches`], for names and emails folded ahead of time.
+    pub fn matches_folded(&self, names: &[Folded], emails: &[Folded]) -> bool {
+        let strip_diacritics = self.options.strip_diacritics;
+        self.matches_fields(
+            names.iter().map(|n| n.get(strip_diacritics)),
+            emails.iter().map(|e| e.get(strip_diacritics)),
+        )
+    }
+
+    fn matches_fields<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str> + Clone,
+        emails: impl Iterator<Item = &'a str> + Clone,
+    ) -> bool {
+        let matches_token = |token: &str| {
+            names.clone().any(|n| self.match_name(n, token))
+                || emails.clone().any(|e| self.match_email(e, token))
+        };
+        matches_token(&self.query) || self.tokens.iter().all(|t| matches_token(t))
+    }
+
+    /// Score how well a contact matches the query, lower is better.
+    ///
+    /// Exact matches of a name or email come first, then prefixes, then matches at the start of a
+    /// word, then anything else.
+    pub fn score(&self, names: &[&str], emails: &[&str]) -> u32 {
+        names
+            .iter()
+            .chain(emails)
+            .map(|field| {
+                let field = fold(field, self.options.strip_diacritics);
+                if field == self.query {
+                    0
+                } else if field.starts_with(&self.query) {
+                    1
+                } else if field
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|w| w.starts_with(&self.query))
+                {
+                    2
+                } else if field.contains(&self.query) {
+                    3
+                } else {
+                    4
+                }
+            })
+            .min()
+            .unwrap_or(4)
+    }
+
+    /// Whether a name (formatted name, nickname, ...) matches the query.
+    pub fn matches_name(&self, name: &str) -> bool {
+        self.matches(&[name], &[])
+    }
+
+    /// Whether an email address matches the query.
+    pub fn matches_email(&self, email: &str) -> bool {
+        self.matches(&[], &[email])
+    }
+
+    fn match_name(&self, name: &str, token: &str) -> bool {
+        match self.options.mode {
+            MatchMode::Prefix => name.starts_with(token),
+            MatchMode::Substring => name.contains(token),
+            MatchMode::Fuzzy => fuzzy_match(name, token),
+            MatchMode::WordBoundary => name
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|w| w.starts_with(token)),
+        }
+    }
+
+    fn match_email(&self, email: &str, token: &str) -> bool {
+        match self.options.mode {
+            MatchMode::Prefix | MatchMode::WordBoundary => email.starts_with(token),
+            MatchMode::Substring => email.contains(token),
+            MatchMode::Fuzzy => fuzzy_match(email, token),
+        }
+    }
+}
+
+fn fuzzy_match(candidate: &str, query: &str) -> bool {
+    let mut candidate = candidate.chars();
+    query.chars().all(|q| candidate.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(mode: MatchMode) -> MatchOptions {
+        MatchOptions {
+            mode,
+            strip_diacritics: true,
+        }
+    }
+
+    #[test]
+    fn modes() {
+        let name = "Jane Doe";
+        let email = "jane.doe@example.com";
+
+        let m = Matcher::new("doe", options(MatchMode::Prefix));
+        assert!(!m.matches_name(name));
+        assert!(!m.matches_email(email));
+
+        let m = Matcher::new("doe", options(MatchMode::Substring));
+        assert!(m.matches_name(name));
+        assert!(m.matches_email(email));
+
+        let m = Matcher::new("jdo", options(MatchMode::Fuzzy));
+        assert!(m.matches_name(name));
+        assert!(m.matches_email(email));
+
+        let m = Matcher::new("Doe", options(MatchMode::WordBoundary));
+        assert!(m.matches_name(name));
+        assert!(!m.matches_email(email));
+        let m = Matcher::new("oe", options(MatchMode::WordBoundary));
+        assert!(!m.matches_name(name));
+    }
+
+    #[test]
+    fn multiple_words() {
+        let names = ["Jane Doe"];
+        let emails = ["jd@example.com"];
+
+        let m = Matcher::new("jane doe", options(MatchMode::Substring));
+        assert!(m.matches(&names, &emails));
+        let m = Matcher::new("doe jane", options(MatchMode::Substring));
+        assert!(m.matches(&names, &emails));
+        let m = Matcher::new("jane example", options(MatchMode::WordBoundary));
+        assert!(!m.matches(&names, &emails));
+        let m = Matcher::new("jane jd", options(MatchMode::WordBoundary));
+        assert!(m.matches(&names, &emails));
+        let m = Matcher::new("jane d", options(MatchMode::Prefix));
+        assert!(m.matches(&names, &emails));
+        let m = Matcher::new("jane smith", options(MatchMode::Substring));
+        assert!(!m.matches(&names, &emails));
+    }
+
+    #[test]
+    fn scores() {
+        let m = Matcher::new("jane", options(MatchMode::Substring));
+        assert_eq!(m.score(&["Jane"], &["jd@example.com"]), 0);
+        assert_eq!(m.score(&["Jane Doe"], &["jd@example.com"]), 1);
+        assert_eq!(m.score(&["Mary Jane"], &["mj@example.com"]), 2);
+        assert_eq!(m.score(&["Maryjane"], &["mj@example.com"]), 3);
+    }
+
+    #[test]
+    fn folding() {
+        assert_eq!(fold("José GARCÍA", true), "jose garcia");
+        assert_eq!(fold("José GARCÍA", false), "josé garcía");
+
+        let m = Matcher::new("jose", options(MatchMode::Prefix));
+        assert!(m.matches_name("José García"));
+        let m = Matcher::new("JOSÉ", options(MatchMode::Prefix));
+        assert!(m.matches_name("Jose Garcia"));
+        let m = Matcher::new(
+            "jose",
+            MatchOptions {
+                mode: MatchMode::Prefix,
+                strip_diacritics: false,
+            },
+        );
+        assert!(!m.matches_name("José García"));
+        assert!(!m.matches_folded(&[Folded::new("José García")], &[]));
+
+        let folded = Folded::new("José GARCÍA");
+        assert_eq!(folded.get(true), "jose garcia");
+        assert_eq!(folded.get(false), "josé garcía");
+        let m = Matcher::new("jose", options(MatchMode::Prefix));
+        assert!(m.matches_folded(&[folded], &[]));
+    }
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+
+/// Honorifics that come before a name.
+const PREFIXES: &[&str] = &["mr", "mrs", "ms", "miss", "mx", "dr", "prof", "sir", "dame"];
+/// Suffixes that come after a name.
+const SUFFIXES: &[&str] = &["jr", "sr", "ii", "iii", "iv", "phd", "md", "esq"];
+/// Particles that belong to the family name, e.g. the `van` in `Ludwig van Beethoven`.
+const PARTICLES: &[&str] = &[
+    "van", "von", "der", "den", "de", "del", "della", "di", "da", "du", "la", "le", "ter", "bin",
+    "al",
+];
+
+/// Most words in a name looked up by [`names_at`].
+const MAX_NAME_WORDS: usize = 4;
+
+/// The structured name of a contact, as in the vcard `N` property.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredName {
+    #[serde(default)]
+    pub family: Vec<String>,
+    #[serde(default)]
+    pub given: Vec<String>,
+    #[serde(default)]
+    pub additional: Vec<String>,
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    #[serde(default)]
+    pub suffixes: Vec<String>,
+}
+
+impl StructuredName {
+    /// Split a display name into its parts heuristically.
+    ///
+    /// `Family, Given` is taken as written, otherwise the last word (along with any particles like
+    /// `van` before it) is the family name, the first word is the given name and anything between
+    /// is additional names.
+    /// Common honorifics and suffixes are split off first.
+    pub fn from_display_name(name: &str) -> Self {
+        let mut s = Self::default();
+        if let Some((family, given)) = name.split_once(',') {
+            s.family = vec![family.trim().to_owned()];
+            s.given = given.split_whitespace().map(|w| w.to_owned()).collect();
+            if s.given.len() > 1 {
+                s.additional = s.given.split_off(1);
+            }
+            return s;
+        }
+
+        let is_one_of = |word: &str, list: &[&str]| {
+            list.contains(&word.trim_end_matches('.').to_lowercase().as_str())
+        };
+        let mut words = name.split_whitespace().collect::<Vec<_>>();
+        while words.len() > 1 && is_one_of(words[0], PREFIXES) {
+            s.prefixes.push(words.remove(0).to_owned());
+        }
+        while words.len() > 1 && is_one_of(words[words.len() - 1], SUFFIXES) {
+            s.suffixes.insert(0, words.pop().unwrap().to_owned());
+        }
+        if words.len() == 1 {
+            s.given = vec![words[0].to_owned()];
+            return s;
+        }
+        if let Some(family) = words.pop() {
+            let mut family = vec![family];
+            while words.len() > 1 && is_one_of(words[words.len() - 1], PARTICLES) {
+                family.insert(0, words.pop().unwrap());
+            }
+            s.family = vec![family.join(" ")];
+        }
+        if !words.is_empty() {
+            s.given = vec![words.remove(0).to_owned()];
+        }
+        s.additional = words.into_iter().map(|w| w.to_owned()).collect();
+        s
+    }
+
+    /// The value of the vcard `N` property for this name.
+    pub fn to_vcard_value(&self) -> String {
+        [
+            &self.family,
+            &self.given,
+            &self.additional,
+            &self.prefixes,
+            &self.suffixes,
+        ]
+        .iter()
+        .map(|part| part.iter().map(|v| escape(v)).collect::<Vec<_>>().join(","))
+        .collect::<Vec<_>>()
+        .join(";")
+    }
+}
+
+/// Escape a component of a structured vcard value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+}
+
+/// Names the character in the line may be on, for looking up contacts mentioned in prose.
+///
+/// These are runs of capitalised words (and particles like `van` between them) containing the word
+/// at the character, longest first.
+pub fn names_at(line: &str, character: usize) -> Vec<String> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '-' || c == '\'';
+    // character ranges of the words in the line
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in chars.iter().chain(std::iter::once(&' ')).enumerate() {
+        match (is_word_char(*c), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    let text = |first: usize, last: usize| {
+        chars[words[first].0..words[last].1]
+            .iter()
+            .collect::<String>()
+    };
+    let capitalised = |i: usize| chars[words[i].0].is_uppercase();
+    let in_name = |i: usize| capitalised(i) || PARTICLES.contains(&text(i, i).as_str());
+    // words are in the same name when only a space separates them
+    let joined = |i: usize| chars[words[i].1..words[i + 1].0] == [' '];
+
+    let Some(at) = words
+        .iter()
+        .position(|(s, e)| *s <= character && character <= *e)
+    else {
+        return Vec::new();
+    };
+    if !capitalised(at) {
+        return Vec::new();
+    }
+    let mut first = at;
+    while first > 0 && joined(first - 1) && in_name(first - 1) {
+        first -= 1;
+    }
+    let mut last = at;
+    while last + 1 < words.len() && joined(last) && in_name(last + 1) {
+        last += 1;
+    }
+
+    let mut names = Vec::new();
+    for len in (1..=MAX_NAME_WORDS.min(last - first + 1)).rev() {
+        for start in first.max((at + 1).saturating_sub(len))..=at.min(last + 1 - len) {
+            let end = start + len - 1;
+            if capitalised(start) && capitalised(end) {
+                names.push(text(start, end));
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(name: &str) -> String {
+        StructuredName::from_display_name(name).to_vcard_value()
+    }
+
+    #[test]
+    fn from_display_name() {
+        assert_eq!(n("Jane Doe"), "Doe;Jane;;;");
+        assert_eq!(n("Jane Mary Doe"), "Doe;Jane;Mary;;");
+        assert_eq!(n("Doe, Jane"), "Doe;Jane;;;");
+        assert_eq!(n("Ludwig van Beethoven"), "van Beethoven;Ludwig;;;");
+        assert_eq!(n("Dr. Jane Doe Jr."), "Doe;Jane;;Dr.;Jr.");
+        assert_eq!(n("Cher"), ";Cher;;;");
+        assert_eq!(n(""), ";;;;");
+    }
+
+    #[test]
+    fn names_in_prose() {
+        assert_eq!(names_at("cc Jane Doe on this", 4), ["Jane Doe", "Jane"]);
+        assert_eq!(
+            names_at("Ask Jane Doe.", 5),
+            ["Ask Jane Doe", "Ask Jane", "Jane Doe", "Jane"]
+        );
+        assert_eq!(
+            names_at("Thanks, Ludwig van Beethoven", 25),
+            ["Ludwig van Beethoven", "Beethoven"]
+        );
+        assert!(names_at("cc jane doe", 4).is_empty());
+        assert!(names_at("cc Jane", 2).is_empty());
+    }
+}
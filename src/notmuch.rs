@@ -0,0 +1,168 @@
+use std::{
+    io::Write as _,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use chrono::{Days, NaiveDate};
+
+use crate::{AddressWeights, Usage};
+
+/// Days back to look for mail with an address in, shortest first, to tell when it was last seen.
+const WINDOWS: [u64; 4] = [7, 30, 90, 365];
+
+/// Weights of the addresses from how much mail there is from and to them in the notmuch
+/// database, and how recently.
+///
+/// The weights are cached at the path against the revision of the database, so they are only
+/// counted again once there is new mail or new addresses.
+pub fn notmuch_weights(
+    emails: &[String],
+    cache: &Path,
+    today: NaiveDate,
+) -> Result<AddressWeights, String> {
+    let revision = run(&["count", "--lastmod", "*"], None)?;
+    // the count of messages changes along with the revision, so is left out
+    let tag = revision
+        .trim()
+        .split('\t')
+        .skip(1)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(weights) = AddressWeights::load(cache, &tag) {
+        if weights.covers(emails.iter().map(|e| e.as_str())) {
+            return Ok(weights);
+        }
+    }
+    let mut input = queries(emails, today).join("\n");
+    input.push('\n');
+    let counts = run(&["count", "--batch"], Some(&input))?;
+    let weights = parse_counts(emails, &counts, today)?;
+    if let Err(err) = weights.save(cache, &tag) {
+        tracing::warn!(path = %cache.display(), "Failed to cache notmuch weights: {err}");
+    }
+    Ok(weights)
+}
+
+/// The queries to count for each address: all of its mail, then its mail in each window.
+fn queries(emails: &[String], today: NaiveDate) -> Vec<String> {
+    emails
+        .iter()
+        .flat_map(|email| {
+            let email = email.replace('"', "");
+            let all = format!("(from:\"{email}\" or to:\"{email}\")");
+            let windows = WINDOWS.iter().map(|days| {
+                let since = today - Days::new(*days);
+                format!("{all} and date:{}..", since.format("%Y-%m-%d"))
+            });
+            let mut queries = vec![all.clone()];
+            queries.extend(windows);
+            queries
+        })
+        .collect()
+}
+
+/// Weights from the counts of the [`queries`], taking an address to have been last seen at the
+/// start of the shortest window it has mail in.
+fn parse_counts(
+    emails: &[String],
+    output: &str,
+    today: NaiveDate,
+) -> Result<AddressWeights, String> {
+    let counts = output
+        .lines()
+        .map(|l| l.trim().parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Unexpected output from notmuch count: {err}"))?;
+    let per_email = WINDOWS.len() + 1;
+    if counts.len() != emails.len() * per_email {
+        return Err(format!(
+            "Expected {} counts from notmuch, got {}",
+            emails.len() * per_email,
+            counts.len()
+        ));
+    }
+    let mut weights = AddressWeights::default();
+    for (email, counts) in emails.iter().zip(counts.chunks(per_email)) {
+        let last = WINDOWS
+            .iter()
+            .zip(&counts[1..])
+            .find(|(_, count)| **count > 0)
+            .map(|(days, _)| today - Days::new(*days));
+        weights.insert(
+            email,
+            Usage {
+                last,
+                messages: Some(counts[0]),
+            },
+        );
+    }
+    Ok(weights)
+}
+
+fn run(args: &[&str], input: Option<&str>) -> Result<String, String> {
+    let mut child = Command::new("notmuch")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to run notmuch: {err}"))?;
+    // dropping stdin once written closes it, for notmuch to finish
+    let mut stdin = child.stdin.take().unwrap();
+    if let Some(input) = input {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|err| format!("Failed to write to notmuch: {err}"))?;
+    }
+    drop(stdin);
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("Failed to run notmuch: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "notmuch {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_to_weights() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let emails = vec!["jane@example.com".to_owned(), "bob@example.com".to_owned()];
+        let queries = queries(&emails, today);
+        assert_eq!(queries.len(), 10);
+        assert_eq!(
+            queries[0],
+            "(from:\"jane@example.com\" or to:\"jane@example.com\")"
+        );
+        assert_eq!(
+            queries[2],
+            "(from:\"jane@example.com\" or to:\"jane@example.com\") and date:2024-01-31.."
+        );
+
+        let weights = parse_counts(&emails, "12\n0\n2\n5\n12\n1\n0\n0\n0\n0\n", today).unwrap();
+        assert_eq!(
+            weights.get("jane@example.com"),
+            Some(Usage {
+                last: NaiveDate::from_ymd_opt(2024, 1, 31),
+                messages: Some(12),
+            })
+        );
+        assert_eq!(
+            weights.get("bob@example.com"),
+            Some(Usage {
+                last: None,
+                messages: Some(1),
+            })
+        );
+        assert!(parse_counts(&emails, "12\n", today).is_err());
+    }
+}
@@ -1,63 +1,403 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, ops::Range, path::PathBuf};
 
-use lsp_types::{Position, TextDocumentContentChangeEvent};
+use lsp_types::{Position, TextDocumentContentChangeEvent, Url};
 
 #[derive(Default)]
 pub struct OpenFiles {
-    files: BTreeMap<String, String>,
+    files: BTreeMap<String, OpenFile>,
 }
 
 impl OpenFiles {
-    fn load(&mut self, uri: &str) {
-        let content = std::fs::read_to_string(uri).unwrap();
-        self.add(uri.to_string(), content);
+    /// Read a file that isn't open from disk.
+    fn load(&mut self, uri: &str) -> Option<()> {
+        let path = Url::parse(uri)
+            .ok()
+            .and_then(|u| u.to_file_path().ok())
+            .unwrap_or_else(|| PathBuf::from(uri));
+        let content = std::fs::read_to_string(path).ok()?;
+        self.add(uri.to_string(), None, None, content);
+        Some(())
     }
 
-    pub fn add(&mut self, uri: String, content: String) {
-        self.files.insert(uri, content);
+    /// Add a file opened in the editor, with the language id and version it gave.
+    pub fn add(
+        &mut self,
+        uri: String,
+        language_id: Option<String>,
+        version: Option<i32>,
+        content: String,
+    ) {
+        let mut file = OpenFile::new(content);
+        file.language_id = language_id;
+        file.version = version;
+        self.files.insert(uri, file);
     }
 
-    pub fn get(&mut self, uri: &str) -> &str {
-        if !self.files.contains_key(uri) {
-            self.load(uri)
-        }
-        self.files.get(uri).unwrap()
+    /// The language id of an open file, unknown for those read from disk.
+    pub fn language_id(&self, uri: &str) -> Option<&str> {
+        self.files.get(uri)?.language_id.as_deref()
+    }
+
+    /// Whether the file's content is out of date, as changes to it were missed, until the client
+    /// sends all of it.
+    pub fn is_stale(&self, uri: &str) -> bool {
+        self.files.get(uri).is_some_and(|f| f.stale)
+    }
+
+    /// The content of the file, or `None` if it isn't open and can't be read, or is stale.
+    pub fn get(&mut self, uri: &str) -> Option<&str> {
+        Some(&self.file(uri)?.content)
+    }
+
+    /// A line of the file, without its line ending.
+    pub fn line(&mut self, uri: &str, line: usize) -> Option<&str> {
+        self.file(uri)?.line(line)
     }
 
-    pub fn apply_changes(&mut self, uri: &str, changes: Vec<TextDocumentContentChangeEvent>) {
-        let content = self.files.get_mut(uri).unwrap();
+    /// The position of a byte offset in the file.
+    pub fn position(&mut self, uri: &str, offset: usize) -> Option<Position> {
+        Some(self.file(uri)?.position(offset))
+    }
+
+    /// The byte offset of a position in the file, clamped to the end of its line.
+    pub fn offset(&mut self, uri: &str, position: Position) -> Option<usize> {
+        Some(self.file(uri)?.offset(position))
+    }
+
+    /// Apply the changes of the version of the file in order.
+    ///
+    /// Changes that can't follow those applied already, as their version isn't newer or their
+    /// range is past the end of the file, mean some were missed, so the file is stale and
+    /// ignores changes to parts of it until one replaces the whole content.
+    ///
+    /// Returns `None` if the file isn't open.
+    pub fn apply_changes(
+        &mut self,
+        uri: &str,
+        version: i32,
+        changes: Vec<TextDocumentContentChangeEvent>,
+    ) -> Option<Applied> {
+        let file = self.files.get_mut(uri)?;
+        let was_stale = file.stale;
+        // versions can skip numbers, e.g. neovim's count its changes, so only going back is a
+        // sign of changes in the wrong order
+        if file.version.is_some_and(|v| version <= v) {
+            file.stale = true;
+        }
+        file.version = Some(version);
+        let mut applied = Applied::Lines(Vec::new());
         for change in changes {
             if let Some(range) = change.range {
-                let start = resolve_position(content, range.start);
-                let end = resolve_position(content, range.end);
-                content.replace_range(start..end, &change.text);
+                if !file.contains(range) {
+                    file.stale = true;
+                }
+                if file.stale {
+                    continue;
+                }
+                let start = file.offset(range.start);
+                let end = file.offset(range.end);
+                file.replace(start..end, &change.text);
+                if let Applied::Lines(line_changes) = &mut applied {
+                    line_changes.push(LineChange {
+                        start: range.start.line,
+                        removed: range.end.line - range.start.line + 1,
+                        inserted: change.text.matches('\n').count() as u32 + 1,
+                    });
+                }
             } else {
                 // full content replace
-                *content = change.text;
+                let language_id = file.language_id.take();
+                *file = OpenFile::new(change.text);
+                file.language_id = language_id;
+                file.version = Some(version);
+                applied = Applied::Full;
             }
         }
+        Some(match (was_stale, file.stale) {
+            (false, true) => Applied::Lost,
+            (true, true) => Applied::Stale,
+            (true, false) => Applied::Full,
+            (false, false) => applied,
+        })
     }
 
     pub fn remove(&mut self, uri: &str) {
         self.files.remove(uri);
     }
-}
 
-fn resolve_position(content: &str, pos: Position) -> usize {
-    let mut count = 0;
-    let mut lines = 0;
-    let mut character = 0;
-    for c in content.chars() {
-        if lines >= pos.line && character >= pos.character {
-            break;
+    /// The number of lines in the file.
+    pub fn line_count(&mut self, uri: &str) -> Option<usize> {
+        Some(self.file(uri)?.line_starts.len())
+    }
+
+    /// The URIs of all open files.
+    pub fn uris(&self) -> Vec<String> {
+        self.files.keys().cloned().collect()
+    }
+
+    fn file(&mut self, uri: &str) -> Option<&OpenFile> {
+        if !self.files.contains_key(uri) {
+            self.load(uri)?;
         }
-        if c == '\n' {
-            lines += 1;
-            character = 0;
+        self.files.get(uri).filter(|f| !f.stale)
+    }
+}
+
+/// What applying changes to a file did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Applied {
+    /// The changes replaced these lines.
+    Lines(Vec<LineChange>),
+    /// The whole content was replaced, making it up to date if it was stale.
+    Full,
+    /// Changes were missed, so the file has just become stale.
+    Lost,
+    /// The file is still stale, the changes were ignored.
+    Stale,
+}
+
+/// Lines of a file replaced by a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineChange {
+    /// The first line the change touched.
+    pub start: u32,
+    /// How many lines the change touched before it was applied.
+    pub removed: u32,
+    /// How many lines replaced them.
+    pub inserted: u32,
+}
+
+impl LineChange {
+    /// Where a line that the change didn't touch is now.
+    pub fn shift(&self, line: u32) -> u32 {
+        if line < self.start {
+            line
         } else {
-            character += 1;
+            line + self.inserted - self.removed
+        }
+    }
+
+    /// Whether the line was replaced by the change.
+    pub fn replaced(&self, line: u32) -> bool {
+        (self.start..self.start + self.removed).contains(&line)
+    }
+}
+
+/// The content of an open file along with where its lines start, kept up to date as it changes.
+struct OpenFile {
+    content: String,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    language_id: Option<String>,
+    /// The version of the content from the client, if it's open in the editor.
+    version: Option<i32>,
+    /// Whether changes were missed, so the content is out of date.
+    stale: bool,
+}
+
+impl OpenFile {
+    fn new(content: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            content,
+            line_starts,
+            language_id: None,
+            version: None,
+            stale: false,
+        }
+    }
+
+    /// Whether the range is within the lines of the file, where its end can be the start of the
+    /// line after the last.
+    fn contains(&self, range: lsp_types::Range) -> bool {
+        let lines = self.line_starts.len() as u32;
+        let within = |p: Position| p.line < lines || (p.line == lines && p.character == 0);
+        range.start <= range.end && within(range.start) && within(range.end)
+    }
+
+    fn line(&self, line: usize) -> Option<&str> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.content.len());
+        let line = &self.content[start..end];
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|s| *s <= offset) - 1;
+        let character = self.content[self.line_starts[line]..offset].chars().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    fn offset(&self, position: Position) -> usize {
+        let Some(line) = self.line(position.line as usize) else {
+            return self.content.len();
+        };
+        let character = line
+            .char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(i, _)| i);
+        self.line_starts[position.line as usize] + character
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        // lines starting within the replaced text are gone, those after it move
+        let first = self.line_starts.partition_point(|s| *s <= range.start);
+        let last = self.line_starts.partition_point(|s| *s <= range.end);
+        for start in &mut self.line_starts[last..] {
+            *start = *start + text.len() - range.len();
         }
-        count += 1;
+        let new_starts = text
+            .match_indices('\n')
+            .map(|(i, _)| range.start + i + 1)
+            .collect::<Vec<_>>();
+        self.line_starts.splice(first..last, new_starts);
+        self.content.replace_range(range, text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(lsp_types::Range::new(
+                Position::new(start.0, start.1),
+                Position::new(end.0, end.1),
+            )),
+            range_length: None,
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn changes() {
+        let mut file = OpenFile::new("To: jane@example.com\r\nSubject: héllo\n\nbody".to_owned());
+        assert_eq!(file.line(1), Some("Subject: héllo"));
+        assert_eq!(
+            file.position(file.content.find("llo").unwrap()),
+            Position::new(1, 11)
+        );
+        assert_eq!(file.position(file.content.len()), Position::new(3, 4));
+        assert_eq!(
+            file.offset(Position::new(1, 11)),
+            file.content.find("llo").unwrap()
+        );
+        assert_eq!(
+            file.offset(Position::new(1, 100)),
+            file.content.find("\n\n").unwrap()
+        );
+
+        let start = file.offset(Position::new(1, 9));
+        let end = file.offset(Position::new(2, 0));
+        file.replace(start..end, "hi\nCc: bob@example.com\n");
+        let expected = OpenFile::new(file.content.clone());
+        assert_eq!(file.line_starts, expected.line_starts);
+        assert_eq!(file.line(2), Some("Cc: bob@example.com"));
+
+        let start = file.offset(Position::new(0, 4));
+        let end = file.offset(Position::new(2, 4));
+        file.replace(start..end, "");
+        assert_eq!(
+            file.line_starts,
+            OpenFile::new(file.content.clone()).line_starts
+        );
+        assert_eq!(file.line(0), Some("To: bob@example.com"));
+    }
+
+    #[test]
+    fn line_changes() {
+        let mut files = OpenFiles::default();
+        files.add(
+            "mail".to_owned(),
+            Some("mail".to_owned()),
+            Some(1),
+            "To: a\nCc: b\nSubject: c\n\nbody".to_owned(),
+        );
+        let changes = files.apply_changes("mail", 2, vec![change((1, 4), (1, 5), "b,\nBcc: d")]);
+        let expected = LineChange {
+            start: 1,
+            removed: 1,
+            inserted: 2,
+        };
+        assert_eq!(changes, Some(Applied::Lines(vec![expected])));
+        assert_eq!(files.line("mail", 2), Some("Bcc: d"));
+        assert!(expected.replaced(1));
+        assert_eq!(expected.shift(0), 0);
+        assert_eq!(expected.shift(2), 3);
+
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "To: a".to_owned(),
+        };
+        assert_eq!(
+            files.apply_changes("mail", 3, vec![full.clone(), change((0, 4), (0, 5), "b")]),
+            Some(Applied::Full)
+        );
+        assert_eq!(files.line("mail", 0), Some("To: b"));
+        assert_eq!(files.line_count("mail"), Some(1));
+        assert_eq!(files.language_id("mail"), Some("mail"));
+
+        // a change to a line that isn't there was made to content the server didn't get
+        assert_eq!(
+            files.apply_changes("mail", 5, vec![change((3, 0), (3, 1), "x")]),
+            Some(Applied::Lost)
+        );
+        assert!(files.is_stale("mail"));
+        assert_eq!(files.line("mail", 0), None);
+        assert_eq!(
+            files.apply_changes("mail", 6, vec![change((0, 0), (0, 1), "x")]),
+            Some(Applied::Stale)
+        );
+        assert_eq!(
+            files.apply_changes("mail", 7, vec![full.clone()]),
+            Some(Applied::Full)
+        );
+        assert_eq!(files.line("mail", 0), Some("To: a"));
+        assert_eq!(
+            files.apply_changes("mail", 7, vec![change((0, 0), (0, 1), "x")]),
+            Some(Applied::Lost)
+        );
+        assert_eq!(files.get("file:///nonexistent/mail"), None);
+    }
+
+    #[test]
+    fn versions() {
+        let mut files = OpenFiles::default();
+        files.add("mail".to_owned(), None, Some(1), "To: a".to_owned());
+        // versions can skip numbers
+        assert_eq!(
+            files.apply_changes("mail", 3, vec![change((0, 4), (0, 5), "b")]),
+            Some(Applied::Lines(vec![LineChange {
+                start: 0,
+                removed: 1,
+                inserted: 1,
+            }]))
+        );
+        // but an older one came out of order
+        assert_eq!(
+            files.apply_changes("mail", 2, vec![change((0, 4), (0, 5), "c")]),
+            Some(Applied::Lost)
+        );
+        assert_eq!(files.get("mail"), None);
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "To: d".to_owned(),
+        };
+        assert_eq!(
+            files.apply_changes("mail", 4, vec![full]),
+            Some(Applied::Full)
+        );
+        assert_eq!(files.get("mail"), Some("To: d"));
+        assert_eq!(files.apply_changes("other", 1, Vec::new()), None);
     }
-    count
 }
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+/// Expand a configured path: a leading `~` or `~user` to the home directory, and environment
+/// variables written as `$VAR`, `${VAR}`, or `%VAR%` on Windows.
+///
+/// The XDG base directories and `HOME` fall back to the platform's defaults when unset, so
+/// `$XDG_DATA_HOME/contacts` works everywhere.
+pub fn expand_path(path: &Path) -> Result<PathBuf, String> {
+    let Some(path) = path.to_str() else {
+        // can't hold any variables we could expand
+        return Ok(path.to_owned());
+    };
+    expand(path, cfg!(windows), &env_var, &home_dir).map(PathBuf::from)
+}
+
+fn expand(
+    path: &str,
+    percent_vars: bool,
+    var: &dyn Fn(&str) -> Option<String>,
+    home: &dyn Fn(Option<&str>) -> Option<String>,
+) -> Result<String, String> {
+    let path = match path.strip_prefix('~') {
+        Some(rest) => {
+            let end = rest.find(['/', '\\']).unwrap_or(rest.len());
+            let (user, rest) = rest.split_at(end);
+            let user = Some(user).filter(|u| !u.is_empty());
+            let home = home(user).ok_or_else(|| match user {
+                Some(user) => format!("Unknown home directory for user {user:?}"),
+                None => "Unknown home directory".to_owned(),
+            })?;
+            format!("{home}{rest}")
+        }
+        None => path.to_owned(),
+    };
+
+    let mut expanded = String::new();
+    let mut rest = path.as_str();
+    while let Some(start) = rest.find(|c| c == '$' || (percent_vars && c == '%')) {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remaining) = if rest[start..].starts_with('%') {
+            match after.find('%') {
+                Some(end) if end > 0 => (&after[..end], &after[end + 1..]),
+                _ => ("", after),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .ok_or_else(|| format!("Unclosed variable in {path:?}"))?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+        if name.is_empty() {
+            // a lone `$` or `%` is just part of the path
+            expanded.push_str(&rest[start..start + 1]);
+            rest = after;
+            continue;
+        }
+        let value = var(name).ok_or_else(|| format!("Undefined variable {name} in {path:?}"))?;
+        expanded.push_str(&value);
+        rest = remaining;
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn env_var(name: &str) -> Option<String> {
+    if let Some(value) = std::env::var(name).ok().filter(|v| !v.is_empty()) {
+        return Some(value);
+    }
+    let default = match name {
+        "HOME" => dirs::home_dir(),
+        "XDG_DATA_HOME" => dirs::data_dir(),
+        "XDG_CONFIG_HOME" => dirs::config_dir(),
+        "XDG_CACHE_HOME" => dirs::cache_dir(),
+        "XDG_STATE_HOME" => dirs::state_dir(),
+        _ => None,
+    };
+    Some(default?.to_str()?.to_owned())
+}
+
+/// The home directory of the current user, or of another one from the password database.
+fn home_dir(user: Option<&str>) -> Option<String> {
+    let Some(user) = user else {
+        return Some(dirs::home_dir()?.to_str()?.to_owned());
+    };
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let fields = line.split(':').collect::<Vec<_>>();
+        (fields.first() == Some(&user))
+            .then(|| fields.get(5).map(|home| home.to_string()))
+            .flatten()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands() {
+        let var = |name: &str| match name {
+            "HOME" => Some("/home/jane".to_owned()),
+            "XDG_DATA_HOME" => Some("/home/jane/.local/share".to_owned()),
+            "APPDATA" => Some("C:\\Users\\jane\\AppData\\Roaming".to_owned()),
+            _ => None,
+        };
+        let home = |user: Option<&str>| match user {
+            None => Some("/home/jane".to_owned()),
+            Some("bob") => Some("/home/bob".to_owned()),
+            Some(_) => None,
+        };
+        let expand = |path: &str, percent_vars: bool| expand(path, percent_vars, &var, &home);
+
+        assert_eq!(expand("~/contacts", false).unwrap(), "/home/jane/contacts");
+        assert_eq!(expand("~", false).unwrap(), "/home/jane");
+        assert_eq!(
+            expand("~bob/contacts", false).unwrap(),
+            "/home/bob/contacts"
+        );
+        assert!(expand("~nobody/contacts", false).is_err());
+        assert_eq!(
+            expand("$XDG_DATA_HOME/contacts", false).unwrap(),
+            "/home/jane/.local/share/contacts"
+        );
+        assert_eq!(
+            expand("${HOME}/mail/$HOME", false).unwrap(),
+            "/home/jane/mail//home/jane"
+        );
+        assert!(expand("$UNDEFINED/contacts", false).is_err());
+        assert!(expand("${HOME/contacts", false).is_err());
+        assert_eq!(expand("/cost/$/100%", false).unwrap(), "/cost/$/100%");
+        assert_eq!(
+            expand("%APPDATA%\\contacts", true).unwrap(),
+            "C:\\Users\\jane\\AppData\\Roaming\\contacts"
+        );
+        assert_eq!(expand("%APPDATA%", false).unwrap(), "%APPDATA%");
+        assert_eq!(expand("/tmp/100%", true).unwrap(), "/tmp/100%");
+        assert_eq!(expand("/plain/path", false).unwrap(), "/plain/path");
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Digits a number needs to be taken for a phone number, so short numbers aren't.
+const MIN_DIGITS: usize = 6;
+
+/// Digits at the end of numbers that are compared, leaving out country codes and trunk prefixes.
+const KEY_DIGITS: usize = 8;
+
+static PHONE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(tel:)?\+?\(?[0-9][0-9 ()./-]*[0-9]").unwrap());
+
+/// A phone number of a contact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phone {
+    /// The first `TYPE` of the number, e.g. `cell` or `work`.
+    pub kind: Option<String>,
+    /// The number as the contact has it.
+    pub number: String,
+}
+
+/// The digits of a phone number, after a `+` if it has a country code, e.g. `+441234567890` for
+/// `tel:+44 (1234) 567-890`.
+///
+/// Returns `None` for text with too few digits to be a phone number.
+pub fn normalize_phone(number: &str) -> Option<String> {
+    let number = number.trim();
+    let number = number.strip_prefix("tel:").unwrap_or(number);
+    // parameters of tel URIs, like `;ext=123`, aren't part of the number
+    let number = number.split(';').next().unwrap_or_default().trim_start();
+    let digits = number
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    if digits.len() < MIN_DIGITS {
+        return None;
+    }
+    Some(if number.starts_with('+') {
+        format!("+{digits}")
+    } else {
+        digits
+    })
+}
+
+/// The key to index a normalized number by: its last digits, which are the same with or without
+/// a country code.
+pub fn phone_key(normalized: &str) -> &str {
+    let digits = normalized.trim_start_matches('+');
+    &digits[digits.len().saturating_sub(KEY_DIGITS)..]
+}
+
+/// Whether two normalized numbers are the same: equal, or a national number with its trunk `0`
+/// that ends an international one, as `01234 567890` does `+44 1234 567890`.
+pub fn phones_match(a: &str, b: &str) -> bool {
+    let national_in = |international: &str, national: &str| {
+        international.starts_with('+')
+            && national
+                .strip_prefix('0')
+                .is_some_and(|n| n.len() >= MIN_DIGITS && international.ends_with(n))
+    };
+    a == b || national_in(a, b) || national_in(b, a)
+}
+
+/// The phone number at the character of the line, with the characters it spans as a half-open
+/// range.
+pub fn phone_at(line: &str, character: usize) -> Option<(usize, usize, String)> {
+    let char_index = |byte: usize| line[..byte].chars().count();
+    PHONE_REGEX.find_iter(line).find_map(|m| {
+        let (start, end) = (char_index(m.start()), char_index(m.end()));
+        if !(start..=end).contains(&character) {
+            return None;
+        }
+        normalize_phone(m.as_str()).map(|number| (start, end, number))
+    })
+}
+
+/// The `tel:` URI of a number, which can't have spaces.
+pub fn tel_uri(number: &str) -> String {
+    let number = number.trim();
+    let number = number.strip_prefix("tel:").unwrap_or(number);
+    let number = number
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .replace(['(', ')'], "");
+    format!("tel:{number}")
+}
+
+/// The query ending at the character of the line after a `tel:`, with the character the `tel:`
+/// starts at, for completing a contact's number.
+pub fn tel_query_at(line: &str, character: usize) -> Option<(usize, String)> {
+    let before = line.chars().take(character + 1).collect::<String>();
+    let start = before.rfind("tel:")?;
+    let query = &before[start + "tel:".len()..];
+    if query.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((before[..start].chars().count(), query.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        assert_eq!(
+            normalize_phone("tel:+44 (1234) 567-890"),
+            Some("+441234567890".to_owned())
+        );
+        assert_eq!(
+            normalize_phone("01234.567890;ext=5"),
+            Some("01234567890".to_owned())
+        );
+        assert_eq!(normalize_phone("2024"), None);
+        assert!(phones_match("+441234567890", "01234567890"));
+        assert!(phones_match("01234567890", "01234567890"));
+        assert!(!phones_match("+441234567890", "01234567891"));
+        assert_eq!(phone_key("+441234567890"), "34567890");
+        assert_eq!(phone_key("01234567890"), "34567890");
+        assert_eq!(tel_uri("+44 (1234) 567 890"), "tel:+44-1234-567-890");
+    }
+
+    #[test]
+    fn at() {
+        let line = "Call Jane on +44 1234 567890 or on 12345";
+        assert_eq!(
+            phone_at(line, 15),
+            Some((13, 28, "+441234567890".to_owned()))
+        );
+        assert_eq!(phone_at(line, 40), None);
+        assert_eq!(phone_at(line, 2), None);
+        assert_eq!(
+            tel_query_at("Phone: tel:Jan", 13),
+            Some((7, "Jan".to_owned()))
+        );
+        assert_eq!(tel_query_at("Phone: tel:", 10), Some((7, String::new())));
+        assert_eq!(tel_query_at("Phone: Jan", 9), None);
+    }
+}
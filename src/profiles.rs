@@ -0,0 +1,303 @@
+use std::{collections::HashMap, ops::Range, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::headers;
+
+/// The lines of a document that addresses are completed and checked in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Everywhere,
+    /// The headers of a message, up to the first empty line.
+    Headers,
+    /// The trailers of a commit message that name people, such as `Signed-off-by:`, in its last
+    /// paragraph.
+    Trailers,
+    /// The headers of a patch from `git format-patch`, and the trailers of its commit message,
+    /// such as `Cc:`, leaving out the diff.
+    Patch,
+}
+
+impl Scope {
+    /// The lines of the text in the scope, with the names of the trailers that are.
+    ///
+    /// The text is a single message, or for `mbox` any number of them, each in its own scope.
+    pub fn lines<'a>(self, text: &str, mbox: bool, trailer_names: &'a [String]) -> ScopeLines<'a> {
+        let mut lines = ScopeLines {
+            headers: Vec::new(),
+            trailers: Vec::new(),
+            trailer_names,
+        };
+        let all = text.lines().collect::<Vec<_>>();
+        for range in headers::messages(text, mbox) {
+            let message = all[range.clone()].join("\n");
+            let shift = |r: Range<usize>| r.start + range.start..r.end + range.start;
+            match self {
+                Self::Everywhere => lines.headers.push(range),
+                Self::Headers => lines.headers.push(shift(0..headers::header_end(&message))),
+                Self::Trailers => lines.trailers.push(shift(headers::trailer_lines(&message))),
+                Self::Patch => {
+                    let end = headers::header_end(&message);
+                    lines.headers.push(shift(0..end));
+                    lines
+                        .trailers
+                        .push(shift(end..headers::patch_diff_start(&message)));
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// The lines of a text in a [`Scope`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeLines<'a> {
+    /// Lines that are all in the scope.
+    headers: Vec<Range<usize>>,
+    /// Lines that are in the scope if they are one of the named trailers.
+    trailers: Vec<Range<usize>>,
+    trailer_names: &'a [String],
+}
+
+impl ScopeLines<'_> {
+    /// Whether the line, with its index in the text, is in the scope.
+    pub fn includes(&self, index: usize, line: &str) -> bool {
+        self.headers.iter().any(|r| r.contains(&index))
+            || (self.trailers.iter().any(|r| r.contains(&index))
+                && headers::trailer_value_start(line, self.trailer_names).is_some())
+    }
+}
+
+/// How maills behaves in documents of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    pub scope: Scope,
+    /// Whether to report addresses that aren't in the contacts.
+    pub diagnostics: bool,
+    /// Whether to only complete when completion is invoked explicitly, not as the user types.
+    pub completion_on_demand: bool,
+    /// Whether documents are mbox files of several messages, each starting with a `From ` line.
+    pub mbox: bool,
+}
+
+impl Profile {
+    pub const DEFAULT: Self = Self {
+        scope: Scope::Everywhere,
+        diagnostics: true,
+        completion_on_demand: false,
+        mbox: false,
+    };
+
+    /// The built-in profile for the language id, if there is one.
+    pub fn builtin(language_id: &str) -> Option<Self> {
+        match language_id {
+            "default" => Some(Self::DEFAULT),
+            "mail" => Some(Self {
+                scope: Scope::Headers,
+                ..Self::DEFAULT
+            }),
+            "gitcommit" => Some(Self {
+                scope: Scope::Trailers,
+                ..Self::DEFAULT
+            }),
+            "patch" => Some(Self {
+                scope: Scope::Patch,
+                ..Self::DEFAULT
+            }),
+            "markdown" => Some(Self {
+                diagnostics: false,
+                completion_on_demand: true,
+                ..Self::DEFAULT
+            }),
+            _ => None,
+        }
+    }
+
+    fn with(self, options: &ProfileOptions) -> Self {
+        Self {
+            scope: options.scope.unwrap_or(self.scope),
+            diagnostics: options.diagnostics.unwrap_or(self.diagnostics),
+            completion_on_demand: options
+                .completion_on_demand
+                .unwrap_or(self.completion_on_demand),
+            mbox: options.mbox.unwrap_or(self.mbox),
+        }
+    }
+}
+
+/// Configuration of the profile for a language, changing the built-in one, or that of `base`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileOptions {
+    /// The built-in profile to start from, e.g. `mail` for another mail filetype.
+    pub base: Option<String>,
+    pub scope: Option<Scope>,
+    pub diagnostics: Option<bool>,
+    pub completion_on_demand: Option<bool>,
+    pub mbox: Option<bool>,
+}
+
+/// The profiles of each language, built-in and configured.
+#[derive(Debug, Default, Clone)]
+pub struct Profiles {
+    configured: HashMap<String, ProfileOptions>,
+}
+
+impl Profiles {
+    /// Profiles with the configured options by language id, `default` being for any language
+    /// without one.
+    pub fn new(configured: HashMap<String, ProfileOptions>) -> Result<Self, String> {
+        for (language_id, options) in &configured {
+            if let Some(base) = &options.base {
+                if Profile::builtin(base).is_none() {
+                    return Err(format!(
+                        "Unknown base profile {base:?} for {language_id:?}, expected one of default, mail, gitcommit, patch or markdown"
+                    ));
+                }
+            }
+        }
+        Ok(Self { configured })
+    }
+
+    /// The profile for the document at the path of the language, or of an unknown language for
+    /// `None`.
+    ///
+    /// Languages without a profile of their own use that of the file's kind if it has one, e.g.
+    /// `patch` for `.patch` files, which editors often open as `diff`.
+    pub fn get(&self, language_id: Option<&str>, path: Option<&Path>) -> Profile {
+        let language_id = language_id.filter(|l| !l.is_empty());
+        let options = language_id.and_then(|l| self.configured.get(l));
+        let base = options
+            .and_then(|o| o.base.as_deref())
+            .or(language_id)
+            .and_then(Profile::builtin)
+            .or_else(|| {
+                options
+                    .is_none()
+                    .then(|| path.and_then(file_kind))
+                    .flatten()
+                    .and_then(Profile::builtin)
+            });
+        match (base, options) {
+            (Some(base), options) => options.map_or(base, |o| base.with(o)),
+            // other languages use the default profile
+            (None, options) => {
+                let default = self
+                    .configured
+                    .get("default")
+                    .map_or(Profile::DEFAULT, |o| Profile::DEFAULT.with(o));
+                options.map_or(default, |o| default.with(o))
+            }
+        }
+    }
+}
+
+/// The built-in profile for the kind of file at the path.
+fn file_kind(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    // git send-email edits cover letters and annotated patches in files like these
+    if name.starts_with(".gitsendemail.msg") {
+        return Some("patch");
+    }
+    match path.extension()?.to_str()? {
+        "patch" => Some("patch"),
+        "eml" => Some("mail"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles() {
+        let profiles = Profiles::new(HashMap::from([
+            (
+                "default".to_owned(),
+                ProfileOptions {
+                    diagnostics: Some(false),
+                    ..Default::default()
+                },
+            ),
+            (
+                "markdown".to_owned(),
+                ProfileOptions {
+                    completion_on_demand: Some(false),
+                    ..Default::default()
+                },
+            ),
+            (
+                "neomutt".to_owned(),
+                ProfileOptions {
+                    base: Some("mail".to_owned()),
+                    ..Default::default()
+                },
+            ),
+        ]))
+        .unwrap();
+        let get = |language_id, path: &str| profiles.get(language_id, Some(Path::new(path)));
+        assert_eq!(get(Some("mail"), "draft").scope, Scope::Headers);
+        assert_eq!(get(Some("neomutt"), "draft").scope, Scope::Headers);
+        assert_eq!(
+            get(Some("gitcommit"), "COMMIT_EDITMSG").scope,
+            Scope::Trailers
+        );
+        assert_eq!(get(Some("diff"), "0001-fix.patch").scope, Scope::Patch);
+        assert_eq!(get(None, ".gitsendemail.msg.x1").scope, Scope::Patch);
+        assert_eq!(get(Some("text"), "draft.eml").scope, Scope::Headers);
+        assert_eq!(
+            get(Some("markdown"), "notes.md"),
+            Profile {
+                scope: Scope::Everywhere,
+                diagnostics: false,
+                completion_on_demand: false,
+                mbox: false,
+            }
+        );
+        assert!(!get(Some("text"), "notes.txt").diagnostics);
+        assert!(!profiles.get(None, None).diagnostics);
+
+        assert!(Profiles::new(HashMap::from([(
+            "text".to_owned(),
+            ProfileOptions {
+                base: Some("nope".to_owned()),
+                ..Default::default()
+            },
+        )]))
+        .is_err());
+    }
+
+    #[test]
+    fn scopes() {
+        let names = headers::DEFAULT_TRAILERS.map(str::to_owned);
+        let included = |scope: Scope, text: &str| {
+            let lines = scope.lines(text, false, &names);
+            text.lines()
+                .enumerate()
+                .filter(|(i, l)| lines.includes(*i, l))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+        let mail = "To: a@example.com\nCc: b@example.com\n\nbody c@example.com\n";
+        assert_eq!(included(Scope::Headers, mail), vec![0, 1]);
+        assert_eq!(included(Scope::Everywhere, mail), vec![0, 1, 2, 3]);
+        let commit = "fix: a thing\n\nSee c@example.com\n\nFixes: abc\nSigned-off-by: A <a@example.com>\n# a comment\n";
+        assert_eq!(included(Scope::Trailers, commit), vec![5]);
+        let patch = "From 1234 Mon Sep 17 00:00:00 2001\nFrom: A <a@example.com>\nSubject: [PATCH] fix\n\nSee c@example.com\n\nCc: d@example.com\nSigned-off-by: A <a@example.com>\n---\n a.txt | 2 +-\n\ndiff --git a/a.txt b/a.txt\n+Cc: e@example.com\n";
+        assert_eq!(included(Scope::Patch, patch), vec![0, 1, 2, 6, 7]);
+
+        let mbox = "From a@example.com Mon Jan 1 00:00:00 2024\nTo: b@example.com\n\nc@example.com\n\nFrom d@example.com Tue Jan 2 00:00:00 2024\nCc: e@example.com\n\nf@example.com\n";
+        let lines = Scope::Headers.lines(mbox, true, &names);
+        assert_eq!(
+            mbox.lines()
+                .enumerate()
+                .filter(|(i, l)| lines.includes(*i, l))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 5, 6]
+        );
+        assert_eq!(included(Scope::Headers, mbox), vec![0, 1]);
+    }
+}
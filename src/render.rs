@@ -0,0 +1,223 @@
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::Avatars;
+
+static LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\(([^)\s]*)\)").unwrap());
+
+/// A section of a rendered contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    /// The contact's photo, falling back to their avatar if enabled.
+    Photo,
+    Name,
+    Nickname,
+    Email,
+    Telephone,
+    Organization,
+    Title,
+    Role,
+    Address,
+    Birthday,
+    /// The contacts the contact is related to, such as their spouse or assistant.
+    Related,
+    Url,
+    Impp,
+    Categories,
+    /// The `X-` properties allowed by [`RenderOptions::extensions`].
+    Extensions,
+    Note,
+}
+
+impl Section {
+    /// The order sections are rendered in by default.
+    pub const DEFAULT_ORDER: [Section; 16] = [
+        Section::Photo,
+        Section::Name,
+        Section::Nickname,
+        Section::Email,
+        Section::Telephone,
+        Section::Organization,
+        Section::Title,
+        Section::Role,
+        Section::Address,
+        Section::Birthday,
+        Section::Related,
+        Section::Url,
+        Section::Impp,
+        Section::Categories,
+        Section::Extensions,
+        Section::Note,
+    ];
+}
+
+/// Options for rendering contacts, e.g. for hover.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// The sections to render, in order.
+    pub sections: Vec<Section>,
+    /// Avatars to show for contacts without a photo.
+    pub avatars: Option<Arc<Avatars>>,
+    /// Names of the `X-` properties to render, ending in `*` to match a prefix, e.g.
+    /// `X-PHONETIC-*`.
+    pub extensions: Vec<String>,
+    /// Most items to show in each list section, e.g. emails, with a last one counting the rest.
+    pub max_items: Option<usize>,
+    /// Most characters of each note to show.
+    pub note_length: Option<usize>,
+}
+
+impl RenderOptions {
+    /// Whether the extension property should be rendered.
+    pub fn renders_extension(&self, name: &str) -> bool {
+        self.extensions
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => name
+                    .get(..prefix.len())
+                    .is_some_and(|p| p.eq_ignore_ascii_case(prefix)),
+                None => name.eq_ignore_ascii_case(allowed),
+            })
+    }
+
+    /// The items of a list section, up to [`max_items`](Self::max_items) of them.
+    pub fn limit_items(&self, items: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut items = items.into_iter().collect::<Vec<_>>();
+        if let Some(max) = self.max_items.filter(|max| items.len() > *max) {
+            let rest = items.len() - max;
+            items.truncate(max);
+            items.push(format!("… and {rest} more"));
+        }
+        items
+    }
+
+    /// The note, cut to [`note_length`](Self::note_length) characters.
+    pub fn limit_note(&self, note: &str) -> String {
+        match self.note_length {
+            Some(length) if note.chars().count() > length => {
+                let cut = note.chars().take(length).collect::<String>();
+                format!("{}…", cut.trim_end())
+            }
+            _ => note.to_owned(),
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            sections: Section::DEFAULT_ORDER.to_vec(),
+            avatars: None,
+            extensions: Vec::new(),
+            max_items: None,
+            note_length: None,
+        }
+    }
+}
+
+/// A readable label for an extension property, e.g. `Phonetic first name` for
+/// `X-PHONETIC-FIRST-NAME`.
+pub fn extension_label(name: &str) -> String {
+    let name = name
+        .get(2..)
+        .filter(|_| name[..2].eq_ignore_ascii_case("X-"));
+    let words = name.unwrap_or_default().to_lowercase().replace('-', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Render a titled markdown list section, e.g. `Email:` followed by an item per value.
+///
+/// Nothing is rendered when there are no items.
+pub fn list_section(lines: &mut Vec<String>, title: &str, items: impl IntoIterator<Item = String>) {
+    let items = items
+        .into_iter()
+        .map(|i| format!("- {i}"))
+        .collect::<Vec<_>>();
+    if items.is_empty() {
+        return;
+    }
+    lines.push(format!("{title}:"));
+    lines.extend(items);
+    lines.push(String::new());
+}
+
+/// Convert a render to plain text, for clients that can't show markdown.
+///
+/// Only the markdown that renders use is converted: headings, emphasised lines and the headings
+/// naming sources lose their markup, links are written out as `text <url>`, and images are left
+/// out.
+pub fn to_plaintext(markdown: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in markdown.lines() {
+        if line.starts_with("![") && line.ends_with(')') {
+            continue;
+        }
+        let line = if let Some(heading) = line.strip_prefix("# ") {
+            heading.to_owned()
+        } else if let Some(emphasised) = line.strip_prefix('_').and_then(|l| l.strip_suffix('_')) {
+            emphasised.to_owned()
+        } else if let Some((label, rest)) = line.strip_prefix('*').and_then(|l| l.split_once('*')) {
+            format!("{label}{}", rest.replace('`', ""))
+        } else {
+            line.to_owned()
+        };
+        let line = LINK_REGEX.replace_all(&line, "$1 <$2>").into_owned();
+        // images leave blank lines behind
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions() {
+        let options = RenderOptions {
+            extensions: vec!["X-PHONETIC-*".to_owned(), "x-anniversary".to_owned()],
+            ..Default::default()
+        };
+        assert!(options.renders_extension("X-PHONETIC-FIRST-NAME"));
+        assert!(options.renders_extension("X-ANNIVERSARY"));
+        assert!(!options.renders_extension("X-ABLABEL"));
+        assert_eq!(
+            extension_label("X-PHONETIC-FIRST-NAME"),
+            "Phonetic first name"
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let options = RenderOptions {
+            max_items: Some(2),
+            note_length: Some(5),
+            ..Default::default()
+        };
+        let items = ["a", "b", "c", "d"].map(|i| i.to_owned());
+        assert_eq!(options.limit_items(items), ["a", "b", "… and 2 more"]);
+        assert_eq!(options.limit_items(["a".to_owned()]), ["a"]);
+        assert_eq!(options.limit_note("Met at the conference"), "Met a…");
+        assert_eq!(options.limit_note("Hello"), "Hello");
+    }
+
+    #[test]
+    fn plaintext() {
+        let markdown = "*VCards* `/contacts/jane.vcf`\n\n![avatar](file:///a.png)\n\n# Jane Doe\n\n_JD_\n\nEmail:\n- jane@example.com\n\nAddress:\n- home: Cambridge ([map](https://www.openstreetmap.org/search?query=Cambridge))\n";
+        assert_eq!(
+            to_plaintext(markdown),
+            "VCards /contacts/jane.vcf\n\nJane Doe\n\nJD\n\nEmail:\n- jane@example.com\n\nAddress:\n- home: Cambridge (map <https://www.openstreetmap.org/search?query=Cambridge>)"
+        );
+    }
+}
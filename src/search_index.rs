@@ -0,0 +1,124 @@
+use crate::{fold, MatchMode, Matcher};
+
+/// An index of the names and emails of a source's contacts, to find candidates for a query
+/// without matching every contact.
+///
+/// It holds every suffix of the folded fields of each entry in sorted order, so the entries with
+/// a field containing a string are found with a binary search.
+/// Candidates still need checking against the query.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    /// Folded fields and the entry each belongs to.
+    fields: Vec<(String, usize)>,
+    /// Field index and byte offset of each suffix, sorted by the suffix.
+    suffixes: Vec<(u32, u32)>,
+}
+
+impl SearchIndex {
+    /// Build the index for entries, given as their index and the names and emails to search.
+    pub fn new<'a>(entries: impl IntoIterator<Item = (usize, Vec<&'a str>)>) -> Self {
+        let mut s = Self::default();
+        for (entry, fields) in entries {
+            for field in fields {
+                s.push_field(entry, field);
+            }
+        }
+        let fields = &s.fields;
+        s.suffixes
+            .sort_unstable_by(|a, b| suffix(fields, *a).cmp(suffix(fields, *b)));
+        s
+    }
+
+    /// Add an entry to the index.
+    pub fn insert(&mut self, entry: usize, fields: Vec<&str>) {
+        for field in fields {
+            let start = self.suffixes.len();
+            self.push_field(entry, field);
+            for suffix_entry in self.suffixes.split_off(start) {
+                let fields = &self.fields;
+                let position = self
+                    .suffixes
+                    .partition_point(|s| suffix(fields, *s) < suffix(fields, suffix_entry));
+                self.suffixes.insert(position, suffix_entry);
+            }
+        }
+    }
+
+    /// The entries that may match the query, in ascending order, or `None` when the index can't
+    /// narrow them down and all entries need checking.
+    pub fn candidates(&self, matcher: &Matcher) -> Option<Vec<usize>> {
+        if matcher.options().mode == MatchMode::Fuzzy {
+            return None;
+        }
+        // any match contains every token of the query in one of its fields, so look for the most
+        // selective one
+        let token = matcher.tokens().iter().max_by_key(|t| t.len())?;
+        let token = fold(token, true);
+        let start = self
+            .suffixes
+            .partition_point(|s| suffix(&self.fields, *s) < token.as_str());
+        let mut entries = self.suffixes[start..]
+            .iter()
+            .take_while(|s| suffix(&self.fields, **s).starts_with(&token))
+            .map(|(field, _)| self.fields[*field as usize].1)
+            .collect::<Vec<_>>();
+        entries.sort_unstable();
+        entries.dedup();
+        Some(entries)
+    }
+
+    fn push_field(&mut self, entry: usize, field: &str) {
+        let field = fold(field, true);
+        let field_index = self.fields.len() as u32;
+        self.suffixes.extend(
+            field
+                .char_indices()
+                .map(|(offset, _)| (field_index, offset as u32)),
+        );
+        self.fields.push((field, entry));
+    }
+}
+
+fn suffix(fields: &[(String, usize)], (field, offset): (u32, u32)) -> &str {
+    &fields[field as usize].0[offset as usize..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchOptions;
+
+    #[test]
+    fn candidates() {
+        let mut index = SearchIndex::new([
+            (0, vec!["Jane Doe", "jane@example.com"]),
+            (1, vec!["José García", "jose@example.org"]),
+            (2, vec!["John Smith", "js@work.example.com"]),
+        ]);
+        index.insert(3, vec!["Mary Jane", "mj@example.net"]);
+
+        let candidates = |query: &str, mode: MatchMode| {
+            let matcher = Matcher::new(
+                query,
+                MatchOptions {
+                    mode,
+                    strip_diacritics: true,
+                },
+            );
+            index.candidates(&matcher)
+        };
+        assert_eq!(candidates("jane", MatchMode::Substring), Some(vec![0, 3]));
+        assert_eq!(candidates("GARC", MatchMode::Prefix), Some(vec![1]));
+        assert_eq!(
+            candidates("example.com", MatchMode::Substring),
+            Some(vec![0, 2])
+        );
+        assert_eq!(
+            candidates("j smith", MatchMode::WordBoundary),
+            Some(vec![2])
+        );
+        assert_eq!(candidates("nobody", MatchMode::Substring), Some(vec![]));
+        assert_eq!(candidates("jd", MatchMode::Fuzzy), None);
+        assert_eq!(candidates("", MatchMode::Substring), None);
+    }
+}
@@ -0,0 +1,227 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, read_dir, read_to_string, rename, write, File},
+    io::{self, BufRead as _, BufReader},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, NaiveDate};
+use itertools::Itertools as _;
+
+use crate::{fold, headers, AddressWeights, Usage};
+
+/// The date and recipients of a sent message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SentMessage {
+    date: Option<NaiveDate>,
+    recipients: Vec<String>,
+}
+
+impl SentMessage {
+    /// Read the headers of the message, without the body.
+    fn read(path: &Path) -> io::Result<Self> {
+        let mut headers = String::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            // bodies and headers may be in any encoding, only the addresses matter
+            let line = match line {
+                Ok(line) => line,
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => continue,
+                Err(err) => return Err(err),
+            };
+            if line.trim().is_empty() {
+                break;
+            }
+            headers.push_str(&line);
+            headers.push('\n');
+        }
+        Ok(Self::parse(&headers))
+    }
+
+    fn parse(headers: &str) -> Self {
+        let date = headers
+            .lines()
+            .find_map(|l| {
+                l.split_once(':')
+                    .filter(|(name, _)| name.trim().eq_ignore_ascii_case("date"))
+            })
+            .and_then(|(_, date)| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .map(|date| date.date_naive());
+        Self {
+            date,
+            recipients: headers::recipient_addresses(headers)
+                .into_iter()
+                .unique_by(|a| fold(a, false))
+                .map(str::to_owned)
+                .collect(),
+        }
+    }
+
+    fn parse_cached(line: &str) -> Option<(String, Self)> {
+        let mut fields = line.split('\t');
+        let key = fields.next().filter(|k| !k.is_empty())?;
+        let date = fields
+            .next()
+            .and_then(|f| NaiveDate::parse_from_str(f, "%Y-%m-%d").ok());
+        let recipients = fields
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|r| !r.is_empty())
+            .map(str::to_owned)
+            .collect();
+        Some((key.to_owned(), Self { date, recipients }))
+    }
+
+    fn cache_line(&self, key: &str) -> String {
+        format!(
+            "{key}\t{}\t{}",
+            self.date
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            self.recipients.join(",")
+        )
+    }
+}
+
+/// Weights of the addresses mail was sent to, from the headers of the messages in the maildirs.
+///
+/// The recipients of each message are cached at the path by its file name, without the flags
+/// that change as it is read or flagged, so only new messages are read on later scans.
+pub fn sent_weights(maildirs: &[PathBuf], cache: &Path) -> Result<AddressWeights, String> {
+    let mut cached = read_to_string(cache)
+        .map(|text| {
+            text.lines()
+                .filter_map(SentMessage::parse_cached)
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let mut messages = HashMap::new();
+    for maildir in maildirs {
+        for path in message_paths(maildir)? {
+            let Some(key) = message_key(&path) else {
+                continue;
+            };
+            let message = match cached.remove(&key) {
+                Some(message) => message,
+                None => match SentMessage::read(&path) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        tracing::warn!(path = %path.display(), "Failed to read sent message: {err}");
+                        continue;
+                    }
+                },
+            };
+            messages.insert(key, message);
+        }
+    }
+    if let Err(err) = save_cache(cache, &messages) {
+        tracing::warn!(path = %cache.display(), "Failed to cache sent messages: {err}");
+    }
+
+    let mut usage = HashMap::<_, Usage>::new();
+    for message in messages.values() {
+        for recipient in &message.recipients {
+            let usage = usage.entry(fold(recipient, false)).or_default();
+            usage.messages = Some(usage.messages.unwrap_or(0) + 1);
+            usage.last = usage.last.max(message.date);
+        }
+    }
+    let mut weights = AddressWeights::default();
+    for (email, usage) in usage {
+        weights.insert(&email, usage);
+    }
+    Ok(weights)
+}
+
+/// The messages in the `cur` and `new` directories of the maildir.
+fn message_paths(maildir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths = Vec::new();
+    for dir in ["cur", "new"] {
+        let dir = maildir.join(dir);
+        let entries = match read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("Failed to read {}: {err}", dir.display())),
+        };
+        paths.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file()),
+        );
+    }
+    if paths.is_empty() && !maildir.join("cur").is_dir() {
+        return Err(format!("{} is not a maildir", maildir.display()));
+    }
+    Ok(paths)
+}
+
+/// The unique part of a maildir file name, before its flags.
+fn message_key(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    Some(name.split_once(':').map_or(name, |(key, _)| key).to_owned())
+}
+
+fn save_cache(path: &Path, messages: &HashMap<String, SentMessage>) -> io::Result<()> {
+    let lines = messages
+        .iter()
+        .map(|(key, message)| message.cache_line(key))
+        .sorted()
+        .join("\n");
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("partial");
+    write(&partial, format!("{lines}\n"))?;
+    rename(partial, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    #[test]
+    fn scan_maildir() {
+        let dir = TestDir::new();
+        let maildir = dir.join("Sent");
+        std::fs::create_dir_all(maildir.join("cur")).unwrap();
+        std::fs::create_dir_all(maildir.join("new")).unwrap();
+        std::fs::write(
+            maildir.join("cur/1.host:2,S"),
+            "Date: Mon, 1 Jan 2024 10:00:00 +0000\nTo: Jane <jane@example.com>,\n bob@example.com\n\nTo: body@example.com\n",
+        )
+        .unwrap();
+        std::fs::write(
+            maildir.join("new/2.host"),
+            "Date: Fri, 2 Feb 2024 10:00:00 +0000\nCc: jane@example.com\n\n",
+        )
+        .unwrap();
+        let cache = dir.join("cache/sent");
+        let maildirs = vec![maildir.clone()];
+
+        let weights = sent_weights(&maildirs, &cache).unwrap();
+        assert_eq!(
+            weights.get("jane@example.com"),
+            Some(Usage {
+                last: NaiveDate::from_ymd_opt(2024, 2, 2),
+                messages: Some(2),
+            })
+        );
+        assert_eq!(
+            weights.get("bob@example.com"),
+            Some(Usage {
+                last: NaiveDate::from_ymd_opt(2024, 1, 1),
+                messages: Some(1),
+            })
+        );
+        assert_eq!(weights.get("body@example.com"), None);
+
+        // cached messages aren't read again, even once their flags change
+        std::fs::remove_file(maildir.join("cur/1.host:2,S")).unwrap();
+        std::fs::write(maildir.join("cur/1.host:2,RS"), "To: other@example.com\n\n").unwrap();
+        assert_eq!(sent_weights(&maildirs, &cache).unwrap(), weights);
+
+        assert!(sent_weights(&[dir.join("missing")], &cache).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use crate::ContactList;
+
+/// A directory for a test's files, removed when it is dropped, even when the test fails.
+pub(crate) struct TestDir(PathBuf);
+
+impl TestDir {
+    pub(crate) fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("maills-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// A contact list of the content in the directory, labelled by its name.
+    pub(crate) fn contact_list(&self, name: &str, content: &str) -> ContactList {
+        let path = self.join(name);
+        std::fs::write(&path, content).unwrap();
+        ContactList::new(path, true).with_label(name.to_owned())
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, read_to_string, rename, write},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use chrono::NaiveDate;
+use itertools::Itertools as _;
+
+use crate::{fold, Usage};
+
+/// How an address was used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    /// Completed or hovered.
+    Used,
+    /// In the address headers of a saved message.
+    Sent,
+}
+
+/// What is known about the use of an address.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UsageEntry {
+    pub uses: u64,
+    pub last_used: Option<NaiveDate>,
+    pub sent: u64,
+    pub last_sent: Option<NaiveDate>,
+}
+
+/// Days for the weight of a use to halve in [`frecency`].
+const HALF_LIFE_DAYS: f64 = 30.0;
+
+/// How frequently and recently something happened `count` times was, higher is more: the count
+/// halves every month since the last time.
+///
+/// Without a last time, it's taken to be long ago.
+pub(crate) fn frecency(count: u64, last: Option<NaiveDate>, today: NaiveDate) -> u64 {
+    let age = last.map_or(365, |last| (today - last).num_days().max(0)) as f64;
+    (count as f64 * 1000.0 * 0.5f64.powf(age / HALF_LIFE_DAYS)) as u64
+}
+
+impl UsageEntry {
+    /// How frequently and recently the address was used, with sends counting twice as much as
+    /// uses.
+    pub fn frecency(&self, today: NaiveDate) -> u64 {
+        frecency(
+            self.uses + 2 * self.sent,
+            self.last_used.max(self.last_sent),
+            today,
+        )
+    }
+
+    fn parse(line: &str) -> Option<(String, Self)> {
+        let mut fields = line.split('\t');
+        let email = fields.next().filter(|e| !e.is_empty())?;
+        let mut count = || fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+        let (uses, sent) = (count(), count());
+        let mut date = || {
+            fields
+                .next()
+                .and_then(|f| NaiveDate::parse_from_str(f, "%Y-%m-%d").ok())
+        };
+        let (last_used, last_sent) = (date(), date());
+        Some((
+            email.to_owned(),
+            Self {
+                uses,
+                last_used,
+                sent,
+                last_sent,
+            },
+        ))
+    }
+
+    fn line(&self, email: &str) -> String {
+        let date = |d: Option<NaiveDate>| d.map(|d| d.format("%Y-%m-%d").to_string());
+        format!(
+            "{email}\t{}\t{}\t{}\t{}",
+            self.uses,
+            self.sent,
+            date(self.last_used).unwrap_or_default(),
+            date(self.last_sent).unwrap_or_default()
+        )
+    }
+}
+
+/// Addresses that have been completed, hovered and sent to, kept in a file across sessions.
+///
+/// The file has a line for each address of `email<TAB>uses<TAB>sent<TAB>last used<TAB>last sent`.
+pub struct UsageLog {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, UsageEntry>>,
+    /// Whether there are records not yet saved.
+    dirty: AtomicBool,
+    /// Counts records that change what [`usage`](Self::usage) gives, for renders to be cached
+    /// against.
+    generation: AtomicU64,
+}
+
+impl UsageLog {
+    /// Load the log from the path, starting an empty one if there isn't a file there yet.
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let entries = match read_to_string(&path) {
+            Ok(text) => text.lines().filter_map(UsageEntry::parse).collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a use of the address on the day.
+    pub fn record(&self, email: &str, kind: UsageKind, today: NaiveDate) {
+        let mut entries = self.entries();
+        let entry = entries.entry(fold(email, false)).or_default();
+        match kind {
+            UsageKind::Used => {
+                entry.uses += 1;
+                entry.last_used = entry.last_used.max(Some(today));
+            }
+            UsageKind::Sent => {
+                entry.sent += 1;
+                entry.last_sent = entry.last_sent.max(Some(today));
+                self.generation.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn entry(&self, email: &str) -> Option<UsageEntry> {
+        self.entries().get(&fold(email, false)).cloned()
+    }
+
+    /// The mail sent to the address, as a [`ContactSource`](crate::ContactSource) gives it.
+    pub fn usage(&self, email: &str) -> Option<Usage> {
+        self.entry(email).filter(|e| e.sent > 0).map(|e| Usage {
+            last: e.last_sent,
+            messages: Some(e.sent),
+        })
+    }
+
+    /// See [`UsageEntry::frecency`], 0 for unused addresses.
+    pub fn frecency(&self, email: &str, today: NaiveDate) -> u64 {
+        self.entries()
+            .get(&fold(email, false))
+            .map_or(0, |e| e.frecency(today))
+    }
+
+    /// The addresses with the highest frecency, highest first.
+    pub fn most_used(&self, count: usize, today: NaiveDate) -> Vec<(String, UsageEntry)> {
+        let mut entries = self
+            .entries()
+            .iter()
+            .map(|(email, entry)| (email.clone(), entry.clone()))
+            .collect::<Vec<_>>();
+        entries.sort_by_cached_key(|(email, entry)| {
+            (std::cmp::Reverse(entry.frecency(today)), email.clone())
+        });
+        entries.truncate(count);
+        entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Write the log to its file if there are new records.
+    pub fn save(&self) -> io::Result<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let mut text = self
+            .entries()
+            .iter()
+            .map(|(email, entry)| entry.line(email))
+            .sorted()
+            .join("\n");
+        text.push('\n');
+        if let Some(parent) = self.path.parent() {
+            create_dir_all(parent)?;
+        }
+        // write alongside and move it into place so a failed write doesn't lose the log
+        let partial = self.path.with_extension("partial");
+        let result = write(&partial, text).and_then(|()| rename(&partial, &self.path));
+        if result.is_err() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, UsageEntry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn record_and_reload() {
+        let dir = TestDir::new();
+        let path = dir.join("usage.db");
+        let log = UsageLog::load(path.clone()).unwrap();
+        assert!(log.is_empty());
+        log.record("Jane@Example.com", UsageKind::Used, day(1));
+        log.record("jane@example.com", UsageKind::Sent, day(3));
+        log.record("bob@example.com", UsageKind::Used, day(2));
+        assert_eq!(log.generation(), 1);
+        assert_eq!(
+            log.usage("jane@example.com"),
+            Some(Usage {
+                last: Some(day(3)),
+                messages: Some(1),
+            })
+        );
+        // only sends are shown as usage
+        assert_eq!(log.usage("bob@example.com"), None);
+        log.save().unwrap();
+
+        let log = UsageLog::load(path).unwrap();
+        assert_eq!(
+            log.entry("jane@example.com"),
+            Some(UsageEntry {
+                uses: 1,
+                last_used: Some(day(1)),
+                sent: 1,
+                last_sent: Some(day(3)),
+            })
+        );
+        assert_eq!(
+            log.most_used(5, day(3))
+                .into_iter()
+                .map(|(email, _)| email)
+                .collect::<Vec<_>>(),
+            vec!["jane@example.com", "bob@example.com"]
+        );
+    }
+
+    #[test]
+    fn frecency_decays() {
+        let entry = UsageEntry {
+            uses: 2,
+            last_used: Some(day(1)),
+            sent: 1,
+            last_sent: None,
+        };
+        assert_eq!(entry.frecency(day(1)), 4000);
+        assert_eq!(entry.frecency(day(31)), 2000);
+        assert_eq!(UsageEntry::default().frecency(day(1)), 0);
+    }
+}
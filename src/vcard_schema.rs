@@ -0,0 +1,536 @@
+//! Knowledge of the vcard format, for helping to edit `.vcf` files.
+
+use std::path::Path;
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Diagnostic, DiagnosticSeverity,
+    Position, Range, TextEdit,
+};
+
+use crate::vcard_text::{ContentLine, VcardText};
+
+/// Maximum length of a physical line in octets, excluding the line break.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Values of the `TYPE` parameter that any property taking it accepts.
+const GENERAL_TYPES: &[&str] = &["work", "home"];
+const TEL_TYPES: &[&str] = &[
+    "work",
+    "home",
+    "text",
+    "voice",
+    "fax",
+    "cell",
+    "video",
+    "pager",
+    "textphone",
+];
+const RELATED_TYPES: &[&str] = &[
+    "contact",
+    "acquaintance",
+    "friend",
+    "met",
+    "co-worker",
+    "colleague",
+    "co-resident",
+    "neighbor",
+    "child",
+    "parent",
+    "sibling",
+    "spouse",
+    "kin",
+    "muse",
+    "crush",
+    "date",
+    "sweetheart",
+    "me",
+    "agent",
+    "emergency",
+];
+
+/// A property defined by RFC 6350.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Values of the `TYPE` parameter, empty if the property doesn't take it.
+    pub types: &'static [&'static str],
+}
+
+const fn property(
+    name: &'static str,
+    description: &'static str,
+    types: &'static [&'static str],
+) -> PropertyInfo {
+    PropertyInfo {
+        name,
+        description,
+        types,
+    }
+}
+
+/// The properties of a vcard.
+pub const PROPERTIES: &[PropertyInfo] = &[
+    property("BEGIN", "Start of a card", &[]),
+    property("END", "End of a card", &[]),
+    property("VERSION", "Version of the vcard format", &[]),
+    property(
+        "SOURCE",
+        "Where the latest version of the card can be found",
+        &[],
+    ),
+    property(
+        "KIND",
+        "Kind of object the card is for, e.g. `individual` or `group`",
+        &[],
+    ),
+    property("XML", "Extended XML-encoded vcard data", &[]),
+    property("FN", "Formatted name", GENERAL_TYPES),
+    property(
+        "N",
+        "Structured name: family; given; additional; prefixes; suffixes",
+        &[],
+    ),
+    property("NICKNAME", "Nickname", GENERAL_TYPES),
+    property("PHOTO", "Image or photograph", GENERAL_TYPES),
+    property("BDAY", "Birthday", &[]),
+    property("ANNIVERSARY", "Date of marriage or equivalent", &[]),
+    property("GENDER", "Sex and gender identity", &[]),
+    property(
+        "ADR",
+        "Delivery address: PO box; extended; street; locality; region; postal code; country",
+        GENERAL_TYPES,
+    ),
+    property("TEL", "Telephone number", TEL_TYPES),
+    property("EMAIL", "Email address", GENERAL_TYPES),
+    property(
+        "IMPP",
+        "Instant messaging and presence protocol URI",
+        GENERAL_TYPES,
+    ),
+    property(
+        "LANG",
+        "Language that may be used to contact the person",
+        GENERAL_TYPES,
+    ),
+    property("TZ", "Time zone", GENERAL_TYPES),
+    property("GEO", "Global position, as a `geo:` URI", GENERAL_TYPES),
+    property("TITLE", "Position or job", GENERAL_TYPES),
+    property(
+        "ROLE",
+        "Function or part played in an organization",
+        GENERAL_TYPES,
+    ),
+    property("LOGO", "Logo of an organization", GENERAL_TYPES),
+    property("ORG", "Organization name and units", GENERAL_TYPES),
+    property("MEMBER", "Member of the group the card represents", &[]),
+    property("RELATED", "Relationship with another entity", RELATED_TYPES),
+    property("CATEGORIES", "Tags for the card", GENERAL_TYPES),
+    property(
+        "NOTE",
+        "Supplemental information or a comment",
+        GENERAL_TYPES,
+    ),
+    property("PRODID", "Product that created the card", &[]),
+    property("REV", "When the card was last revised", &[]),
+    property(
+        "SOUND",
+        "Sound, e.g. the pronunciation of the name",
+        GENERAL_TYPES,
+    ),
+    property("UID", "Globally unique identifier of the card", &[]),
+    property(
+        "CLIENTPIDMAP",
+        "Maps PID parameters to a global identifier",
+        &[],
+    ),
+    property("URL", "Website", GENERAL_TYPES),
+    property(
+        "KEY",
+        "Public key or authentication certificate",
+        GENERAL_TYPES,
+    ),
+    property("FBURL", "Busy time URL", GENERAL_TYPES),
+    property(
+        "CALADRURI",
+        "Calendar address to send scheduling requests to",
+        GENERAL_TYPES,
+    ),
+    property("CALURI", "Calendar URI", GENERAL_TYPES),
+];
+
+/// Look up a property by name, ignoring case.
+pub fn property_info(name: &str) -> Option<&'static PropertyInfo> {
+    PROPERTIES
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// Whether the file at the path is a vcard file to help edit.
+pub fn is_vcard_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("vcf") || e.eq_ignore_ascii_case("vcard"))
+}
+
+/// Completions for the vcard line at the cursor: property names at the start of the line, and
+/// values of the `TYPE` parameter.
+pub fn complete(line: &str, line_number: u32, character: usize) -> Vec<CompletionItem> {
+    let before = line.chars().take(character).collect::<String>();
+    if before.starts_with([' ', '\t']) || before.contains(':') {
+        // folded continuation or value
+        return Vec::new();
+    }
+    let edit = |start: usize, new_text: String| {
+        Some(CompletionTextEdit::Edit(TextEdit {
+            range: Range::new(
+                Position::new(line_number, start as u32),
+                Position::new(line_number, character as u32),
+            ),
+            new_text,
+        }))
+    };
+
+    let mut parts = before.split(';');
+    let name = parts.next().unwrap_or_default();
+    let Some(parameter) = parts.next_back() else {
+        // still typing the name, after any group
+        let start = name.rfind('.').map_or(0, |i| name[..=i].chars().count());
+        let prefix = name.chars().skip(start).collect::<String>();
+        return PROPERTIES
+            .iter()
+            .filter(|p| p.name.starts_with(&prefix.to_uppercase()))
+            .flat_map(|p| {
+                std::iter::once(p.name.to_owned())
+                    .chain(p.types.iter().map(|t| format!("{};TYPE={t}", p.name)))
+                    .map(|label| CompletionItem {
+                        label: label.clone(),
+                        kind: Some(CompletionItemKind::PROPERTY),
+                        detail: Some(p.description.to_owned()),
+                        text_edit: edit(start, label),
+                        ..Default::default()
+                    })
+            })
+            .collect();
+    };
+
+    let Some(value) = parameter
+        .get(..5)
+        .filter(|p| p.eq_ignore_ascii_case("TYPE="))
+        .map(|_| &parameter[5..])
+    else {
+        return Vec::new();
+    };
+    let Some(info) = property_info(name.rsplit('.').next().unwrap_or_default()) else {
+        return Vec::new();
+    };
+    let typed = value.rsplit(',').next().unwrap_or_default();
+    let start = before.chars().count() - typed.chars().count();
+    info.types
+        .iter()
+        .filter(|t| t.starts_with(&typed.to_lowercase()))
+        .map(|t| CompletionItem {
+            label: (*t).to_owned(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            text_edit: edit(start, (*t).to_owned()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The `UID` a `RELATED` or `MEMBER` line refers to its contact by, e.g.
+/// `urn:uuid:4fbe8971-0bc3-424c-9c26-36c3e1eff6b1`, rather than by email or as text.
+pub fn uid_reference(line: &str) -> Option<String> {
+    let (head, value) = line.split_once(':')?;
+    let name = head.split(';').next().unwrap_or_default();
+    let name = name.rsplit('.').next().unwrap_or_default();
+    let is_text = head
+        .split(';')
+        .any(|p| p.eq_ignore_ascii_case("VALUE=text"));
+    let value = value.trim();
+    (["RELATED", "MEMBER"]
+        .iter()
+        .any(|n| name.eq_ignore_ascii_case(n))
+        && !is_text
+        && !value.is_empty()
+        && !value.starts_with("mailto:"))
+    .then(|| value.to_owned())
+}
+
+/// Format a vcard file: order properties as in RFC 6350 (keeping grouped properties together),
+/// upper-case property and parameter names, lower-case `TYPE` values, fold long lines and use
+/// CRLF line endings.
+pub fn format(text: &str) -> String {
+    let mut text = VcardText::parse(text);
+    text.set_line_ending("\r\n");
+    text.map_cards(|properties| {
+        let rank = |p: &ContentLine| {
+            PROPERTIES
+                .iter()
+                .position(|info| info.name == p.name())
+                .unwrap_or(PROPERTIES.len())
+        };
+        // grouped properties are ranked by the first of the group, so labels stay with their values
+        let group_rank = |p: &ContentLine| match p.group() {
+            Some(group) => properties
+                .iter()
+                .filter(|o| o.group().as_ref() == Some(&group))
+                .map(rank)
+                .min()
+                .unwrap_or_default(),
+            None => rank(p),
+        };
+        let mut ranked = properties
+            .iter()
+            .map(|p| (group_rank(p), p))
+            .collect::<Vec<_>>();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        ranked
+            .into_iter()
+            .map(|(_, p)| ContentLine::new(&format_head(p), &p.value()))
+            .collect()
+    });
+    text.to_string()
+}
+
+/// The head of the property with consistent casing, e.g. `item1.EMAIL;TYPE=work`.
+fn format_head(property: &ContentLine) -> String {
+    let mut head = match property.group() {
+        Some(group) => format!("{group}.{}", property.name()),
+        None => property.name(),
+    };
+    for parameter in property.parameters() {
+        head.push(';');
+        match parameter.split_once('=') {
+            Some((name, value)) if name.eq_ignore_ascii_case("TYPE") => {
+                head.push_str(&format!("TYPE={}", value.to_lowercase()))
+            }
+            Some((name, value)) => head.push_str(&format!("{}={value}", name.to_uppercase())),
+            None => head.push_str(&parameter.to_uppercase()),
+        }
+    }
+    head
+}
+
+/// Check the text of a vcard file for problems that sync tools and other clients may choke on.
+pub fn validate(text: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let diagnostic = |line: usize, length: usize, severity, message: String| Diagnostic {
+        range: Range::new(
+            Position::new(line as u32, 0),
+            Position::new(line as u32, length as u32),
+        ),
+        severity: Some(severity),
+        message,
+        ..Default::default()
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.len() > MAX_LINE_OCTETS {
+            diagnostics.push(diagnostic(
+                i,
+                line.chars().count(),
+                DiagnosticSeverity::WARNING,
+                format!("Line is longer than {MAX_LINE_OCTETS} octets and should be folded"),
+            ));
+        }
+    }
+
+    let text = VcardText::parse(text);
+    for card in 0..text.card_count() {
+        let properties = text.properties_with_line_numbers(card);
+        let begin = properties.first().map_or(0, |(line, _)| line - 1);
+        for required in ["VERSION", "FN"] {
+            if !properties.iter().any(|(_, p)| p.name() == required) {
+                diagnostics.push(diagnostic(
+                    begin,
+                    "BEGIN:VCARD".len(),
+                    DiagnosticSeverity::ERROR,
+                    format!("Card is missing {required}"),
+                ));
+            }
+        }
+        for (line, property) in properties {
+            let length = property.physical_lines()[0].chars().count();
+            if let Some((severity, message)) = check_property(property) {
+                diagnostics.push(diagnostic(line, length, severity, message));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Check a single property of a card.
+fn check_property(property: &ContentLine) -> Option<(DiagnosticSeverity, String)> {
+    let name = property.name();
+    if !property.unfolded().contains(':') {
+        return Some((
+            DiagnosticSeverity::ERROR,
+            format!("{name} is missing `:` before its value"),
+        ));
+    }
+    if property_info(&name).is_none() && !name.starts_with("X-") {
+        return Some((
+            DiagnosticSeverity::WARNING,
+            format!("Unknown property {name}, extensions should start with `X-`"),
+        ));
+    }
+    let value = property.value();
+    let is_text = property
+        .parameters()
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case("VALUE=text"));
+    match name.as_str() {
+        "BDAY" | "ANNIVERSARY" if !is_text && !is_date_and_or_time(&value) => Some((
+            DiagnosticSeverity::ERROR,
+            format!("{name} is not a date, e.g. 19900131, --0131 or 1990-01-31"),
+        )),
+        "UID" if value.trim().is_empty() => {
+            Some((DiagnosticSeverity::ERROR, "UID is empty".to_owned()))
+        }
+        "UID" if value.contains(char::is_whitespace) => Some((
+            DiagnosticSeverity::WARNING,
+            "UID contains whitespace".to_owned(),
+        )),
+        "UID" => match value.get(..9) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("urn:uuid:") => {
+                uuid::Uuid::parse_str(&value[9..]).is_err().then(|| {
+                    (
+                        DiagnosticSeverity::WARNING,
+                        "UID is not a valid urn:uuid".to_owned(),
+                    )
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the value is a date, time or date-time as in RFC 6350, also allowing the extended
+/// format with `-` and `:` that vcard 3 uses.
+fn is_date_and_or_time(value: &str) -> bool {
+    let re = regex::Regex::new(
+        r"^(\d{4}(-?\d{2}(-?\d{2})?)?|--\d{2}(-?\d{2})?|---\d{2})?(T\d{2}(:?\d{2}(:?\d{2})?)?(Z|[+-]\d{2}(:?\d{2})?)?)?$",
+    )
+    .unwrap();
+    !value.is_empty() && re.is_match(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(line: &str) -> Vec<String> {
+        complete(line, 0, line.chars().count())
+            .into_iter()
+            .map(|i| i.label)
+            .collect()
+    }
+
+    #[test]
+    fn completions() {
+        assert_eq!(
+            labels("EMA"),
+            vec!["EMAIL", "EMAIL;TYPE=work", "EMAIL;TYPE=home"]
+        );
+        assert_eq!(labels("item1.ema")[0], "EMAIL");
+        assert_eq!(labels("TEL;TYPE=work,ce"), vec!["cell"]);
+        assert_eq!(labels("RELATED;type=sp"), vec!["spouse"]);
+        assert!(labels("EMAIL:jane").is_empty());
+        assert!(labels(" EMA").is_empty());
+        assert!(labels("EMAIL;PREF=").is_empty());
+    }
+
+    #[test]
+    fn uid_references() {
+        assert_eq!(
+            uid_reference("RELATED;TYPE=spouse:urn:uuid:1234"),
+            Some("urn:uuid:1234".to_owned())
+        );
+        assert_eq!(
+            uid_reference("item1.MEMBER:urn:uuid:5678\r"),
+            Some("urn:uuid:5678".to_owned())
+        );
+        assert_eq!(uid_reference("MEMBER:mailto:jane@example.com"), None);
+        assert_eq!(uid_reference("RELATED;VALUE=text:Jane's friend"), None);
+        assert_eq!(uid_reference("UID:urn:uuid:1234"), None);
+    }
+
+    #[test]
+    fn formatting() {
+        let card = "BEGIN:VCARD
+email;type=WORK;pref=1:jane@example.com
+item1.URL:https://example.com
+FN:Jane Doe
+item1.X-ABLabel:blog
+VERSION:4.0
+NOTE:a note that is far too long to fit on a single line without being folded
+END:VCARD
+";
+        let expected = "BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Jane Doe\r
+EMAIL;TYPE=work;PREF=1:jane@example.com\r
+NOTE:a note that is far too long to fit on a single line without being fold\r
+ ed\r
+item1.URL:https://example.com\r
+item1.X-ABLABEL:blog\r
+END:VCARD\r
+";
+        assert_eq!(format(card), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn validation() {
+        let card = "BEGIN:VCARD\r
+VERSION:4.0\r
+EMAIL:jane@example.com\r
+BDAY:31/01/1990\r
+FOO:bar\r
+X-FOO:bar\r
+UID:urn:uuid:not-a-uuid\r
+NOTE:a note that is far too long to fit on a single line without being folded\r
+END:VCARD\r
+";
+        let problems = validate(card)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    7,
+                    "Line is longer than 75 octets and should be folded".to_owned()
+                ),
+                (0, "Card is missing FN".to_owned()),
+                (
+                    3,
+                    "BDAY is not a date, e.g. 19900131, --0131 or 1990-01-31".to_owned()
+                ),
+                (
+                    4,
+                    "Unknown property FOO, extensions should start with `X-`".to_owned()
+                ),
+                (6, "UID is not a valid urn:uuid".to_owned()),
+            ]
+        );
+
+        for date in [
+            "19900131",
+            "1990-01-31",
+            "--0131",
+            "---31",
+            "1990",
+            "T102200Z",
+        ] {
+            assert!(is_date_and_or_time(date), "{date}");
+        }
+        for date in ["", "31/01/1990", "1990-1-31"] {
+            assert!(!is_date_and_or_time(date), "{date}");
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! Line-wise editing of vcard files.
+//!
+//! Rather than re-serializing parsed cards, which drops anything the parser doesn't model (`X-`
+//! properties, groups, unknown parameters, formatting), edits patch the original text of the file:
+//! content lines that aren't edited are written back exactly as they were read.
+
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+
+/// Maximum length of a content line in octets, excluding the line break.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A content line of a vcard, possibly folded over several physical lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentLine {
+    physical: Vec<String>,
+}
+
+impl ContentLine {
+    /// Create a content line from its name (with any group and parameters) and value, folding it
+    /// if it is too long.
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            physical: fold_line(&format!("{name}:{value}")),
+        }
+    }
+
+    /// The line with any folding removed.
+    pub fn unfolded(&self) -> String {
+        let mut line = self.physical[0].clone();
+        for continuation in &self.physical[1..] {
+            line.push_str(&continuation[1..]);
+        }
+        line
+    }
+
+    /// The raw physical lines making up this content line.
+    pub fn physical_lines(&self) -> &[String] {
+        &self.physical
+    }
+
+    /// The name and parameters of the line, e.g. `item1.EMAIL;TYPE=work`.
+    fn head(&self) -> String {
+        let unfolded = self.unfolded();
+        let mut in_quotes = false;
+        for (i, c) in unfolded.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => return unfolded[..i].to_owned(),
+                _ => {}
+            }
+        }
+        unfolded
+    }
+
+    /// The group of the property, e.g. `item1` for `item1.EMAIL`.
+    pub fn group(&self) -> Option<String> {
+        let head = self.head();
+        let name = head.split(';').next().unwrap_or_default();
+        name.rsplit_once('.').map(|(group, _)| group.to_owned())
+    }
+
+    /// The property name, upper-cased and without any group, e.g. `EMAIL`.
+    pub fn name(&self) -> String {
+        let head = self.head();
+        let name = head.split(';').next().unwrap_or_default();
+        let name = name.rsplit('.').next().unwrap_or_default();
+        name.to_uppercase()
+    }
+
+    /// The parameters of the line, e.g. `TYPE=work` for `EMAIL;TYPE=work:...`.
+    pub fn parameters(&self) -> Vec<String> {
+        self.head()
+            .split(';')
+            .skip(1)
+            .map(|p| p.to_owned())
+            .collect()
+    }
+
+    /// Where the value starts in the physical lines: the index of the physical line, and the
+    /// start and end character of the value within it.
+    ///
+    /// The end is cut short at the end of the physical line if the value is folded.
+    pub fn value_position(&self) -> (usize, usize, usize) {
+        let mut offset = self.head().chars().count() + 1;
+        for (i, physical) in self.physical.iter().enumerate() {
+            // continuation lines start with the folding whitespace
+            let skip = usize::from(i > 0);
+            let len = physical.chars().count() - skip;
+            if offset < len || i == self.physical.len() - 1 {
+                let start = (offset + skip).min(len + skip);
+                let value_len = self.value().chars().count();
+                return (i, start, (start + value_len).min(len + skip));
+            }
+            offset -= len;
+        }
+        (0, 0, 0)
+    }
+
+    /// The (still escaped) value of the line.
+    pub fn value(&self) -> String {
+        let unfolded = self.unfolded();
+        unfolded[self.head().len()..]
+            .strip_prefix(':')
+            .unwrap_or_default()
+            .to_owned()
+    }
+}
+
+/// The text of a vcard file, split into content lines for editing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcardText {
+    lines: Vec<ContentLine>,
+    line_ending: &'static str,
+}
+
+impl VcardText {
+    pub fn parse(text: &str) -> Self {
+        let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut lines: Vec<ContentLine> = Vec::new();
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+            let is_continuation = line.starts_with([' ', '\t']);
+            match lines.last_mut() {
+                Some(last) if is_continuation => last.physical.push(line.to_owned()),
+                _ => lines.push(ContentLine {
+                    physical: vec![line.to_owned()],
+                }),
+            }
+        }
+        Self { lines, line_ending }
+    }
+
+    /// The ranges of content line indices of each card, from `BEGIN:VCARD` to `END:VCARD`
+    /// inclusive.
+    fn card_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut begin = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            let unfolded = line.unfolded();
+            if unfolded.eq_ignore_ascii_case("BEGIN:VCARD") {
+                begin = Some(i);
+            } else if unfolded.eq_ignore_ascii_case("END:VCARD") {
+                if let Some(b) = begin.take() {
+                    ranges.push((b, i));
+                }
+            }
+        }
+        ranges
+    }
+
+    /// The number of cards in the file.
+    pub fn card_count(&self) -> usize {
+        self.card_ranges().len()
+    }
+
+    /// The content lines of a card, excluding its `BEGIN` and `END` lines.
+    pub fn properties(&self, card: usize) -> &[ContentLine] {
+        match self.card_ranges().get(card) {
+            Some(&(begin, end)) => &self.lines[begin + 1..end],
+            None => &[],
+        }
+    }
+
+    /// The first physical line number of each content line of a card, along with the line.
+    pub fn properties_with_line_numbers(&self, card: usize) -> Vec<(usize, &ContentLine)> {
+        let Some(&(begin, end)) = self.card_ranges().get(card) else {
+            return Vec::new();
+        };
+        let mut line_number = self.lines[..=begin]
+            .iter()
+            .map(|l| l.physical.len())
+            .sum::<usize>();
+        let mut properties = Vec::new();
+        for line in &self.lines[begin + 1..end] {
+            properties.push((line_number, line));
+            line_number += line.physical.len();
+        }
+        properties
+    }
+
+    /// The card containing the given physical line, including its `BEGIN` and `END` lines.
+    pub fn card_at_line(&self, line: usize) -> Option<usize> {
+        let starts = self
+            .lines
+            .iter()
+            .scan(0, |start, l| {
+                let this = *start;
+                *start += l.physical.len();
+                Some(this)
+            })
+            .collect::<Vec<_>>();
+        self.card_ranges().iter().position(|&(begin, end)| {
+            starts[begin] <= line && line < starts[end] + self.lines[end].physical.len()
+        })
+    }
+
+    /// The text of a single card, from `BEGIN` to `END`.
+    pub fn card_text(&self, card: usize) -> String {
+        let Some(&(begin, end)) = self.card_ranges().get(card) else {
+            return String::new();
+        };
+        Self {
+            lines: self.lines[begin..=end].to_vec(),
+            line_ending: self.line_ending,
+        }
+        .to_string()
+    }
+
+    /// Find the first card whose properties satisfy the predicate.
+    pub fn find_card(&self, predicate: impl Fn(&[ContentLine]) -> bool) -> Option<usize> {
+        (0..self.card_count()).find(|&card| predicate(self.properties(card)))
+    }
+
+    /// Replace the first property with the given name in the card, or add it if there isn't one.
+    pub fn set_property(&mut self, card: usize, line: ContentLine) {
+        let Some(&(begin, end)) = self.card_ranges().get(card) else {
+            return;
+        };
+        let name = line.name();
+        match (begin + 1..end).find(|&i| self.lines[i].name() == name) {
+            Some(i) => self.lines[i] = line,
+            None => self.lines.insert(end, line),
+        }
+    }
+
+    /// Add a property to the end of the card.
+    pub fn add_property(&mut self, card: usize, line: ContentLine) {
+        if let Some(&(_, end)) = self.card_ranges().get(card) {
+            self.lines.insert(end, line);
+        }
+    }
+
+    /// Remove all properties with the given name from the card.
+    pub fn remove_property(&mut self, card: usize, name: &str) {
+        let Some(&(begin, end)) = self.card_ranges().get(card) else {
+            return;
+        };
+        let name = name.to_uppercase();
+        let mut i = begin + 1;
+        let mut end = end;
+        while i < end {
+            if self.lines[i].name() == name {
+                self.lines.remove(i);
+                end -= 1;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// Remove a whole card, from `BEGIN` to `END`.
+    pub fn remove_card(&mut self, card: usize) {
+        if let Some(&(begin, end)) = self.card_ranges().get(card) {
+            self.lines.drain(begin..=end);
+        }
+    }
+
+    /// Mark the card as modified, so that sync tools pick up the change: refresh its `REV` and
+    /// give it a `UID` if it doesn't have one, keeping any existing one.
+    pub fn mark_modified(&mut self, card: usize, now: DateTime<Utc>) {
+        self.set_property(
+            card,
+            ContentLine::new("REV", &now.format("%Y%m%dT%H%M%SZ").to_string()),
+        );
+        if !self.properties(card).iter().any(|p| p.name() == "UID") {
+            let uid = format!("urn:uuid:{}", uuid::Uuid::new_v4());
+            self.add_property(card, ContentLine::new("UID", &uid));
+        }
+    }
+
+    /// Replace the properties of each card, e.g. to reorder or rewrite them.
+    pub fn map_cards(&mut self, mut f: impl FnMut(Vec<ContentLine>) -> Vec<ContentLine>) {
+        // from the back so that earlier ranges stay valid
+        for (begin, end) in self.card_ranges().into_iter().rev() {
+            let properties = self.lines.drain(begin + 1..end).collect();
+            self.lines.splice(begin + 1..begin + 1, f(properties));
+        }
+    }
+
+    /// Set the line ending the file is written with.
+    pub fn set_line_ending(&mut self, line_ending: &'static str) {
+        self.line_ending = line_ending;
+    }
+
+    /// Append the cards in the given text to the end of the file.
+    pub fn append(&mut self, text: &str) {
+        self.lines.extend(Self::parse(text).lines);
+    }
+}
+
+impl Display for VcardText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            for physical in &line.physical {
+                write!(f, "{physical}{}", self.line_ending)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escape a text value, e.g. `,` to `\,`.
+pub fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace('\n', "\\n")
+}
+
+/// Unescape a text value, e.g. `\,` to `,`.
+pub fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Fold a line into physical lines of at most 75 octets, without splitting characters.
+pub fn fold_line(line: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for c in line.chars() {
+        if current.len() + c.len_utf8() > MAX_LINE_OCTETS {
+            lines.push(std::mem::take(&mut current));
+            current.push(' ');
+        }
+        current.push(c);
+    }
+    lines.push(current);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = "BEGIN:VCARD\r
+VERSION:4.0\r
+FN:Jane Doe\r
+item1.EMAIL;TYPE=work:jane@work.com\r
+X-CUSTOM;X-PARAM=1:keep me\r
+NOTE:a long note that has been folded over multiple lines by some other\r
+  tool\r
+END:VCARD\r
+";
+
+    #[test]
+    fn round_trip() {
+        let text = VcardText::parse(CARD);
+        assert_eq!(text.to_string(), CARD);
+    }
+
+    #[test]
+    fn properties() {
+        let text = VcardText::parse(CARD);
+        let props = text.properties(0);
+        assert_eq!(props.len(), 5);
+        assert_eq!(props[2].name(), "EMAIL");
+        assert_eq!(props[2].parameters(), vec!["TYPE=work".to_owned()]);
+        assert_eq!(props[2].value(), "jane@work.com");
+        assert_eq!(
+            props[4].value(),
+            "a long note that has been folded over multiple lines by some other tool"
+        );
+        let numbers = text
+            .properties_with_line_numbers(0)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+        assert_eq!(props[2].value_position(), (0, 22, 35));
+        assert_eq!(props[4].value_position(), (0, 5, 71));
+    }
+
+    #[test]
+    fn cards() {
+        let file = format!("{CARD}{CARD}");
+        let text = VcardText::parse(&file);
+        assert_eq!(text.card_at_line(0), Some(0));
+        assert_eq!(text.card_at_line(7), Some(0));
+        assert_eq!(text.card_at_line(8), Some(1));
+        assert_eq!(text.card_at_line(16), None);
+        assert_eq!(text.card_text(1), CARD);
+
+        let mut text = text;
+        text.remove_card(0);
+        assert_eq!(text.to_string(), CARD);
+    }
+
+    #[test]
+    fn edit_preserves_other_lines() {
+        let mut text = VcardText::parse(CARD);
+        text.set_property(0, ContentLine::new("FN", "Jane Smith"));
+        text.add_property(0, ContentLine::new("EMAIL", "jane@home.com"));
+        let expected = CARD
+            .replace("FN:Jane Doe", "FN:Jane Smith")
+            .replace("END:VCARD", "EMAIL:jane@home.com\r\nEND:VCARD");
+        assert_eq!(text.to_string(), expected);
+    }
+
+    #[test]
+    fn mark_modified() {
+        let now = DateTime::parse_from_rfc3339("2024-11-02T10:20:30Z")
+            .unwrap()
+            .to_utc();
+        let mut text = VcardText::parse(CARD);
+        text.mark_modified(0, now);
+        let props = text.properties(0);
+        assert_eq!(props[5].unfolded(), "REV:20241102T102030Z");
+        assert_eq!(props[6].name(), "UID");
+
+        let uid = props[6].clone();
+        text.mark_modified(0, now);
+        let props = text.properties(0);
+        assert_eq!(props.len(), 7);
+        assert_eq!(props[6], uid);
+    }
+
+    #[test]
+    fn unescape_value() {
+        assert_eq!(unescape(r"Doe\, Jane\nline\;\\"), "Doe, Jane\nline;\\");
+        assert_eq!(
+            unescape(&escape("Doe, Jane\nline;\\")),
+            "Doe, Jane\nline;\\"
+        );
+    }
+
+    #[test]
+    fn fold() {
+        let line = "NOTE:".to_owned() + &"é".repeat(50);
+        let folded = fold_line(&line);
+        assert!(folded.iter().all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert_eq!(ContentLine { physical: folded }.unfolded(), line);
+    }
+}
@@ -1,204 +1,1585 @@
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fs::{read_dir, read_to_string, File},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+use chrono::{Local, Utc};
 use itertools::Itertools as _;
+use lsp_types::{CompletionItemKind, Url};
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
 use uriparse::URI;
-use vcard4::{property::Property as _, Vcard, VcardBuilder};
+use vcard4::{
+    property::{AddressProperty, DeliveryAddress, Kind, Property},
+    Vcard, VcardBuilder,
+};
+
+use crate::{
+    fold, normalize_phone,
+    phone::{phone_key, phones_match},
+    render::{extension_label, list_section, RenderOptions, Section},
+    vcard_text::{escape, unescape, ContentLine, VcardText},
+    Birthday, Conflict, Contact, ContactMeta, ContactSource, Encryption, FileStamp, Folded,
+    Location, Mailbox, Match, Matcher, MergeInto, Phone, PostalAddress, SearchIndex,
+    StructuredName,
+};
+
+/// Restricts the contacts used for completion and diagnostics by their `CATEGORIES`.
+#[derive(Debug, Clone, Default)]
+pub struct CategoryFilter {
+    /// Only use contacts with at least one of these categories, if any are given.
+    pub include: Vec<String>,
+    /// Never use contacts with any of these categories.
+    pub exclude: Vec<String>,
+}
+
+impl CategoryFilter {
+    fn allows(&self, vcard: &Vcard) -> bool {
+        let categories = categories(vcard)
+            .map(|c| c.to_lowercase())
+            .collect::<Vec<_>>();
+        let has = |wanted: &String| categories.contains(&wanted.to_lowercase());
+        (self.include.is_empty() || self.include.iter().any(has)) && !self.exclude.iter().any(has)
+    }
+}
+
+/// Which files in the vcard directory are loaded.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Load files that are symlinks, as vdirsyncer sometimes creates.
+    pub follow_symlinks: bool,
+    /// Load dotfiles and editor backups (`*~`, `.#*`), which are skipped by default.
+    pub include_hidden: bool,
+    /// Extensions of the files to load, without the leading `.`, matched ignoring case.
+    pub extensions: Vec<String>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            follow_symlinks: true,
+            include_hidden: false,
+            extensions: vec!["vcf".to_owned()],
+        }
+    }
+}
 
-use crate::{ContactSource, Location, Mailbox};
+impl ScanOptions {
+    /// Whether to load the file, which is also accepted with an encrypted extension after one of
+    /// the usual ones, e.g. `contact.vcf.gpg`.
+    fn accepts(&self, path: &Path, encryption: Option<&Encryption>) -> bool {
+        let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else {
+            return false;
+        };
+        if !self.include_hidden && (file_name.starts_with('.') || file_name.ends_with('~')) {
+            return false;
+        }
+        let is_symlink = path.symlink_metadata().is_ok_and(|m| m.is_symlink());
+        if is_symlink && !self.follow_symlinks {
+            return false;
+        }
+        let has_extension = |path: &Path| {
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or_default();
+            self.extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(extension))
+        };
+        let is_encrypted = encryption.is_some_and(|e| e.is_encrypted(path));
+        // is_file follows symlinks, skipping broken ones
+        path.is_file()
+            && (has_extension(path) || is_encrypted && has_extension(&path.with_extension("")))
+    }
+}
 
 pub struct VCards {
     root: PathBuf,
+    /// Name of the source, shown alongside its completion items.
+    label: String,
+    completion_kind: CompletionItemKind,
+    render_options: RenderOptions,
+    category_filter: CategoryFilter,
+    scan_options: ScanOptions,
+    encryption: Option<Encryption>,
+    /// A multi-card file to append new contacts to, rather than creating a file per contact.
+    new_contact_file: Option<PathBuf>,
     vcards: BTreeMap<PathBuf, Vec<vcard4::Vcard>>,
+    /// The state of each file when it was loaded.
+    stamps: HashMap<PathBuf, FileStamp>,
     emails_lower: HashSet<String>,
+    /// The cards in the search index with each phone number, by the number's
+    /// [`phone_key`].
+    phones: HashMap<String, Vec<usize>>,
+    /// The file and index of each card by its normalized `UID`.
+    uids: HashMap<String, (PathBuf, usize)>,
+    /// The file and index of each card in the search index.
+    cards: Vec<(PathBuf, usize)>,
+    /// The folded names and emails of each card, in the same order.
+    folded: Vec<FoldedCard>,
+    search_index: SearchIndex,
+    /// What the cards of each loaded file can conflict by, found as it is read so that conflicts
+    /// are found again without reading every file.
+    conflict_keys: BTreeMap<PathBuf, ConflictKeys>,
+    conflicts: Vec<Conflict>,
+    /// Whether addresses in the cards count as known for diagnostics.
+    diagnostics: bool,
+    /// Whether new contacts are created and existing cards edited.
+    writable: bool,
+    /// Whether the source only gets contacts harvested from messages sent.
+    harvest: bool,
+}
+
+/// What the cards of a file can conflict with others by.
+#[derive(Debug, Default)]
+struct ConflictKeys {
+    /// The `UID` of each card with one, at its line.
+    uids: Vec<(String, Location)>,
+    /// The folded name and email of each card for each of its `FN`s and `EMAIL`s, with the
+    /// mailbox to name it by, at the line of the email.
+    contacts: Vec<((String, String), String, Location)>,
 }
 
+impl ConflictKeys {
+    fn of(path: &Path, content: &str) -> Self {
+        let mut keys = Self::default();
+        let text = VcardText::parse(content);
+        for card in 0..text.card_count() {
+            let properties = text.properties_with_line_numbers(card);
+            let location = |line: usize, property: &ContentLine| Location {
+                path: path.to_owned(),
+                line: Some(line as u32),
+                columns: Some((0, property.physical_lines()[0].chars().count() as u32)),
+            };
+            if let Some((line, uid)) = properties.iter().find(|(_, p)| p.name() == "UID") {
+                keys.uids.push((uid.value(), location(*line, uid)));
+            }
+            let names = properties
+                .iter()
+                .filter(|(_, p)| p.name() == "FN")
+                .map(|(_, p)| unescape(&p.value()))
+                .collect::<Vec<_>>();
+            for (line, property) in properties.iter().filter(|(_, p)| p.name() == "EMAIL") {
+                let email = unescape(&property.value());
+                for name in &names {
+                    keys.contacts.push((
+                        (fold(name, true), fold(&email, true)),
+                        format!("{name} <{email}>"),
+                        location(*line, property),
+                    ));
+                }
+            }
+        }
+        keys
+    }
+}
+
+/// Properties that a card has at most one of, so merged cards keep their own.
+const SINGLE_PROPERTIES: [&str; 9] = [
+    "ANNIVERSARY",
+    "BDAY",
+    "GENDER",
+    "KIND",
+    "N",
+    "PRODID",
+    "REV",
+    "UID",
+    "VERSION",
+];
+
 impl ContactSource for VCards {
     fn render(&self, mailbox: &Mailbox) -> String {
         let vcards = self.get_by_mailbox(mailbox);
         vcards
             .iter()
-            .map(|vc| render_vcard(vc))
+            .map(|vc| {
+                let mut rendered = render_card(vc, &self.render_options, &|value| {
+                    self.render_related(value)
+                });
+                if is_group(vc) {
+                    let mut lines = Vec::new();
+                    list_section(
+                        &mut lines,
+                        "Members",
+                        self.render_options
+                            .limit_items(self.members(vc).iter().map(|m| m.to_string())),
+                    );
+                    rendered.push_str(&lines.join("\n"));
+                }
+                rendered
+            })
             .collect::<Vec<_>>()
             .join("\n\n")
     }
 
-    fn find_matching(&self, word: String) -> Box<dyn Iterator<Item = (String, Mailbox)> + '_> {
+    fn find_matching<'a>(&'a self, matcher: &'a Matcher) -> Box<dyn Iterator<Item = Match> + 'a> {
         Box::new(
-            self.vcards
-                .values()
-                .flatten()
-                .filter(move |vc| match_vcard(vc, &word))
-                .flat_map(mailboxes_for_vcard)
-                .unique()
-                .map(|m| ("VCards".to_owned(), m)),
+            self.candidates(matcher)
+                .filter(move |i| self.folded[*i].allowed && self.folded[*i].matches(matcher))
+                .map(|i| self.card(i))
+                .flat_map(move |vc| {
+                    let aliases = vc
+                        .formatted_name
+                        .iter()
+                        .skip(1)
+                        .chain(&vc.nickname)
+                        .map(|n| n.value.clone())
+                        .collect::<Vec<_>>();
+                    let addresses = preferred_emails(vc);
+                    mailboxes_for_vcard(vc)
+                        .into_iter()
+                        .map(move |mailbox| Match {
+                            source: self.label.clone(),
+                            kind: self.completion_kind,
+                            mailbox,
+                            aliases: aliases.clone(),
+                            addresses: addresses.clone(),
+                            members: Vec::new(),
+                        })
+                })
+                .unique_by(|m| m.mailbox.clone())
+                .chain(self.matching_groups(matcher)),
         )
     }
 
+    fn find_contacts(&self, matcher: &Matcher) -> Vec<Contact> {
+        self.candidates(matcher)
+            .filter(|i| self.folded[*i].allowed && self.folded[*i].matches(matcher))
+            .filter_map(|i| self.to_contact(i))
+            .collect()
+    }
+
     fn contains(&self, email: &str) -> bool {
-        self.emails_lower.contains(&email.to_lowercase())
+        self.diagnostics && self.emails_lower.contains(&fold(email, true))
     }
 
     fn locations(&self, mailbox: &Mailbox) -> Vec<Location> {
-        self.vcards
+        self.cards_to_locate(mailbox)
+            .into_iter()
+            .map(|i| &self.cards[i].0)
+            .dedup()
+            .map(|p| {
+                let position = self
+                    .read_file(p)
+                    .and_then(|content| email_position(&VcardText::parse(&content), mailbox));
+                Location {
+                    path: p.clone(),
+                    line: position.map(|(line, _, _)| line),
+                    columns: position.map(|(_, start, end)| (start, end)),
+                }
+            })
+            .collect()
+    }
+
+    fn group_locations(&self, mailbox: &Mailbox) -> Vec<Location> {
+        let email = fold(&mailbox.email, true);
+        // members are either the address or the UID of a card with it
+        let uids = self
+            .folded
             .iter()
-            .filter(|(_, vcs)| {
-                vcs.iter().any(|vc| {
-                    vc.email
-                        .iter()
-                        .any(|e| e.value.to_lowercase() == mailbox.email.to_lowercase())
-                        && mailbox.name.as_ref().map_or(true, |name| {
-                            vc.formatted_name
-                                .iter()
-                                .any(|f| f.value.to_lowercase() == name.to_lowercase())
-                        })
-                })
+            .positions(|card| card.emails.iter().any(|e| e.get(true) == email))
+            .filter_map(|i| self.card(i).uid.as_ref())
+            .map(|uid| normalize_uid(&uid.to_string()))
+            .collect::<HashSet<_>>();
+        let is_member = |uri: &str| match uri.strip_prefix("mailto:") {
+            Some(address) => fold(address, true) == email,
+            None => uids.contains(&normalize_uid(uri)),
+        };
+        (0..self.cards.len())
+            .filter(|i| {
+                let vc = self.card(*i);
+                is_group(vc) && vc.member.iter().any(|m| is_member(&m.value.to_string()))
+            })
+            .map(|i| &self.cards[i].0)
+            .dedup()
+            .flat_map(|path| {
+                let positions = self
+                    .read_file(path)
+                    .map(|content| member_positions(&VcardText::parse(&content), &is_member))
+                    .unwrap_or_default();
+                if positions.is_empty() {
+                    return vec![Location {
+                        path: path.clone(),
+                        line: None,
+                        columns: None,
+                    }];
+                }
+                positions
+                    .into_iter()
+                    .map(|(line, start, end)| Location {
+                        path: path.clone(),
+                        line: Some(line),
+                        columns: Some((start, end)),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn writable(&self) -> bool {
+        self.writable
+    }
+
+    fn harvest(&self) -> bool {
+        self.harvest
+    }
+
+    fn phones(&self, mailbox: &Mailbox) -> Vec<Phone> {
+        self.cards_for_mailbox(mailbox)
+            .flat_map(|i| &self.card(i).tel)
+            .map(|tel| Phone {
+                kind: first_type(tel),
+                number: tel.to_string(),
             })
-            .map(|(p, _)| Location {
-                path: p.clone(),
-                line: None,
+            .collect()
+    }
+
+    fn render_phone(&self, phone: &str) -> String {
+        let cards = self
+            .phones
+            .get(phone_key(phone))
+            .into_iter()
+            .flatten()
+            .filter(|i| self.folded[**i].allowed)
+            .filter(|i| {
+                self.card(**i)
+                    .tel
+                    .iter()
+                    .filter_map(|tel| normalize_phone(&tel.to_string()))
+                    .any(|number| phones_match(&number, phone))
+            });
+        cards
+            .map(|i| render_vcard(self.card(*i), &self.render_options))
+            .join("\n\n")
+    }
+
+    fn postal_addresses(&self, mailbox: &Mailbox) -> Vec<PostalAddress> {
+        self.cards_for_mailbox(mailbox)
+            .map(|i| self.card(i))
+            .flat_map(|vc| {
+                let name = vc
+                    .formatted_name
+                    .first()
+                    .map(|f| f.value.clone())
+                    .or_else(|| mailbox.name.clone());
+                vc.address.iter().map(move |a| PostalAddress {
+                    kind: first_type(a),
+                    lines: name
+                        .clone()
+                        .into_iter()
+                        .chain(address_lines(&a.value))
+                        .collect(),
+                })
             })
             .collect()
     }
 
-    fn create_contact(&mut self, mailbox: Mailbox) -> Option<PathBuf> {
-        let filename = uuid::Uuid::new_v4().to_string();
-        let path = self.root.join(&filename).with_extension("vcf");
-        let vcard = VcardBuilder::new(mailbox.name.unwrap_or_default())
+    fn birthday(&self, mailbox: &Mailbox) -> Option<Birthday> {
+        self.cards_for_mailbox(mailbox)
+            .filter(|i| self.folded[*i].allowed)
+            .find_map(|i| Birthday::parse(&self.card(i).bday.as_ref()?.to_string()))
+    }
+
+    fn uid_locations(&self, uid: &str) -> Vec<Location> {
+        self.uids
+            .get(&normalize_uid(uid))
+            .and_then(|(path, i)| self.card_location(path, *i))
+            .into_iter()
+            .collect()
+    }
+
+    fn contact_files(&self, mailbox: &Mailbox) -> Vec<PathBuf> {
+        self.cards_for_mailbox(mailbox)
+            .map(|i| self.cards[i].0.clone())
+            .dedup()
+            .collect()
+    }
+
+    fn create_contact(
+        &mut self,
+        mailbox: Mailbox,
+        name: Option<StructuredName>,
+    ) -> Option<Location> {
+        if !self.writable {
+            return None;
+        }
+        let uid = uuid::Uuid::new_v4().to_string();
+        let formatted_name = mailbox.name.clone().unwrap_or_default();
+        let name = name.unwrap_or_else(|| StructuredName::from_display_name(&formatted_name));
+        let vcard = VcardBuilder::new(formatted_name)
             .uid(
-                URI::try_from(format!("urn:uuid:{}", filename).as_str())
+                URI::try_from(format!("urn:uuid:{}", uid).as_str())
                     .unwrap()
                     .into_owned(),
             )
-            .email(mailbox.email)
+            .email(mailbox.email.clone())
             .finish();
-        let mut f = File::create(&path).unwrap();
-        f.write_all(vcard.to_string().as_bytes()).unwrap();
-        self.vcards.insert(path.clone(), vec![vcard]);
-        Some(path)
+        let mut card = VcardText::parse(&vcard.to_string());
+        card.set_property(0, ContentLine::new("N", &name.to_vcard_value()));
+        card.mark_modified(0, Utc::now());
+
+        let (path, content) = match &self.new_contact_file {
+            Some(file) => {
+                let existing = self.read_file(file).unwrap_or_default();
+                let content = if existing.trim().is_empty() {
+                    card.to_string()
+                } else {
+                    let mut text = VcardText::parse(&existing);
+                    text.append(&card.to_string());
+                    text.to_string()
+                };
+                (file.clone(), content)
+            }
+            None => {
+                let filename = match &self.encryption {
+                    Some(encryption) if encryption.encrypt_new => {
+                        format!("{uid}.vcf.{}", encryption.extension())
+                    }
+                    _ => format!("{uid}.vcf"),
+                };
+                (self.root.join(filename), card.to_string())
+            }
+        };
+        self.write_file(&path, &content).ok()?;
+        let position = email_position(&VcardText::parse(&content), &mailbox);
+        self.reload_file(&path, content);
+        Some(Location {
+            path,
+            line: position.map(|(line, _, _)| line),
+            columns: position.map(|(_, start, end)| (start, end)),
+        })
+    }
+
+    fn conflicts(&self) -> Vec<Conflict> {
+        self.conflicts.clone()
+    }
+
+    /// Merge the cards into the one picked by `into`: it gets any properties of the others that
+    /// it doesn't have already, then they are removed, along with files left without cards.
+    fn merge(&mut self, conflict: &Conflict, into: &MergeInto) -> Option<Location> {
+        if !self.writable || !self.conflicts.contains(conflict) {
+            return None;
+        }
+        let mut texts = BTreeMap::new();
+        let mut cards = Vec::new();
+        for location in &conflict.locations {
+            if !texts.contains_key(&location.path) {
+                let text = VcardText::parse(&self.read_file(&location.path)?);
+                texts.insert(location.path.clone(), text);
+            }
+            let card = texts[&location.path].card_at_line(location.line? as usize)?;
+            if !cards.contains(&(location.path.clone(), card)) {
+                cards.push((location.path.clone(), card));
+            }
+        }
+        let base = match into {
+            MergeInto::First => 0,
+            MergeInto::Newest => cards
+                .iter()
+                .enumerate()
+                .max_by_key(|(i, (path, card))| {
+                    let rev = texts[path]
+                        .properties(*card)
+                        .iter()
+                        .find(|p| p.name() == "REV")
+                        .map(|p| p.value().replace(['-', ':'], ""));
+                    // the first of those revised at the same time
+                    (rev, std::cmp::Reverse(*i))
+                })
+                .map_or(0, |(i, _)| i),
+            MergeInto::Under(dir) => cards
+                .iter()
+                .position(|(p, _)| p.starts_with(dir))
+                .unwrap_or(0),
+            MergeInto::Location(i) => {
+                let location = conflict.locations.get(*i)?;
+                let card = texts[&location.path].card_at_line(location.line? as usize)?;
+                cards
+                    .iter()
+                    .position(|c| *c == (location.path.clone(), card))?
+            }
+        };
+        cards.swap(0, base);
+        let [(path, card), others @ ..] = cards.as_slice() else {
+            return None;
+        };
+        if others.is_empty() {
+            return None;
+        }
+
+        let mut merged = texts[path].properties(*card).to_vec();
+        let mut added = Vec::new();
+        for (other_path, other) in others {
+            for property in texts[other_path].properties(*other) {
+                let name = property.name();
+                let has = |p: &ContentLine| {
+                    p.unfolded() == property.unfolded()
+                        || (SINGLE_PROPERTIES.contains(&name.as_str()) && p.name() == name)
+                };
+                if !merged.iter().any(has) {
+                    merged.push(property.clone());
+                    added.push(property.clone());
+                }
+            }
+        }
+        let text = texts.get_mut(path)?;
+        for property in added {
+            text.add_property(*card, property);
+        }
+        text.mark_modified(*card, Utc::now());
+        // from the back of each file so that the indices of the others stay valid
+        for (other_path, other) in others.iter().sorted().rev() {
+            texts.get_mut(other_path)?.remove_card(*other);
+        }
+        let card = card - others.iter().filter(|(p, c)| p == path && c < card).count();
+
+        // the merged card first, so that nothing is lost if removing the others fails
+        let mut paths = texts.keys().cloned().collect::<Vec<_>>();
+        paths.sort_by_key(|p| p != path);
+        for p in paths {
+            let text = &texts[&p];
+            if text.card_count() == 0 {
+                std::fs::remove_file(&p).ok()?;
+                self.vcards.remove(&p);
+                self.stamps.remove(&p);
+                self.conflict_keys.remove(&p);
+                self.index();
+                self.conflicts = self.find_conflicts();
+            } else {
+                let content = text.to_string();
+                self.write_file(&p, &content).ok()?;
+                self.reload_file(&p, content);
+            }
+        }
+        let line = texts[path]
+            .properties_with_line_numbers(card)
+            .first()
+            .map(|(line, _)| *line as u32);
+        Some(Location {
+            path: path.clone(),
+            line,
+            columns: None,
+        })
+    }
+
+    fn add_note(&mut self, mailbox: &Mailbox, note: &str) -> Option<Location> {
+        if !self.writable {
+            return None;
+        }
+        let path = self.cards[*self.cards_to_locate(mailbox).first()?]
+            .0
+            .clone();
+        let mut text = VcardText::parse(&self.read_file(&path)?);
+        let (line, _, _) = email_position(&text, mailbox)?;
+        let card = text.card_at_line(line as usize)?;
+        let entry = format!("{}: {}", Local::now().format("%Y-%m-%d"), note.trim());
+        let notes = text
+            .properties(card)
+            .iter()
+            .find(|p| p.name() == "NOTE")
+            .map(|p| unescape(&p.value()))
+            .filter(|n| !n.trim().is_empty());
+        let notes = match notes {
+            Some(notes) => format!("{}\n{entry}", notes.trim_end()),
+            None => entry,
+        };
+        text.set_property(card, ContentLine::new("NOTE", &escape(&notes)));
+        text.mark_modified(card, Utc::now());
+        let line = text
+            .properties_with_line_numbers(card)
+            .into_iter()
+            .find(|(_, p)| p.name() == "NOTE")
+            .map(|(line, _)| line as u32);
+        let content = text.to_string();
+        self.write_file(&path, &content).ok()?;
+        self.reload_file(&path, content);
+        Some(Location {
+            path,
+            line,
+            columns: None,
+        })
+    }
+
+    fn contact_count(&self) -> usize {
+        self.cards.len()
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.root.exists() {
+            problems.push(format!(
+                "{} doesn't exist, it has no contacts until it is created",
+                self.root.display()
+            ));
+        }
+        // files that failed to load are stamped but have no cards
+        problems.extend(
+            self.stamps
+                .keys()
+                .filter(|p| !self.vcards.contains_key(*p))
+                .sorted()
+                .map(|p| format!("Failed to load {}, see the logs for why", p.display())),
+        );
+        problems
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.vcards.keys().cloned().collect()
+    }
+
+    fn watched_paths(&self) -> Vec<PathBuf> {
+        self.new_contact_file
+            .iter()
+            .chain([&self.root])
+            .cloned()
+            .collect()
+    }
+
+    fn reload(&mut self, changed: &[PathBuf]) -> bool {
+        let reloaded = if changed.contains(&self.root) {
+            // the directory itself changed, so check all of its files
+            let mut paths = self.vcard_files();
+            paths.extend(self.stamps.keys().cloned());
+            self.refresh_files(paths)
+        } else {
+            self.refresh_files(changed.to_vec())
+        };
+        if reloaded {
+            self.index();
+            self.conflicts = self.find_conflicts();
+        }
+        reloaded
     }
 }
 
 impl VCards {
+    /// Load the vcards in the directory, or the single file, at the given path.
+    ///
+    /// When given a file, new contacts are appended to it.
     pub fn new(value: PathBuf) -> Self {
+        let new_contact_file = value.is_file().then(|| value.clone());
         let mut s = Self {
             root: value,
+            label: "VCards".to_owned(),
+            completion_kind: CompletionItemKind::REFERENCE,
+            render_options: RenderOptions::default(),
+            category_filter: CategoryFilter::default(),
+            scan_options: ScanOptions::default(),
+            encryption: None,
+            new_contact_file,
             vcards: BTreeMap::new(),
+            stamps: HashMap::new(),
             emails_lower: HashSet::new(),
+            phones: HashMap::new(),
+            uids: HashMap::new(),
+            cards: Vec::new(),
+            folded: Vec::new(),
+            search_index: SearchIndex::default(),
+            conflict_keys: BTreeMap::new(),
+            conflicts: Vec::new(),
+            diagnostics: true,
+            writable: true,
+            harvest: false,
         };
         s.load_vcards();
         s
     }
 
-    fn load_vcards(&mut self) {
+    /// Set the kind of completion items for this source's contacts.
+    pub fn with_completion_kind(mut self, kind: CompletionItemKind) -> Self {
+        self.completion_kind = kind;
+        self
+    }
+
+    /// Set the name of the source, shown alongside its completion items.
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Set whether the source only gets contacts harvested from messages sent, rather than those
+    /// added by hand.
+    pub fn with_harvest(mut self, harvest: bool) -> Self {
+        self.harvest = harvest;
+        self
+    }
+
+    /// Set whether addresses in the cards count as known for diagnostics.
+    pub fn with_diagnostics(mut self, diagnostics: bool) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Set whether new contacts are created and existing cards edited.
+    pub fn with_writable(mut self, writable: bool) -> Self {
+        self.writable = writable;
+        self
+    }
+
+    /// Set how contacts are rendered, e.g. for hover.
+    pub fn with_render_options(mut self, options: RenderOptions) -> Self {
+        self.render_options = options;
+        self
+    }
+
+    /// Restrict the contacts used for completion and diagnostics by their categories.
+    pub fn with_category_filter(mut self, filter: CategoryFilter) -> Self {
+        self.category_filter = filter;
+        self.index();
+        self
+    }
+
+    /// Set which files in the directory are loaded, reloading them.
+    pub fn with_scan_options(mut self, options: ScanOptions) -> Self {
+        self.scan_options = options;
+        self.load_vcards();
+        self
+    }
+
+    /// Decrypt encrypted files when loading them and encrypt them when writing, reloading them.
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self.load_vcards();
+        self
+    }
+
+    /// Append new contacts to the given file instead of creating a file per contact.
+    ///
+    /// Relative paths are taken from the vcard directory, and the file is created if it doesn't
+    /// exist yet.
+    pub fn with_new_contact_file(mut self, file: PathBuf) -> Self {
+        let file = self.root.join(file);
+        if let Some(content) = self.read_file(&file) {
+            self.reload_file(&file, content);
+        }
+        self.new_contact_file = Some(file);
+        self
+    }
+
+    /// Edit the card for the mailbox in place, returning the path of the file it is in.
+    ///
+    /// The edit is applied to the text of the file so that anything it doesn't touch is kept
+    /// exactly as it was, the card is marked as modified, then the file is reloaded.
+    pub fn edit_card(
+        &mut self,
+        mailbox: &Mailbox,
+        edit: impl FnOnce(&mut VcardText, usize),
+    ) -> Option<PathBuf> {
+        if !self.writable {
+            return None;
+        }
+        let email = fold(&mailbox.email, true);
+        let path = self
+            .vcards
+            .iter()
+            .find(|(_, vcs)| {
+                vcs.iter()
+                    .any(|vc| vc.email.iter().any(|e| fold(&e.value, true) == email))
+            })
+            .map(|(p, _)| p.clone())?;
+        let mut text = VcardText::parse(&self.read_file(&path)?);
+        let card = text.find_card(|props| {
+            props
+                .iter()
+                .any(|p| p.name() == "EMAIL" && fold(&p.value(), true) == email)
+        })?;
+        edit(&mut text, card);
+        text.mark_modified(card, Utc::now());
+        let content = text.to_string();
+        self.write_file(&path, &content).ok()?;
+        self.reload_file(&path, content);
+        Some(path)
+    }
+
+    /// Read a vcard file, decrypting it if needed.
+    fn read_file(&self, path: &Path) -> Option<String> {
+        match &self.encryption {
+            Some(encryption) if encryption.is_encrypted(path) => match encryption.decrypt(path) {
+                Ok(content) => Some(content),
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), "Failed to decrypt vcard: {err}");
+                    None
+                }
+            },
+            _ => read_to_string(path).ok(),
+        }
+    }
+
+    /// Write a vcard file, encrypting it if needed.
+    fn write_file(&self, path: &Path, content: &str) -> Result<(), String> {
+        let bytes = match &self.encryption {
+            Some(encryption) if encryption.is_encrypted(path) => {
+                encryption.encrypt(content).map_err(|err| {
+                    tracing::warn!(path = %path.display(), "Failed to encrypt vcard: {err}");
+                    err
+                })?
+            }
+            _ => content.as_bytes().to_vec(),
+        };
+        let mut f = File::create(path).map_err(|err| err.to_string())?;
+        f.write_all(&bytes).map_err(|err| err.to_string())
+    }
+
+    fn reload_file(&mut self, path: &Path, content: String) {
+        let keys = ConflictKeys::of(path, &content);
+        match vcard4::parse_loose(content) {
+            Ok(vcards) => {
+                self.vcards.insert(path.to_owned(), vcards);
+                self.conflict_keys.insert(path.to_owned(), keys);
+            }
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "Failed to load vcard: {err}");
+                self.vcards.remove(path);
+                self.conflict_keys.remove(path);
+            }
+        }
+        // the file is up to date, so its change event doesn't need to read it again
+        if let Some(stamp) = FileStamp::of(path) {
+            self.stamps.insert(path.to_owned(), stamp);
+        }
+        self.index();
+        self.conflicts = self.find_conflicts();
+    }
+
+    /// The files to load: those in the directory, or the single file.
+    fn vcard_files(&self) -> Vec<PathBuf> {
         let mut vcard_files = Vec::new();
-        for entry in read_dir(&self.root).unwrap() {
-            let entry = entry.unwrap();
-            let path = entry.path();
-            if path.is_file() && path.extension().unwrap_or_default() == "vcf" {
-                vcard_files.push(path);
+        if self.root.is_file() {
+            vcard_files.push(self.root.clone());
+        } else {
+            // a directory that doesn't exist yet, e.g. before the first sync, has no cards
+            for entry in read_dir(&self.root).into_iter().flatten().flatten() {
+                let path = entry.path();
+                if self.scan_options.accepts(&path, self.encryption.as_ref()) {
+                    vcard_files.push(path);
+                }
             }
+            if let Some(file) = &self.new_contact_file {
+                if file.is_file() && !vcard_files.contains(file) {
+                    vcard_files.push(file.clone());
+                }
+            }
+        }
+        vcard_files
+    }
+
+    /// Whether the file should be loaded.
+    fn is_vcard_file(&self, path: &Path) -> bool {
+        if self.root.is_file() {
+            return path == self.root;
         }
+        (self.new_contact_file.as_deref() == Some(path) && path.is_file())
+            || (path.parent() == Some(&self.root)
+                && self.scan_options.accepts(path, self.encryption.as_ref()))
+    }
 
+    fn load_vcards(&mut self) {
         self.vcards.clear();
-        for path in vcard_files {
-            let content = read_to_string(&path).unwrap_or_default();
-            match vcard4::parse_loose(content) {
-                Ok(vcards) => {
-                    for email in vcards.iter().flat_map(|v| &v.email).map(|w| &w.value) {
-                        self.emails_lower.insert(email.to_lowercase());
+        self.stamps.clear();
+        self.conflict_keys.clear();
+        let vcard_files = self.vcard_files();
+        self.refresh_files(vcard_files);
+        self.index();
+        self.conflicts = self.find_conflicts();
+    }
+
+    /// Bring the given files up to date: read those added or changed since they were loaded,
+    /// going by their modification time and size, and drop those that are gone.
+    ///
+    /// Returns whether any cards changed, in which case the caller should rebuild the indexes.
+    fn refresh_files(&mut self, paths: Vec<PathBuf>) -> bool {
+        let mut changed = Vec::new();
+        let mut removed = false;
+        for path in paths {
+            match FileStamp::of(&path).filter(|_| self.is_vcard_file(&path)) {
+                Some(stamp) => {
+                    if self.stamps.get(&path) != Some(&stamp)
+                        && !changed.iter().any(|(p, _)| *p == path)
+                    {
+                        changed.push((path, stamp));
                     }
-                    self.vcards.entry(path).or_default().extend(vcards);
                 }
-                Err(err) => {
-                    // skip card that couldn't be loaded
-                    eprintln!("Failed to load vcard at {:?}: {}", path, err);
+                None => {
+                    if self.stamps.remove(&path).is_some() {
+                        self.vcards.remove(&path);
+                        self.conflict_keys.remove(&path);
+                        removed = true;
+                    }
                 }
             }
         }
+
+        // reading and parsing dominate startup on large collections, so do them in parallel
+        let loaded = changed
+            .into_par_iter()
+            .map(|(path, stamp)| {
+                let vcards = self.read_file(&path).and_then(|content| {
+                    let keys = ConflictKeys::of(&path, &content);
+                    match vcard4::parse_loose(content) {
+                        Ok(vcards) => Some((vcards, keys)),
+                        Err(err) => {
+                            // skip card that couldn't be loaded
+                            tracing::warn!(
+                                path = %path.display(),
+                                "Failed to load vcard: {err}"
+                            );
+                            None
+                        }
+                    }
+                });
+                (path, stamp, vcards)
+            })
+            .collect::<Vec<_>>();
+        let reloaded = removed || !loaded.is_empty();
+        for (path, stamp, vcards) in loaded {
+            // failed files are kept stamped so they're only retried once they change
+            self.stamps.insert(path.clone(), stamp);
+            match vcards {
+                Some((vcards, keys)) => {
+                    self.conflict_keys.insert(path.clone(), keys);
+                    self.vcards.insert(path, vcards);
+                }
+                None => {
+                    self.vcards.remove(&path);
+                    self.conflict_keys.remove(&path);
+                }
+            }
+        }
+        reloaded
     }
 
-    fn get_by_mailbox(&self, mailbox: &Mailbox) -> Vec<&Vcard> {
-        self.vcards
+    /// Find cards sharing a `UID`, or with the same name and email, from the keys of the files
+    /// found when they were loaded.
+    fn find_conflicts(&self) -> Vec<Conflict> {
+        let mut uids = BTreeMap::<String, Vec<Location>>::new();
+        let mut contacts = BTreeMap::<(String, String), (String, Vec<Location>)>::new();
+        for keys in self.conflict_keys.values() {
+            for (uid, location) in &keys.uids {
+                uids.entry(uid.clone()).or_default().push(location.clone());
+            }
+            for (key, mailbox, location) in &keys.contacts {
+                contacts
+                    .entry(key.clone())
+                    .or_insert_with(|| (mailbox.clone(), Vec::new()))
+                    .1
+                    .push(location.clone());
+            }
+        }
+
+        let uids = uids
+            .into_iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(uid, locations)| Conflict {
+                message: format!("UID {uid} is used by {} cards", locations.len()),
+                locations,
+            });
+        let contacts = contacts
+            .into_values()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(mailbox, locations)| Conflict {
+                message: format!("{mailbox} is in {} cards", locations.len()),
+                locations,
+            });
+        uids.chain(contacts).collect()
+    }
+
+    /// Index the loaded cards by email and `UID`.
+    fn index(&mut self) {
+        self.uids = self
+            .vcards
+            .iter()
+            .flat_map(|(path, vcs)| {
+                vcs.iter().enumerate().filter_map(|(i, vc)| {
+                    let uid = vc.uid.as_ref()?.to_string();
+                    Some((normalize_uid(&uid), (path.clone(), i)))
+                })
+            })
+            .collect();
+        self.emails_lower = self
+            .vcards
             .values()
             .flatten()
-            .filter(|vc| {
-                vc.email
+            .filter(|vc| self.category_filter.allows(vc))
+            .flat_map(|vc| &vc.email)
+            .map(|e| fold(&e.value, true))
+            .collect();
+        self.cards = self
+            .vcards
+            .iter()
+            .flat_map(|(path, vcs)| (0..vcs.len()).map(|i| (path.clone(), i)))
+            .collect();
+        self.phones = HashMap::new();
+        for (i, (path, j)) in self.cards.iter().enumerate() {
+            let numbers = self.vcards[path][*j]
+                .tel
+                .iter()
+                .filter_map(|tel| normalize_phone(&tel.to_string()))
+                .map(|number| phone_key(&number).to_owned())
+                .unique();
+            for key in numbers {
+                self.phones.entry(key).or_default().push(i);
+            }
+        }
+        self.folded = self
+            .cards
+            .iter()
+            .map(|(path, i)| FoldedCard::new(&self.vcards[path][*i], &self.category_filter))
+            .collect();
+        self.search_index =
+            SearchIndex::new(self.cards.iter().enumerate().map(|(i, (path, j))| {
+                let vc = &self.vcards[path][*j];
+                let fields = vc
+                    .formatted_name
                     .iter()
-                    .any(|e| e.value.to_lowercase() == mailbox.email.to_lowercase())
-                    && mailbox.name.as_ref().map_or(true, |name| {
-                        vc.formatted_name
-                            .iter()
-                            .any(|f| f.value.to_lowercase() == name.to_lowercase())
-                    })
+                    .chain(&vc.nickname)
+                    .map(|n| n.value.as_str())
+                    .chain(vc.email.iter().map(|e| e.value.as_str()))
+                    .collect();
+                (i, fields)
+            }));
+    }
+
+    fn card(&self, i: usize) -> &Vcard {
+        let (path, j) = &self.cards[i];
+        &self.vcards[path][*j]
+    }
+
+    /// The cards that may match the query, in order.
+    fn candidates<'a>(&'a self, matcher: &Matcher) -> Box<dyn Iterator<Item = usize> + 'a> {
+        match self.search_index.candidates(matcher) {
+            Some(candidates) => Box::new(candidates.into_iter()),
+            None => Box::new(0..self.cards.len()),
+        }
+    }
+
+    /// The card a `RELATED` value refers to by its `UID`, or by an email with `mailto:`.
+    fn related_card(&self, value: &str) -> Option<(PathBuf, usize)> {
+        match value.strip_prefix("mailto:") {
+            Some(email) => {
+                let email = fold(email, true);
+                let i = self
+                    .folded
+                    .iter()
+                    .position(|card| card.emails.iter().any(|e| e.get(true) == email))?;
+                Some(self.cards[i].clone())
+            }
+            None => self.uids.get(&normalize_uid(value)).cloned(),
+        }
+    }
+
+    /// The related contact by name, linking to its card.
+    fn render_related(&self, value: &str) -> Option<String> {
+        let (path, i) = self.related_card(value)?;
+        let vcard = self.vcards.get(&path)?.get(i)?;
+        let name = vcard
+            .formatted_name
+            .first()
+            .map(|n| n.value.clone())
+            .or_else(|| preferred_emails(vcard).into_iter().next())?;
+        let Some(mut url) = Url::from_file_path(&path).ok() else {
+            return Some(name);
+        };
+        if let Some(line) = self.card_location(&path, i).and_then(|l| l.line) {
+            url.set_fragment(Some(&format!("L{}", line + 1)));
+        }
+        Some(format!("[{name}]({url})"))
+    }
+
+    /// Where the card is in its file, at its `BEGIN` line.
+    fn card_location(&self, path: &Path, card: usize) -> Option<Location> {
+        let text = VcardText::parse(&self.read_file(path)?);
+        let line = text
+            .properties_with_line_numbers(card)
+            .first()
+            .map(|(line, _)| line.saturating_sub(1) as u32);
+        Some(Location {
+            path: path.to_owned(),
+            line,
+            columns: None,
+        })
+    }
+
+    /// The mailboxes of the members of a group card: cards referenced by their `UID`, using
+    /// their preferred email, and `mailto:` URIs.
+    fn members(&self, group: &Vcard) -> Vec<Mailbox> {
+        group
+            .member
+            .iter()
+            .filter_map(|member| {
+                let uri = member.value.to_string();
+                if let Some(email) = uri.strip_prefix("mailto:") {
+                    return Some(Mailbox {
+                        name: None,
+                        email: email.to_owned(),
+                    });
+                }
+                let (path, i) = self.uids.get(&normalize_uid(&uri))?;
+                let vcard = self.vcards.get(path)?.get(*i)?;
+                let email = preferred_emails(vcard).into_iter().next()?;
+                Some(Mailbox {
+                    name: vcard.formatted_name.first().map(|n| n.value.clone()),
+                    email,
+                })
             })
             .collect()
     }
+
+    /// Groups matching the query, which complete to all of their members.
+    fn matching_groups<'a>(&'a self, matcher: &'a Matcher) -> impl Iterator<Item = Match> + 'a {
+        self.candidates(matcher)
+            .filter(move |i| self.folded[*i].allowed && self.folded[*i].matches(matcher))
+            .map(|i| self.card(i))
+            .filter(|vc| is_group(vc))
+            .filter_map(|vc| {
+                let members = self.members(vc);
+                if members.is_empty() {
+                    return None;
+                }
+                Some(Match {
+                    source: self.label.clone(),
+                    kind: CompletionItemKind::FOLDER,
+                    mailbox: Mailbox {
+                        name: vc.formatted_name.first().map(|n| n.value.clone()),
+                        email: String::new(),
+                    },
+                    aliases: vc.nickname.iter().map(|n| n.value.clone()).collect(),
+                    addresses: Vec::new(),
+                    members,
+                })
+            })
+    }
+
+    /// The contact of a card in the search index, or `None` for a group without members.
+    fn to_contact(&self, i: usize) -> Option<Contact> {
+        let (path, j) = &self.cards[i];
+        let vc = self.card(i);
+        let name = vc.formatted_name.first().map(|n| n.value.clone());
+        let members = if is_group(vc) {
+            let members = self.members(vc);
+            if members.is_empty() {
+                return None;
+            }
+            members
+        } else {
+            Vec::new()
+        };
+        Some(Contact {
+            id: Contact::id_in(path, *j),
+            names: vc
+                .formatted_name
+                .iter()
+                .chain(&vc.nickname)
+                .map(|n| n.value.clone())
+                .collect(),
+            mailboxes: preferred_emails(vc)
+                .into_iter()
+                .map(|email| Mailbox {
+                    name: name.clone(),
+                    email,
+                })
+                .collect(),
+            meta: ContactMeta {
+                source: self.label.clone(),
+                kind: if members.is_empty() {
+                    self.completion_kind
+                } else {
+                    CompletionItemKind::FOLDER
+                },
+                path: Some(path.clone()),
+                birthday: vc
+                    .bday
+                    .as_ref()
+                    .and_then(|bday| Birthday::parse(&bday.to_string())),
+                phones: vc
+                    .tel
+                    .iter()
+                    .map(|tel| Phone {
+                        kind: first_type(tel),
+                        number: tel.to_string(),
+                    })
+                    .collect(),
+            },
+            members,
+        })
+    }
+
+    fn get_by_mailbox(&self, mailbox: &Mailbox) -> Vec<&Vcard> {
+        self.cards_for_mailbox(mailbox)
+            .map(|i| self.card(i))
+            .collect()
+    }
+
+    /// The cards to go to for the mailbox: those with its email and name, else those with its
+    /// email, else those with its name, ignoring case and diacritics.
+    fn cards_to_locate(&self, mailbox: &Mailbox) -> Vec<usize> {
+        let email = fold(&mailbox.email, true);
+        let name = mailbox.name.as_deref().map(|n| fold(n, true));
+        let has_email = |card: &FoldedCard| card.emails.iter().any(|e| e.get(true) == email);
+        let has_name = |names: &[Folded]| {
+            name.as_ref()
+                .is_some_and(|name| names.iter().any(|n| n.get(true) == name))
+        };
+        let cards = |matches: &dyn Fn(&FoldedCard) -> bool| {
+            self.folded.iter().positions(matches).collect::<Vec<_>>()
+        };
+        [
+            cards(&|card| has_email(card) && (name.is_none() || has_name(card.formatted_names()))),
+            cards(&has_email),
+            cards(&|card| has_name(&card.names)),
+        ]
+        .into_iter()
+        .find(|cards| !cards.is_empty())
+        .unwrap_or_default()
+    }
+
+    /// The cards with the mailbox's email, and its name if it has one.
+    fn cards_for_mailbox<'a>(&'a self, mailbox: &Mailbox) -> impl Iterator<Item = usize> + 'a {
+        let email = fold(&mailbox.email, false);
+        let name = mailbox.name.as_deref().map(|n| fold(n, false));
+        self.folded.iter().enumerate().filter_map(move |(i, card)| {
+            let matches = card.emails.iter().any(|e| e.get(false) == email)
+                && name
+                    .as_ref()
+                    .is_none_or(|name| card.formatted_names().iter().any(|f| f.get(false) == name));
+            matches.then_some(i)
+        })
+    }
+}
+
+/// The line and character columns of the mailbox's `EMAIL` property in the file, falling back to
+/// the card's `FN`.
+fn email_position(text: &VcardText, mailbox: &Mailbox) -> Option<(u32, u32, u32)> {
+    let email = fold(&mailbox.email, true);
+    let name = mailbox.name.as_deref().map(|n| fold(n, true));
+    let has_value = |props: &[ContentLine], property: &str, value: &str| {
+        props
+            .iter()
+            .any(|p| p.name() == property && fold(&unescape(&p.value()), true) == value)
+    };
+    let card = text.find_card(|props| {
+        has_value(props, "EMAIL", &email) && name.as_ref().is_none_or(|n| has_value(props, "FN", n))
+    })?;
+    let properties = text.properties_with_line_numbers(card);
+    let (line_number, line) = properties
+        .iter()
+        .find(|(_, p)| p.name() == "EMAIL" && fold(&unescape(&p.value()), true) == email)
+        .or_else(|| properties.iter().find(|(_, p)| p.name() == "FN"))?;
+    let (offset, start, end) = line.value_position();
+    Some(((line_number + offset) as u32, start as u32, end as u32))
 }
 
-fn render_vcard(vcard: &Vcard) -> String {
+/// The line and character columns of the `MEMBER` properties in the file for the member.
+fn member_positions(text: &VcardText, is_member: &impl Fn(&str) -> bool) -> Vec<(u32, u32, u32)> {
+    (0..text.card_count())
+        .flat_map(|card| text.properties_with_line_numbers(card))
+        .filter(|(_, p)| p.name() == "MEMBER" && is_member(&p.value()))
+        .map(|(line_number, line)| {
+            let (offset, start, end) = line.value_position();
+            ((line_number + offset) as u32, start as u32, end as u32)
+        })
+        .collect()
+}
+
+/// Render a card as markdown, e.g. for hover.
+pub fn render_vcard(vcard: &Vcard, options: &RenderOptions) -> String {
+    render_card(vcard, options, &|_| None)
+}
+
+/// Render the card, rendering the contacts it is related to with `resolve`, or as their `RELATED`
+/// value where it gives `None`.
+fn render_card(
+    vcard: &Vcard,
+    options: &RenderOptions,
+    resolve: &dyn Fn(&str) -> Option<String>,
+) -> String {
     let mut lines = Vec::new();
-    if let Some(formatted_name) = vcard.formatted_name.first() {
-        lines.push(format!("# {}", formatted_name.value));
-        lines.push(String::new());
-    }
-    if let Some(nick) = vcard.nickname.first() {
-        lines.push(format!("_{}_", nick.value));
-        lines.push(String::new());
-    }
-    if !vcard.email.is_empty() {
-        lines.push("Email:".to_owned());
-        for e in vcard.email.iter() {
-            let mut line = "- ".to_owned();
-            if let Some(typ) = &e
-                .parameters()
-                .and_then(|p| p.types.as_ref().and_then(|types| types.first()))
-            {
-                line.push_str(&typ.to_string());
-                line.push_str(": ");
-            }
-            line.push_str(&e.value);
-            lines.push(line);
-        }
-        lines.push(String::new());
-    }
-    if !vcard.tel.is_empty() {
-        lines.push("Telephone:".to_owned());
-        for e in vcard.tel.iter() {
-            let mut line = "- ".to_owned();
-            if let Some(typ) = &e
-                .parameters()
-                .and_then(|p| p.types.as_ref().and_then(|types| types.first()))
-            {
-                line.push_str(&typ.to_string());
-                line.push_str(": ");
-            }
-            line.push_str(&e.to_string());
-            lines.push(line);
-        }
-        lines.push(String::new());
+    for section in &options.sections {
+        match section {
+            Section::Photo => {
+                let image = match vcard.photo.first() {
+                    Some(photo) => Some(format!("![photo]({photo})")),
+                    None => options.avatars.as_ref().and_then(|avatars| {
+                        vcard.email.iter().find_map(|e| avatars.render(&e.value))
+                    }),
+                };
+                if let Some(image) = image {
+                    lines.push(image);
+                    lines.push(String::new());
+                }
+            }
+            Section::Name => {
+                if let Some(formatted_name) = vcard.formatted_name.first() {
+                    lines.push(format!("# {}", formatted_name.value));
+                    lines.push(String::new());
+                }
+            }
+            Section::Nickname => {
+                if let Some(nick) = vcard.nickname.first() {
+                    lines.push(format!("_{}_", nick.value));
+                    lines.push(String::new());
+                }
+            }
+            Section::Email => list_section(
+                &mut lines,
+                "Email",
+                options.limit_items(vcard.email.iter().map(|e| typed_value(e, &e.value))),
+            ),
+            Section::Telephone => list_section(
+                &mut lines,
+                "Telephone",
+                options.limit_items(vcard.tel.iter().map(|t| typed_value(t, &t.to_string()))),
+            ),
+            Section::Organization => list_section(
+                &mut lines,
+                "Organization",
+                options.limit_items(
+                    vcard
+                        .org
+                        .iter()
+                        .map(|o| o.value.iter().filter(|v| !v.is_empty()).join(", ")),
+                ),
+            ),
+            Section::Title => list_section(
+                &mut lines,
+                "Title",
+                options.limit_items(vcard.title.iter().map(|t| t.value.clone())),
+            ),
+            Section::Role => list_section(
+                &mut lines,
+                "Role",
+                options.limit_items(vcard.role.iter().map(|r| r.value.clone())),
+            ),
+            Section::Address => list_section(
+                &mut lines,
+                "Address",
+                options.limit_items(vcard.address.iter().map(render_address)),
+            ),
+            Section::Related => list_section(
+                &mut lines,
+                "Related",
+                options.limit_items(vcard.related.iter().map(|r| {
+                    let value = r.to_string();
+                    let related = resolve(&value).unwrap_or_else(|| {
+                        value.strip_prefix("mailto:").unwrap_or(&value).to_owned()
+                    });
+                    format!("{}: {related}", relation_label(r))
+                })),
+            ),
+            Section::Birthday => list_section(
+                &mut lines,
+                "Birthday",
+                options.limit_items(vcard.bday.iter().map(|b| {
+                    let bday = b.to_string();
+                    Birthday::parse(&bday).map_or(bday, |b| b.describe(Local::now().date_naive()))
+                })),
+            ),
+            Section::Url => list_section(
+                &mut lines,
+                "URL",
+                options.limit_items(
+                    vcard
+                        .url
+                        .iter()
+                        .map(|u| typed_value(u, &u.value.to_string())),
+                ),
+            ),
+            Section::Impp => list_section(
+                &mut lines,
+                "IM",
+                options.limit_items(
+                    vcard
+                        .impp
+                        .iter()
+                        .map(|i| typed_value(i, &i.value.to_string())),
+                ),
+            ),
+            Section::Categories => {
+                let categories = categories(vcard).join(", ");
+                if !categories.is_empty() {
+                    lines.push(format!("Categories: {categories}"));
+                    lines.push(String::new());
+                }
+            }
+            Section::Extensions => {
+                for extension in vcard
+                    .extensions
+                    .iter()
+                    .filter(|e| options.renders_extension(&e.name))
+                {
+                    lines.push(format!(
+                        "{}: {}",
+                        extension_label(&extension.name),
+                        extension.value
+                    ));
+                    lines.push(String::new());
+                }
+            }
+            Section::Note => {
+                for note in &vcard.note {
+                    lines.push(options.limit_note(&note.value));
+                    lines.push(String::new());
+                }
+            }
+        }
     }
     lines.join("\n")
 }
 
-fn match_vcard(vc: &Vcard, word: &str) -> bool {
-    let matched_email = vc
-        .email
-        .iter()
-        .any(|e| e.value.to_lowercase().contains(word));
-    let matched_fn = vc
-        .formatted_name
+fn categories(vcard: &Vcard) -> impl Iterator<Item = &String> {
+    vcard
+        .categories
         .iter()
-        .any(|n| n.value.to_lowercase().contains(word));
-    let matched_nick = vc
-        .nickname
+        .flat_map(|c| &c.value)
+        .filter(|c| !c.is_empty())
+}
+
+/// Prefix the value with the first `TYPE` of the property, if any, e.g. `work: jane@work.com`.
+fn typed_value(property: &impl Property, value: &str) -> String {
+    match first_type(property) {
+        Some(typ) => format!("{typ}: {value}"),
+        None => value.to_owned(),
+    }
+}
+
+/// How the contact of a `RELATED` property is related, from its first `TYPE`, e.g. `Spouse`.
+fn relation_label(property: &impl Property) -> String {
+    let Some(typ) = first_type(property) else {
+        return "Related".to_owned();
+    };
+    let mut chars = typ.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or(typ)
+}
+
+/// The first `TYPE` of the property, if any.
+fn first_type(property: &impl Property) -> Option<String> {
+    property
+        .parameters()
+        .and_then(|p| p.types.as_ref().and_then(|types| types.first()))
+        .map(|typ| typ.to_string())
+}
+
+/// Render a postal address on one line, as its `LABEL` if it has one, with its type and a link to
+/// it on a map.
+fn render_address(address: &AddressProperty) -> String {
+    let formatted = format_address(&address.value);
+    let label = address
+        .parameters
+        .as_ref()
+        .and_then(|p| p.label.as_deref())
+        .map(|label| {
+            label
+                .replace("\\n", "\n")
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .join(", ")
+        })
+        .filter(|label| !label.is_empty());
+    let rendered = typed_value(address, label.as_deref().unwrap_or(&formatted));
+    match map_url(&formatted) {
+        Some(url) => format!("{rendered} ([map]({url}))"),
+        None => rendered,
+    }
+}
+
+/// A link to search for the address on OpenStreetMap.
+fn map_url(address: &str) -> Option<Url> {
+    if address.is_empty() {
+        return None;
+    }
+    Url::parse_with_params(
+        "https://www.openstreetmap.org/search",
+        &[("query", address)],
+    )
+    .ok()
+}
+
+/// Format a postal address on one line, skipping empty components.
+fn format_address(address: &DeliveryAddress) -> String {
+    address_lines(address).join(", ")
+}
+
+/// The lines of a postal address as it goes on an envelope, skipping empty components.
+fn address_lines(address: &DeliveryAddress) -> Vec<String> {
+    let locality = [&address.locality, &address.region, &address.postal_code]
+        .into_iter()
+        .flatten()
+        .filter(|p| !p.is_empty())
+        .join(" ");
+    [
+        address.po_box.clone(),
+        address.extended_address.clone(),
+        address.street_address.clone(),
+        Some(locality),
+        address.country_name.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|p| !p.is_empty())
+    .collect()
+}
+
+fn is_group(vcard: &Vcard) -> bool {
+    matches!(vcard.kind.as_ref().map(|k| &k.value), Some(Kind::Group))
+}
+
+/// Normalize a `UID` for lookups, as `MEMBER` references may differ in case or omit `urn:uuid:`.
+fn normalize_uid(uid: &str) -> String {
+    let uid = uid.trim().to_lowercase();
+    uid.strip_prefix("urn:uuid:").unwrap_or(&uid).to_owned()
+}
+
+/// The names and emails of a card, folded when it is loaded.
+struct FoldedCard {
+    /// Formatted names then nicknames.
+    names: Vec<Folded>,
+    formatted_names: usize,
+    emails: Vec<Folded>,
+    /// Whether the category filter allows the card.
+    allowed: bool,
+}
+
+impl FoldedCard {
+    fn new(vc: &Vcard, category_filter: &CategoryFilter) -> Self {
+        Self {
+            names: vc
+                .formatted_name
+                .iter()
+                .chain(&vc.nickname)
+                .map(|n| Folded::new(&n.value))
+                .collect(),
+            formatted_names: vc.formatted_name.len(),
+            emails: vc.email.iter().map(|e| Folded::new(&e.value)).collect(),
+            allowed: category_filter.allows(vc),
+        }
+    }
+
+    fn formatted_names(&self) -> &[Folded] {
+        &self.names[..self.formatted_names]
+    }
+
+    fn matches(&self, matcher: &Matcher) -> bool {
+        matcher.matches_folded(&self.names, &self.emails)
+    }
+}
+
+/// The emails of the vcard, ordered by their `PREF` parameter.
+fn preferred_emails(vcard: &Vcard) -> Vec<String> {
+    vcard
+        .email
         .iter()
-        .any(|n| n.value.to_lowercase().contains(word));
-    matched_email || matched_fn || matched_nick
+        .sorted_by_key(|e| e.parameters().and_then(|p| p.pref).unwrap_or(u8::MAX))
+        .map(|e| e.value.clone())
+        .collect()
 }
 
 fn mailboxes_for_vcard(vcard: &Vcard) -> Vec<Mailbox> {
@@ -221,3 +1602,176 @@ fn mailboxes_for_vcard(vcard: &Vcard) -> Vec<Mailbox> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    #[test]
+    fn reload_changed_files() {
+        let dir = TestDir::new();
+        let root = dir.to_path_buf();
+        let path = root.join("jane.vcf");
+        let card = "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Jane Doe\r\nEND:VCARD\r\n";
+        std::fs::write(&path, card).unwrap();
+        let mut vcards = VCards::new(root.clone());
+        assert!(vcards.stamps.contains_key(&path));
+
+        // unchanged files aren't read again
+        assert!(!vcards.reload(std::slice::from_ref(&path)));
+        assert!(!vcards.reload(std::slice::from_ref(&root)));
+
+        std::fs::write(&path, card.replace("Jane", "Janet")).unwrap();
+        assert!(vcards.reload(std::slice::from_ref(&path)));
+        assert!(!vcards.reload(std::slice::from_ref(&path)));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(vcards.reload(std::slice::from_ref(&root)));
+        assert!(vcards.stamps.is_empty());
+    }
+
+    #[test]
+    fn conflicts_on_reload() {
+        let dir = TestDir::new();
+        let card = |uid: &str| {
+            format!("BEGIN:VCARD\r\nVERSION:4.0\r\nUID:{uid}\r\nFN:Jane Doe\r\nEND:VCARD\r\n")
+        };
+        let (a, b) = (dir.join("a.vcf"), dir.join("b.vcf"));
+        std::fs::write(&a, card("1")).unwrap();
+        std::fs::write(&b, card("1")).unwrap();
+        let mut vcards = VCards::new(dir.to_path_buf());
+        assert_eq!(vcards.conflicts().len(), 1);
+
+        // only the files that changed are read again, which change size as well so their
+        // stamps do
+        std::fs::write(&b, card("22")).unwrap();
+        std::fs::write(&a, card("333")).unwrap();
+        assert!(vcards.reload(std::slice::from_ref(&a)));
+        assert_eq!(vcards.conflicts().len(), 0);
+        std::fs::write(&a, card("22")).unwrap();
+        assert!(vcards.reload(std::slice::from_ref(&a)));
+        assert_eq!(vcards.conflicts().len(), 0);
+        assert!(vcards.reload(std::slice::from_ref(&b)));
+        assert_eq!(vcards.conflicts().len(), 1);
+
+        std::fs::remove_file(&b).unwrap();
+        assert!(vcards.reload(std::slice::from_ref(&b)));
+        assert!(vcards.conflicts().is_empty());
+    }
+
+    #[test]
+    fn merge_conflict() {
+        let dir = TestDir::new();
+        let root = dir.to_path_buf();
+        let card = |tel: &str| {
+            format!(
+                "BEGIN:VCARD\r\nVERSION:4.0\r\nUID:1\r\nFN:Jane Doe\r\nTEL:{tel}\r\nEND:VCARD\r\n"
+            )
+        };
+        std::fs::write(root.join("a.vcf"), card("1234")).unwrap();
+        std::fs::write(root.join("b.vcf"), card("5678")).unwrap();
+        let mut vcards = VCards::new(root.clone());
+        let conflicts = vcards.conflicts();
+        assert_eq!(conflicts.len(), 1);
+
+        let location = vcards.merge(&conflicts[0], &MergeInto::First).unwrap();
+        assert_eq!(location.path, root.join("a.vcf"));
+        assert!(!root.join("b.vcf").exists());
+        let merged = std::fs::read_to_string(root.join("a.vcf")).unwrap();
+        assert!(merged.contains("TEL:1234\r\n") && merged.contains("TEL:5678\r\n"));
+        assert_eq!(merged.matches("UID:").count(), 1);
+        assert!(vcards.conflicts().is_empty());
+        // it is gone now
+        assert!(vcards.merge(&conflicts[0], &MergeInto::First).is_none());
+    }
+
+    /// Cards of Jane Doe at the paths under the directory, each with its `REV` if it has one,
+    /// merged as `into` picks, returning the file of the merged card.
+    fn merge_into(dir: &Path, revs: &[(&str, Option<&str>)], into: &MergeInto) -> PathBuf {
+        for (path, rev) in revs {
+            let rev = rev.map_or_else(String::new, |rev| format!("REV:{rev}\r\n"));
+            std::fs::write(
+                dir.join(path),
+                format!("BEGIN:VCARD\r\nVERSION:4.0\r\nUID:1\r\nFN:Jane Doe\r\n{rev}END:VCARD\r\n"),
+            )
+            .unwrap();
+        }
+        let mut vcards = VCards::new(dir.to_path_buf()).with_writable(true);
+        let conflicts = vcards.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        let location = vcards.merge(&conflicts[0], into).unwrap();
+        assert!(vcards.conflicts().is_empty());
+        location.path.strip_prefix(dir).unwrap().to_path_buf()
+    }
+
+    #[test]
+    fn merge_strategies() {
+        let newest = [
+            ("a.vcf", Some("20200101T000000Z")),
+            ("b.vcf", Some("2024-01-01T00:00:00Z")),
+            ("c.vcf", None),
+        ];
+        let dir = TestDir::new();
+        assert_eq!(
+            merge_into(&dir, &newest, &MergeInto::Newest),
+            Path::new("b.vcf")
+        );
+
+        // the first of those revised at the same time
+        let tied = [
+            ("a.vcf", None),
+            ("b.vcf", Some("20240101T000000Z")),
+            ("c.vcf", Some("20240101T000000Z")),
+        ];
+        let dir = TestDir::new();
+        assert_eq!(
+            merge_into(&dir, &tied, &MergeInto::Newest),
+            Path::new("b.vcf")
+        );
+
+        let dir = TestDir::new();
+        assert_eq!(
+            merge_into(&dir, &newest, &MergeInto::Under(dir.join("c.vcf"))),
+            Path::new("c.vcf")
+        );
+        // the first when none are under the path
+        let dir = TestDir::new();
+        assert_eq!(
+            merge_into(&dir, &newest, &MergeInto::Under(dir.join("work"))),
+            Path::new("a.vcf")
+        );
+
+        let dir = TestDir::new();
+        assert_eq!(
+            merge_into(&dir, &newest, &MergeInto::Location(2)),
+            Path::new("c.vcf")
+        );
+    }
+
+    #[test]
+    fn postal_address() {
+        let address = DeliveryAddress {
+            po_box: None,
+            extended_address: Some(String::new()),
+            street_address: Some("1 High Street".to_owned()),
+            locality: Some("Cambridge".to_owned()),
+            region: None,
+            postal_code: Some("CB1 1AA".to_owned()),
+            country_name: Some("United Kingdom".to_owned()),
+        };
+        assert_eq!(
+            address_lines(&address),
+            vec!["1 High Street", "Cambridge CB1 1AA", "United Kingdom"]
+        );
+        assert_eq!(
+            format_address(&address),
+            "1 High Street, Cambridge CB1 1AA, United Kingdom"
+        );
+        assert_eq!(
+            map_url(&format_address(&address)).unwrap().as_str(),
+            "https://www.openstreetmap.org/search?query=1+High+Street%2C+Cambridge+CB1+1AA%2C+United+Kingdom"
+        );
+        assert_eq!(map_url(""), None);
+    }
+}
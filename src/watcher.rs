@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use crossbeam_channel::Receiver;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+/// Watches source files for changes made outside of the editor, e.g. by sync tools.
+pub struct Watcher {
+    // dropping the watcher stops it
+    watcher: RecommendedWatcher,
+    /// Receives the paths that changed.
+    pub receiver: Receiver<Vec<PathBuf>>,
+}
+
+impl Watcher {
+    /// Watch the given files and directories.
+    ///
+    /// Files are watched through their directory so that they are still watched after being
+    /// replaced, as editors and sync tools tend to do.
+    pub fn new(paths: &[PathBuf]) -> notify::Result<Self> {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                let _ = sender.send(event.paths);
+            }
+        })?;
+        let mut s = Self { watcher, receiver };
+        s.watch(paths)?;
+        Ok(s)
+    }
+
+    /// Watch more files and directories, e.g. of a source that just loaded.
+    pub fn watch(&mut self, paths: &[PathBuf]) -> notify::Result<()> {
+        for path in paths {
+            let dir = if path.is_dir() {
+                path.as_path()
+            } else {
+                match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => continue,
+                }
+            };
+            self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+        Ok(())
+    }
+}
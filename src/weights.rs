@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, read_to_string, rename, write},
+    io,
+    path::Path,
+};
+
+use chrono::NaiveDate;
+use itertools::Itertools as _;
+
+use crate::{fold, Usage};
+
+/// How much mail there is with each address, from outside of the sources, to rank completions
+/// by.
+///
+/// They can be kept in a cache file tagged with the state of what they were computed from, with a
+/// line for each address of `email<TAB>messages<TAB>last`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddressWeights {
+    entries: HashMap<String, Usage>,
+}
+
+impl AddressWeights {
+    /// Add the usage of the address, combined with any already known.
+    pub fn insert(&mut self, email: &str, usage: Usage) {
+        let entry = self.entries.entry(fold(email, false)).or_default();
+        *entry = entry.combine(usage);
+    }
+
+    pub fn get(&self, email: &str) -> Option<Usage> {
+        self.entries.get(&fold(email, false)).copied()
+    }
+
+    /// See [`Usage::frecency`], 0 for unknown addresses.
+    pub fn frecency(&self, email: &str, today: NaiveDate) -> u64 {
+        self.get(email).map_or(0, |u| u.frecency(today))
+    }
+
+    /// Whether all of the addresses have weights, even if they have no messages.
+    pub fn covers<'a>(&self, emails: impl IntoIterator<Item = &'a str>) -> bool {
+        emails
+            .into_iter()
+            .all(|e| self.entries.contains_key(&fold(e, false)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Read the weights cached at the path, if they were cached with the same tag.
+    pub fn load(path: &Path, tag: &str) -> Option<Self> {
+        let text = read_to_string(path).ok()?;
+        let mut lines = text.lines();
+        if lines.next()? != tag {
+            return None;
+        }
+        let mut weights = Self::default();
+        for line in lines {
+            let mut fields = line.split('\t');
+            let Some(email) = fields.next().filter(|e| !e.is_empty()) else {
+                continue;
+            };
+            let messages = fields.next().and_then(|f| f.parse().ok());
+            let last = fields
+                .next()
+                .and_then(|f| NaiveDate::parse_from_str(f, "%Y-%m-%d").ok());
+            weights.insert(email, Usage { last, messages });
+        }
+        Some(weights)
+    }
+
+    /// Cache the weights at the path, tagged so a stale cache isn't loaded.
+    pub fn save(&self, path: &Path, tag: &str) -> io::Result<()> {
+        let lines = self
+            .entries
+            .iter()
+            .map(|(email, usage)| {
+                format!(
+                    "{email}\t{}\t{}",
+                    usage.messages.unwrap_or(0),
+                    usage
+                        .last
+                        .map(|d| d.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default()
+                )
+            })
+            .sorted()
+            .join("\n");
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("partial");
+        write(&partial, format!("{tag}\n{lines}\n"))?;
+        rename(partial, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestDir;
+
+    #[test]
+    fn cached() {
+        let dir = TestDir::new();
+        let path = dir.join("weights");
+        let mut weights = AddressWeights::default();
+        let last = NaiveDate::from_ymd_opt(2024, 1, 1);
+        weights.insert(
+            "Jane@Example.com",
+            Usage {
+                last,
+                messages: Some(3),
+            },
+        );
+        weights.insert(
+            "bob@example.com",
+            Usage {
+                last: None,
+                messages: Some(0),
+            },
+        );
+        assert!(weights.covers(["jane@example.com", "bob@example.com"]));
+        assert!(!weights.covers(["jo@example.com"]));
+        weights.save(&path, "rev 1").unwrap();
+
+        assert_eq!(AddressWeights::load(&path, "rev 2"), None);
+        let loaded = AddressWeights::load(&path, "rev 1").unwrap();
+        assert_eq!(loaded, weights);
+        assert_eq!(loaded.frecency("jane@example.com", last.unwrap()), 3000);
+        assert_eq!(loaded.frecency("bob@example.com", last.unwrap()), 0);
+    }
+}